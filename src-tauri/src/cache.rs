@@ -0,0 +1,116 @@
+//! On-disk cache of parse results, keyed by a hash of the project's input files
+
+use crate::parsers::dbt_project::{target_dir, CATALOG_FILE_NAME};
+use crate::parsers::semantic_manifest::SEMANTIC_MANIFEST_FILE_NAME;
+use crate::parsers::DbtProjectParser;
+use crate::types::{ParseResult, ProjectConfig};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Cache file written to the root of the dbt project
+pub const CACHE_FILE_NAME: &str = ".semantic-tracer-cache.json";
+
+/// Bumped whenever the cache entry layout changes; cached results are also
+/// tied to the app version, since a newer parser may produce different ones
+const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// Directories holding build output or vendored code rather than project inputs
+const IGNORED_DIRS: &[&str] = &[".git", "target", "dbt_packages", "logs", "node_modules"];
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    #[serde(default)]
+    version: String,
+    hash: String,
+    result: ParseResult,
+}
+
+/// Hash the cache version and parse configuration together with the path and
+/// contents of every input file (the dbt project, the semantic layer directory
+/// and profiles.yml), plus the catalog and semantic manifest in the project's
+/// target directory, which is otherwise ignored. The parsed `dbt_project.yml`
+/// is hashed too, so a change to an `env_var()` it reads invalidates the cache.
+///
+/// The hash is only compared against caches written by the same version, so
+/// the unstable `DefaultHasher` algorithm is fine here.
+pub fn project_hash(config: &ProjectConfig) -> Result<String> {
+    let mut hasher = DefaultHasher::new();
+    cache_version().hash(&mut hasher);
+    serde_json::to_string(config)?.hash(&mut hasher);
+
+    let project_dir = Path::new(&config.dbt_project_path);
+    let project = DbtProjectParser::new(project_dir).parse_project().ok();
+    serde_json::to_string(&project)?.hash(&mut hasher);
+    let target_dir = target_dir(
+        project_dir,
+        project.as_ref().and_then(|p| p.target_path.as_deref()),
+    );
+
+    let mut roots = vec![config.dbt_project_path.as_str()];
+    roots.extend(config.semantic_layer_path.as_deref());
+    roots.extend(config.profiles_path.as_deref());
+
+    for root in roots {
+        let root = Path::new(root);
+        if !root.exists() {
+            anyhow::bail!("Path does not exist: {:?}", root);
+        }
+
+        let mut files: Vec<_> = WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|e| {
+                let name = e.file_name().to_string_lossy();
+                !(e.file_type().is_dir()
+                    && (IGNORED_DIRS.contains(&name.as_ref()) || e.path() == target_dir))
+            })
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && e.file_name() != CACHE_FILE_NAME)
+            .map(|e| e.into_path())
+            .collect();
+        files.sort();
+
+        for file in files {
+            file.hash(&mut hasher);
+            fs::read(&file)
+                .with_context(|| format!("Failed to read {:?}", file))?
+                .hash(&mut hasher);
+        }
+    }
+
+    for target_file in [CATALOG_FILE_NAME, SEMANTIC_MANIFEST_FILE_NAME] {
+        if let Ok(content) = fs::read(target_dir.join(target_file)) {
+            content.hash(&mut hasher);
+        }
+    }
+
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+/// Load the cached result for `project_path` if it was written for `hash`
+pub fn load(project_path: &Path, hash: &str) -> Option<ParseResult> {
+    let content = fs::read_to_string(project_path.join(CACHE_FILE_NAME)).ok()?;
+    let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+    (entry.version == cache_version() && entry.hash == hash).then_some(entry.result)
+}
+
+/// Write `result` to the cache file in `project_path`, replacing any previous entry
+pub fn store(project_path: &Path, hash: &str, result: &ParseResult) -> Result<()> {
+    let entry = CacheEntry {
+        version: cache_version(),
+        hash: hash.to_string(),
+        result: result.clone(),
+    };
+    let cache_file = project_path.join(CACHE_FILE_NAME);
+    fs::write(&cache_file, serde_json::to_string(&entry)?)
+        .with_context(|| format!("Failed to write cache file {:?}", cache_file))
+}
+
+/// Cache format and app version a cache entry was written by
+fn cache_version() -> String {
+    format!("{}-{}", CACHE_FORMAT_VERSION, env!("CARGO_PKG_VERSION"))
+}
@@ -1,26 +1,147 @@
 //! Tauri IPC commands for the Semantic Layer Metrics Lineage Tracer
 
-use crate::lineage::{LineageAnalyzer, LineageBuilder};
-use crate::parsers::{DbtProjectParser, DbtSemanticLayerParser};
-use crate::types::{ParseResult, ProjectConfig, SemanticLayerType};
+use crate::cache;
+use crate::error::CommandError;
+use crate::lineage::{diff, export, LineageAnalyzer, LineageBuilder};
+use crate::parsers::dbt_project::{read_sql, resolve_relations, CATALOG_FILE_NAME};
+use crate::parsers::semantic_manifest::SEMANTIC_MANIFEST_FILE_NAME;
+use crate::parsers::{
+    DbtProjectParser, DbtSemanticLayerParser, LookmlParser, SemanticManifestParser,
+};
+use crate::paths;
+use crate::types::{
+    AuditIssue, AuditResult, BlastRadius, ConsolidationHint, DbtModel, ExportFormat,
+    GraphEndpoints, GraphStats, IssueSeverity, LineageDiff, LineageEdgeType, LineageGraph,
+    LineageNode, LineageNodeType, MeasureColumn, MeasureUsage, MetricCompleteness, MetricTreeNode,
+    ParseProgress, ParseResult, ProjectConfig, Scope, SemanticLayerType, SourceUtilization,
+    ValidationReport,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::Path;
+use tauri::Emitter;
+
+/// Emit a `parse-progress` event, logging rather than failing if the window is gone
+fn emit_progress(window: &tauri::Window, phase: &str, count: usize) {
+    let payload = ParseProgress {
+        phase: phase.to_string(),
+        count,
+    };
+    if let Err(e) = window.emit("parse-progress", payload) {
+        log::warn!("Failed to emit parse progress: {}", e);
+    }
+}
 
 /// Load and parse a dbt project with its semantic layer
+///
+/// Emits `parse-progress` events on `window` as work proceeds. The payload is
+/// `{ "phase": string, "count": number }`, where `phase` is one of `models`
+/// (sent after each model path with the running model count), `sources`,
+/// `semantic_layer` (count of metrics), `lineage` (count of nodes) or `audit`
+/// (count of issues).
+///
+/// With `config.use_cache` set, the result is cached in the project directory
+/// and reused (with `from_cache` set and no progress events) until any input
+/// file changes.
+///
+/// With `config.fail_on` set, audit issues at or above that severity add an
+/// error and clear `success`, so the result can gate CI.
+///
+/// Relative and `~`-prefixed paths in `config` are resolved first.
 #[tauri::command]
-pub async fn parse_project(config: ProjectConfig) -> Result<ParseResult, String> {
+pub async fn parse_project(
+    window: tauri::Window,
+    config: ProjectConfig,
+) -> Result<ParseResult, CommandError> {
+    let config = resolve_config_paths(&config)?;
+    parse_project_cached(&config, |phase, count| emit_progress(&window, phase, count))
+}
+
+/// Replace the configured paths with canonical absolute ones, so parsing does
+/// not depend on the working directory the app was launched from
+fn resolve_config_paths(config: &ProjectConfig) -> Result<ProjectConfig, CommandError> {
+    let resolve = |raw: &str| {
+        paths::resolve_path(raw)
+            .map(|p| paths::normalize_path(&p))
+            .map_err(|e| CommandError::PathNotFound(format!("{:#}", e)))
+    };
+
+    Ok(ProjectConfig {
+        dbt_project_path: resolve(&config.dbt_project_path)?,
+        semantic_layer_path: config
+            .semantic_layer_path
+            .as_deref()
+            .map(resolve)
+            .transpose()?,
+        profiles_path: config.profiles_path.as_deref().map(resolve).transpose()?,
+        ..config.clone()
+    })
+}
+
+/// Serve a parse from the cache when nothing changed, otherwise parse and refresh the cache
+fn parse_project_cached(
+    config: &ProjectConfig,
+    progress: impl Fn(&str, usize),
+) -> Result<ParseResult, CommandError> {
+    if !config.use_cache {
+        return parse_project_files(config, progress);
+    }
+
+    let project_path = Path::new(&config.dbt_project_path);
+    let hash = match cache::project_hash(config) {
+        Ok(hash) => Some(hash),
+        Err(e) => {
+            log::warn!("Failed to hash project inputs, skipping cache: {}", e);
+            None
+        }
+    };
+
+    if let Some(ref hash) = hash {
+        if let Some(mut cached) = cache::load(project_path, hash) {
+            log::info!("Loaded parse result from cache");
+            cached.from_cache = true;
+            return Ok(cached);
+        }
+    }
+
+    let result = parse_project_files(config, progress)?;
+    if let Some(ref hash) = hash {
+        if let Err(e) = cache::store(project_path, hash, &result) {
+            log::warn!("Failed to write parse cache: {}", e);
+        }
+    }
+    Ok(result)
+}
+
+/// Parse every input of the project, reporting `(phase, count)` through `progress`
+fn parse_project_files(
+    config: &ProjectConfig,
+    progress: impl Fn(&str, usize),
+) -> Result<ParseResult, CommandError> {
     let mut result = ParseResult::default();
 
     // Validate project path exists
     let project_path = Path::new(&config.dbt_project_path);
     if !project_path.exists() {
-        return Err(format!("Project path does not exist: {}", config.dbt_project_path));
+        return Err(CommandError::PathNotFound(format!(
+            "Project path does not exist: {}",
+            config.dbt_project_path
+        )));
     }
 
     // Parse dbt project
-    let dbt_parser = DbtProjectParser::new(&config.dbt_project_path);
+    let dbt_parser = DbtProjectParser::new(&config.dbt_project_path)
+        .with_ref_macros(config.ref_macros.clone().unwrap_or_default())
+        .with_source_macros(config.source_macros.clone().unwrap_or_default())
+        .with_sql_extensions(config.sql_extensions.clone().unwrap_or_default());
 
     let project = match dbt_parser.parse_project() {
         Ok(p) => {
+            for name in dbt_parser.take_unset_env_vars() {
+                result.warnings.push(format!(
+                    "Environment variable '{}' used in dbt_project.yml is not set and has no default",
+                    name
+                ));
+            }
             result.dbt_project = Some(p.clone());
             p
         }
@@ -31,7 +152,7 @@ pub async fn parse_project(config: ProjectConfig) -> Result<ParseResult, String>
     };
 
     // Parse models
-    match dbt_parser.parse_models(&project) {
+    match dbt_parser.parse_models_with_progress(&project, |count| progress("models", count)) {
         Ok(models) => {
             log::info!("Parsed {} models", models.len());
             result.models = models;
@@ -51,23 +172,130 @@ pub async fn parse_project(config: ProjectConfig) -> Result<ParseResult, String>
             result.warnings.push(format!("Failed to parse some sources: {}", e));
         }
     }
+    progress("sources", result.sources.len());
+
+    // Parse snapshots
+    match dbt_parser.parse_snapshots(&project) {
+        Ok(snapshots) => {
+            log::info!("Parsed {} snapshots", snapshots.len());
+            result.snapshots = snapshots;
+        }
+        Err(e) => {
+            result.warnings.push(format!("Failed to parse some snapshots: {}", e));
+        }
+    }
+
+    // Parse seeds
+    match dbt_parser.parse_seeds(&project) {
+        Ok(seeds) => {
+            log::info!("Parsed {} seeds", seeds.len());
+            result.seeds = seeds;
+        }
+        Err(e) => {
+            result.warnings.push(format!("Failed to parse some seeds: {}", e));
+        }
+    }
+
+    // Parse data tests, only needed when they go into the graph
+    if config.include_tests {
+        match dbt_parser.parse_tests(&project) {
+            Ok(tests) => {
+                log::info!("Parsed {} tests", tests.len());
+                result.tests = tests;
+            }
+            Err(e) => {
+                result
+                    .warnings
+                    .push(format!("Failed to parse some tests: {}", e));
+            }
+        }
+    }
+
+    // Report files that were skipped rather than silently dropping them
+    result.warnings.extend(dbt_parser.take_read_failures());
+
+    // Fill column data types from catalog.json, when dbt docs have been generated
+    match dbt_parser.merge_catalog(&project, &mut result.models, &mut result.sources) {
+        Ok(true) => log::info!("Merged column types from {}", CATALOG_FILE_NAME),
+        Ok(false) => {}
+        Err(e) => {
+            result
+                .warnings
+                .push(format!("Failed to read {}: {}", CATALOG_FILE_NAME, e));
+        }
+    }
+
+    // Qualify relation names from the active profile target
+    if let Some(ref profiles_path) = config.profiles_path {
+        match dbt_parser.resolve_target(&project, profiles_path) {
+            Ok(Some(target)) => {
+                dbt_parser.qualify_relations(&target, &mut result.models, &mut result.sources);
+            }
+            Ok(None) => {
+                result.warnings.push(format!(
+                    "No target for profile '{}' found in profiles.yml",
+                    project.profile.as_deref().unwrap_or("")
+                ));
+            }
+            Err(e) => {
+                result.warnings.push(format!("Failed to read profiles.yml: {}", e));
+            }
+        }
+    }
 
     // Parse semantic layer based on type
     match config.semantic_layer_type {
         SemanticLayerType::DbtSemanticLayer => {
-            let semantic_parser = DbtSemanticLayerParser::new(&config.dbt_project_path);
-            match semantic_parser.parse() {
-                Ok((semantic_models, metrics)) => {
+            // dbt's compiled manifest has refs and defaults already resolved
+            let manifest = if config.prefer_manifest {
+                match SemanticManifestParser::new(&config.dbt_project_path)
+                    .parse(&project, &result.models)
+                {
+                    Ok(manifest) => manifest,
+                    Err(e) => {
+                        result.warnings.push(format!(
+                            "Failed to read {}, falling back to YAML: {}",
+                            SEMANTIC_MANIFEST_FILE_NAME, e
+                        ));
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            match manifest {
+                Some((semantic_models, metrics)) => {
                     log::info!(
-                        "Parsed {} semantic models and {} metrics",
+                        "Read {} semantic models and {} metrics from {}",
                         semantic_models.len(),
-                        metrics.len()
+                        metrics.len(),
+                        SEMANTIC_MANIFEST_FILE_NAME
                     );
                     result.semantic_models = semantic_models;
                     result.metrics = metrics;
                 }
-                Err(e) => {
-                    result.warnings.push(format!("Failed to parse semantic layer: {}", e));
+                None => {
+                    let semantic_parser = DbtSemanticLayerParser::new(&config.dbt_project_path)
+                        .with_strict(config.strict);
+                    match semantic_parser.parse() {
+                        Ok((semantic_models, metrics, errors)) => {
+                            log::info!(
+                                "Parsed {} semantic models and {} metrics",
+                                semantic_models.len(),
+                                metrics.len()
+                            );
+                            result.semantic_models = semantic_models;
+                            result.metrics = metrics;
+                            result.errors.extend(errors);
+                            result.warnings.extend(semantic_parser.take_read_failures());
+                        }
+                        Err(e) => {
+                            result
+                                .warnings
+                                .push(format!("Failed to parse semantic layer: {}", e));
+                        }
+                    }
                 }
             }
         }
@@ -86,6 +314,9 @@ pub async fn parse_project(config: ProjectConfig) -> Result<ParseResult, String>
                         result.warnings.push(
                             "Snowflake semantic layer parsing is basic - full support coming soon".to_string()
                         );
+                        result
+                            .warnings
+                            .extend(snowflake_parser.validate_table_refs(&snowflake_layer));
                     }
                     Err(e) => {
                         result.warnings.push(format!("Failed to parse Snowflake semantic layer: {}", e));
@@ -95,27 +326,72 @@ pub async fn parse_project(config: ProjectConfig) -> Result<ParseResult, String>
                 result.warnings.push("Snowflake semantic layer path not provided".to_string());
             }
         }
+        SemanticLayerType::Looker => {
+            if let Some(ref lookml_path) = config.semantic_layer_path {
+                match LookmlParser::new().parse(lookml_path) {
+                    Ok((views, explores)) => {
+                        log::info!(
+                            "Parsed LookML project: {} views, {} explores",
+                            views.len(),
+                            explores.len()
+                        );
+                        result.lookml_views = views;
+                        result.lookml_explores = explores;
+                    }
+                    Err(e) => {
+                        result.warnings.push(format!("Failed to parse LookML project: {}", e));
+                    }
+                }
+            } else {
+                result.warnings.push("LookML project path not provided".to_string());
+            }
+        }
         SemanticLayerType::None => {
             log::info!("No semantic layer type specified, skipping semantic layer parsing");
         }
     }
+    progress("semantic_layer", result.metrics.len());
 
     // Build lineage graph
-    let lineage_builder = LineageBuilder::new();
-    result.lineage = lineage_builder.build(
+    let lineage_builder = LineageBuilder::new()
+        .with_pruning(config.prune_unreferenced)
+        .with_deterministic_ids(config.deterministic_ids)
+        .with_snapshots(result.snapshots.clone())
+        .with_seeds(result.seeds.clone())
+        .with_tests(result.tests.clone())
+        .with_lookml_views(result.lookml_views.clone());
+    let (lineage, diagnostics) = lineage_builder.build_with_diagnostics(
         &result.models,
         &result.sources,
         &result.semantic_models,
         &result.metrics,
     );
+    result.lineage = lineage;
     log::info!(
         "Built lineage graph with {} nodes and {} edges",
         result.lineage.nodes.len(),
         result.lineage.edges.len()
     );
 
+    // Scope once the whole graph is built, so scoped models keep their
+    // upstream models even when those are outside the scope
+    let mut diagnostics = diagnostics;
+    if let Some(ref scope) = config.scope {
+        apply_scope(scope, project_path, &mut result);
+        let node_ids: HashSet<&str> =
+            result.lineage.nodes.iter().map(|n| n.id.as_str()).collect();
+        diagnostics.retain(|d| d.node_id.as_deref().map_or(true, |id| node_ids.contains(id)));
+        log::info!("Scoped parse to {} models", result.models.len());
+    }
+    progress("lineage", result.lineage.nodes.len());
+
     // Run audit analysis
-    let analyzer = LineageAnalyzer::new();
+    let analyzer = LineageAnalyzer::new()
+        .with_required_owner(config.require_metric_owner)
+        .with_allowed_aggs(config.allowed_aggs.clone())
+        .with_diagnostics(diagnostics)
+        .with_completeness_policy(config.completeness_policy.clone())
+        .with_severity_overrides(config.severity_overrides.clone().unwrap_or_default());
     result.audit = analyzer.analyze(
         &result.lineage,
         &result.models,
@@ -128,63 +404,628 @@ pub async fn parse_project(config: ProjectConfig) -> Result<ParseResult, String>
         result.audit.completeness_score,
         result.audit.issues.len()
     );
+    progress("audit", result.audit.issues.len());
+
+    // The graph and audit are done with the SQL; drop it so the result sent
+    // to the frontend (and cached) doesn't carry the text of every model
+    if !config.keep_raw_sql {
+        for model in &mut result.models {
+            model.raw_sql = None;
+        }
+        for snapshot in &mut result.snapshots {
+            snapshot.raw_sql = None;
+        }
+    }
+
+    // Gate on audit issues at or above the configured severity, for CI
+    if let Some(ref threshold) = config.fail_on {
+        let failing = result
+            .audit
+            .issues
+            .iter()
+            .filter(|i| i.severity <= *threshold)
+            .count();
+        if failing > 0 {
+            result.errors.push(format!(
+                "{} audit issue(s) at or above {:?} severity",
+                failing, threshold
+            ));
+        }
+    }
 
     result.success = result.errors.is_empty();
     Ok(result)
 }
 
+/// Parse several dbt projects in parallel and merge them into one result
+///
+/// Node and edge ids are prefixed with the project's index in `configs`
+/// (`0::<id>`), so graphs never collide even with deterministic ids or two
+/// checkouts of the same project, and every node gets a `project` metadata
+/// entry with the project name. Nodes with the same
+/// type and name in different projects stay distinct unless `merge_by_name`
+/// is set, in which case they collapse into the first project's node.
+///
+/// Entities and audit issues are concatenated, with models, sources,
+/// semantic models and metrics tagged with their project in `project`. Errors
+/// and warnings are prefixed with the project name, summary counts are summed
+/// and coverage scores are averaged weighted by project size.
+///
+/// Commands that look entities up by name accept `project::name` to pick one
+/// project's, and fail with `AmbiguousNode` on a bare name that several
+/// projects define.
+#[tauri::command]
+pub async fn parse_projects(
+    configs: Vec<ProjectConfig>,
+    merge_by_name: Option<bool>,
+) -> Result<ParseResult, CommandError> {
+    let configs = configs
+        .iter()
+        .map(resolve_config_paths)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let results = std::thread::scope(|scope| {
+        let handles: Vec<_> = configs
+            .iter()
+            .map(|config| scope.spawn(move || parse_project_cached(config, |_, _| {})))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle.join().unwrap_or_else(|_| {
+                    Err(CommandError::Internal("Project parse panicked".to_string()))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    let projects = configs
+        .iter()
+        .zip(results)
+        .map(|(config, result)| {
+            let name = result
+                .dbt_project
+                .as_ref()
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| config.dbt_project_path.clone());
+            (name, result)
+        })
+        .collect();
+    Ok(merge_parse_results(
+        projects,
+        merge_by_name.unwrap_or(false),
+    ))
+}
+
+/// Merge per-project parse results, namespacing ids by project index
+fn merge_parse_results(projects: Vec<(String, ParseResult)>, merge_by_name: bool) -> ParseResult {
+    let mut merged = ParseResult {
+        success: true,
+        ..Default::default()
+    };
+
+    let mut by_name: HashMap<(LineageNodeType, String), String> = HashMap::new();
+    let mut edge_keys: HashSet<(String, String, LineageEdgeType, Option<String>)> = HashSet::new();
+    // (score * weight, weight) for completeness, documentation, column documentation,
+    // model and ownership coverage
+    let mut scores = [(0.0, 0.0); 5];
+
+    for (index, (project, mut result)) in projects.into_iter().enumerate() {
+        // Old id -> merged id, for this project's edges and issues
+        let mut ids: HashMap<String, String> = HashMap::new();
+        let node_count = result.lineage.nodes.len();
+        let column_count: usize = result
+            .models
+            .iter()
+            .map(|m| m.columns.len())
+            .chain(result.sources.iter().map(|s| s.columns.len()))
+            .sum();
+        for mut node in result.lineage.nodes {
+            let id = format!("{}::{}", index, node.id);
+            if merge_by_name {
+                let key = (node.node_type.clone(), node.name.clone());
+                if let Some(existing) = by_name.get(&key) {
+                    ids.insert(node.id, existing.clone());
+                    continue;
+                }
+                by_name.insert(key, id.clone());
+            }
+            let old_id = std::mem::replace(&mut node.id, id.clone());
+            ids.insert(old_id, id);
+            node.metadata
+                .insert("project".to_string(), serde_json::json!(project));
+            merged.lineage.nodes.push(node);
+        }
+
+        for mut edge in result.lineage.edges {
+            let (Some(source), Some(target)) = (ids.get(&edge.source), ids.get(&edge.target))
+            else {
+                continue;
+            };
+            // Collapsed nodes can bring the same edge in from two projects
+            if merge_by_name
+                && !edge_keys.insert((
+                    source.clone(),
+                    target.clone(),
+                    edge.edge_type,
+                    edge.label.clone(),
+                ))
+            {
+                continue;
+            }
+            edge.id = format!("{}::{}", index, edge.id);
+            edge.source = source.clone();
+            edge.target = target.clone();
+            merged.lineage.edges.push(edge);
+        }
+
+        let audit = result.audit;
+        let summary = &audit.summary;
+        for (slot, (score, weight)) in scores.iter_mut().zip([
+            (audit.completeness_score, summary.total_metrics),
+            (audit.documentation_coverage, node_count),
+            (audit.column_documentation_coverage, column_count),
+            (audit.model_coverage, summary.total_models),
+            (audit.ownership_coverage, summary.total_metrics),
+        ]) {
+            slot.0 += score * weight as f64;
+            slot.1 += weight as f64;
+        }
+
+        let totals = &mut merged.audit.summary;
+        totals.total_metrics += summary.total_metrics;
+        totals.total_measures += summary.total_measures;
+        totals.total_models += summary.total_models;
+        totals.total_sources += summary.total_sources;
+        totals.documented_metrics += summary.documented_metrics;
+        totals.documented_models += summary.documented_models;
+        totals.tested_models += summary.tested_models;
+        totals.orphaned_models += summary.orphaned_models;
+        totals.owned_metrics += summary.owned_metrics;
+        for (metric_type, count) in &summary.metrics_by_type {
+            *totals
+                .metrics_by_type
+                .entry(metric_type.clone())
+                .or_default() += count;
+        }
+        for (agg, count) in &summary.measures_by_agg {
+            *totals.measures_by_agg.entry(agg.clone()).or_default() += count;
+        }
+        for (issue_type, count) in audit.issue_counts {
+            *merged.audit.issue_counts.entry(issue_type).or_default() += count;
+        }
+        for (severity, count) in audit.severity_counts {
+            *merged.audit.severity_counts.entry(severity).or_default() += count;
+        }
+        merged
+            .audit
+            .issues
+            .extend(audit.issues.into_iter().map(|mut issue| {
+                issue.node_id = issue.node_id.and_then(|id| ids.get(&id).cloned());
+                issue
+            }));
+
+        // Entities carry their project like the nodes' `project` metadata
+        for model in &mut result.models {
+            model.project = Some(project.clone());
+        }
+        for source in &mut result.sources {
+            source.project = Some(project.clone());
+        }
+        for semantic_model in &mut result.semantic_models {
+            semantic_model.project = Some(project.clone());
+        }
+        for metric in &mut result.metrics {
+            metric.project = Some(project.clone());
+        }
+
+        merged.models.extend(result.models);
+        merged.sources.extend(result.sources);
+        merged.snapshots.extend(result.snapshots);
+        merged.seeds.extend(result.seeds);
+        merged.tests.extend(result.tests);
+        merged.semantic_models.extend(result.semantic_models);
+        merged.metrics.extend(result.metrics);
+        merged.lookml_views.extend(result.lookml_views);
+        merged.lookml_explores.extend(result.lookml_explores);
+        merged
+            .errors
+            .extend(result.errors.iter().map(|e| format!("{}: {}", project, e)));
+        merged.warnings.extend(
+            result
+                .warnings
+                .iter()
+                .map(|w| format!("{}: {}", project, w)),
+        );
+        merged.success &= result.success;
+    }
+
+    let weighted = |(total, weight): (f64, f64)| if weight > 0.0 { total / weight } else { 0.0 };
+    merged.audit.completeness_score = weighted(scores[0]);
+    merged.audit.documentation_coverage = weighted(scores[1]);
+    merged.audit.column_documentation_coverage = weighted(scores[2]);
+    merged.audit.model_coverage = weighted(scores[3]);
+    merged.audit.ownership_coverage = weighted(scores[4]);
+
+    merged
+}
+
+/// Separates a project from a name looked up in merged results, as in
+/// `finance::stg_orders`
+const PROJECT_SEPARATOR: &str = "::";
+
+/// Find the item `matches` accepts for `name`, where `name` may be qualified
+/// with its project as `project::name`. A bare name matching items of more
+/// than one project, as in results merged by `parse_projects`, is ambiguous.
+fn find_named<'a, T>(
+    items: impl IntoIterator<Item = &'a T>,
+    name: &str,
+    matches: impl Fn(&'a T, &str) -> bool,
+    project: impl Fn(&'a T) -> Option<&'a str>,
+) -> Result<Option<&'a T>, CommandError> {
+    let (wanted_project, name) = match name.split_once(PROJECT_SEPARATOR) {
+        Some((wanted_project, name)) => (Some(wanted_project), name),
+        None => (None, name),
+    };
+    let found: Vec<&T> = items
+        .into_iter()
+        .filter(|&item| {
+            matches(item, name) && wanted_project.map_or(true, |p| project(item) == Some(p))
+        })
+        .collect();
+
+    let mut projects: Vec<&str> = found.iter().filter_map(|&item| project(item)).collect();
+    projects.sort_unstable();
+    projects.dedup();
+    if projects.len() > 1 {
+        return Err(CommandError::AmbiguousNode(format!(
+            "'{}' is defined in projects {}; qualify it as `<project>{}{}`",
+            name,
+            projects.join(", "),
+            PROJECT_SEPARATOR,
+            name
+        )));
+    }
+    Ok(found.into_iter().next())
+}
+
+/// Find the node called `name` of `node_type`, or of any type when `None`;
+/// see [`find_named`] for project-qualified names
+fn find_node<'a>(
+    graph: &'a LineageGraph,
+    node_type: Option<&LineageNodeType>,
+    name: &str,
+) -> Result<Option<&'a LineageNode>, CommandError> {
+    find_named(
+        &graph.nodes,
+        name,
+        |n, name| n.name == name && node_type.map_or(true, |t| n.node_type == *t),
+        |n| n.metadata.get("project")?.as_str(),
+    )
+}
+
+/// Check a project's structure without building the lineage graph or running the audit
+///
+/// Only undefined model refs and sources, duplicate names and metrics with
+/// undefined inputs are checked, which makes this cheap enough for a
+/// pre-commit hook. The report fails when any issue has Error severity.
+#[tauri::command]
+pub async fn validate_project(config: ProjectConfig) -> Result<ValidationReport, CommandError> {
+    let config = resolve_config_paths(&config)?;
+
+    let dbt_parser = DbtProjectParser::new(&config.dbt_project_path)
+        .with_ref_macros(config.ref_macros.clone().unwrap_or_default())
+        .with_source_macros(config.source_macros.clone().unwrap_or_default())
+        .with_sql_extensions(config.sql_extensions.clone().unwrap_or_default());
+    let project = dbt_parser.parse_project().map_err(|e| {
+        CommandError::ParseFailed(format!("Failed to parse dbt_project.yml: {}", e))
+    })?;
+    let models = dbt_parser
+        .parse_models(&project)
+        .map_err(|e| CommandError::ParseFailed(format!("Failed to parse models: {}", e)))?;
+    let sources = dbt_parser
+        .parse_sources(&project)
+        .map_err(|e| CommandError::ParseFailed(format!("Failed to parse sources: {}", e)))?;
+    let snapshots = dbt_parser
+        .parse_snapshots(&project)
+        .map_err(|e| CommandError::ParseFailed(format!("Failed to parse snapshots: {}", e)))?;
+    let seeds = dbt_parser
+        .parse_seeds(&project)
+        .map_err(|e| CommandError::ParseFailed(format!("Failed to parse seeds: {}", e)))?;
+
+    let (semantic_models, metrics) = match config.semantic_layer_type {
+        SemanticLayerType::DbtSemanticLayer => {
+            let (semantic_models, metrics, _) =
+                DbtSemanticLayerParser::new(&config.dbt_project_path)
+                    .parse()
+                    .map_err(|e| {
+                        CommandError::ParseFailed(format!("Failed to parse semantic layer: {}", e))
+                    })?;
+            (semantic_models, metrics)
+        }
+        _ => (Vec::new(), Vec::new()),
+    };
+
+    let issues = LineageAnalyzer::new()
+        .with_severity_overrides(config.severity_overrides.clone().unwrap_or_default())
+        .validate(&models, &sources, &snapshots, &seeds, &semantic_models, &metrics);
+
+    Ok(ValidationReport {
+        passed: !issues.iter().any(|i| i.severity == IssueSeverity::Error),
+        issues,
+    })
+}
+
 /// Get lineage for a specific metric (upstream dependencies)
 #[tauri::command]
 pub async fn get_metric_lineage(
     parse_result: ParseResult,
     metric_name: String,
-) -> Result<ParseResult, String> {
+) -> Result<ParseResult, CommandError> {
     // Find the metric node
-    let metric_node = parse_result
-        .lineage
-        .nodes
-        .iter()
-        .find(|n| n.name == metric_name && n.node_type == crate::types::LineageNodeType::Metric)
-        .ok_or_else(|| format!("Metric '{}' not found", metric_name))?;
+    let metric_node = find_node(
+        &parse_result.lineage,
+        Some(&LineageNodeType::Metric),
+        &metric_name,
+    )?
+    .ok_or_else(|| CommandError::NodeNotFound(format!("Metric '{}' not found", metric_name)))?;
 
-    // BFS to find all upstream nodes
-    let mut visited = std::collections::HashSet::new();
-    let mut queue = vec![metric_node.id.clone()];
-    let mut relevant_node_ids = std::collections::HashSet::new();
+    // BFS to find all upstream nodes, recording the distance from the metric
+    // and the role implied by the edge that first reached each node
+    let mut annotations: HashMap<String, (usize, &str)> = HashMap::new();
+    annotations.insert(metric_node.id.clone(), (0, "metric"));
+    let mut queue = VecDeque::from([metric_node.id.clone()]);
 
-    while let Some(current) = queue.pop() {
-        if visited.contains(&current) {
-            continue;
-        }
-        visited.insert(current.clone());
-        relevant_node_ids.insert(current.clone());
+    while let Some(current) = queue.pop_front() {
+        let distance = annotations[&current].0;
 
         // Find edges where this node is the source
         for edge in &parse_result.lineage.edges {
-            if edge.source == current && !visited.contains(&edge.target) {
-                queue.push(edge.target.clone());
+            if edge.source == current && !annotations.contains_key(&edge.target) {
+                let role = lineage_role(&edge.edge_type);
+                annotations.insert(edge.target.clone(), (distance + 1, role));
+                queue.push_back(edge.target.clone());
             }
         }
     }
+    let relevant_node_ids: HashSet<&String> = annotations.keys().collect();
 
     // Filter graph to only include relevant nodes and edges
-    let mut filtered_result = ParseResult::default();
-    filtered_result.success = true;
-    filtered_result.lineage.nodes = parse_result
+    let nodes = parse_result
         .lineage
         .nodes
-        .into_iter()
-        .filter(|n| relevant_node_ids.contains(&n.id))
+        .iter()
+        .filter_map(|n| {
+            let (distance, role) = annotations.get(&n.id)?;
+            let mut node = n.clone();
+            node.metadata.insert("lineage_role".to_string(), serde_json::json!(role));
+            node.metadata.insert("distance".to_string(), serde_json::json!(distance));
+            Some(node)
+        })
         .collect();
 
-    filtered_result.lineage.edges = parse_result
+    let edges = parse_result
         .lineage
         .edges
-        .into_iter()
+        .iter()
         .filter(|e| relevant_node_ids.contains(&e.source) && relevant_node_ids.contains(&e.target))
+        .cloned()
         .collect();
 
-    Ok(filtered_result)
+    Ok(subgraph_result(
+        &parse_result,
+        LineageGraph { nodes, edges },
+    ))
+}
+
+/// Wrap a subgraph of `full` in a parse result holding the project, the
+/// entities behind its nodes and the audit issues about them, so it exports
+/// like a full parse. Audit scores and the summary still describe the whole
+/// project; issue counts are recounted for the kept issues.
+fn subgraph_result(full: &ParseResult, lineage: LineageGraph) -> ParseResult {
+    let node_ids: HashSet<&str> = lineage.nodes.iter().map(|n| n.id.as_str()).collect();
+    let issues: Vec<AuditIssue> = full
+        .audit
+        .issues
+        .iter()
+        .filter(|i| i.node_id.as_deref().is_some_and(|id| node_ids.contains(id)))
+        .cloned()
+        .collect();
+    let mut issue_counts: HashMap<String, usize> = HashMap::new();
+    let mut severity_counts: HashMap<String, usize> = HashMap::new();
+    for issue in &issues {
+        *issue_counts
+            .entry(format!("{:?}", issue.issue_type))
+            .or_default() += 1;
+        *severity_counts
+            .entry(format!("{:?}", issue.severity))
+            .or_default() += 1;
+    }
+
+    let mut result = ParseResult {
+        success: true,
+        dbt_project: full.dbt_project.clone(),
+        models: full.models.clone(),
+        sources: full.sources.clone(),
+        snapshots: full.snapshots.clone(),
+        seeds: full.seeds.clone(),
+        tests: full.tests.clone(),
+        semantic_models: full.semantic_models.clone(),
+        metrics: full.metrics.clone(),
+        audit: AuditResult {
+            issues,
+            issue_counts,
+            severity_counts,
+            ..full.audit.clone()
+        },
+        lineage,
+        ..Default::default()
+    };
+    retain_graph_entities(&mut result);
+    result
+}
+
+/// Drop the models, sources, snapshots, seeds, tests, semantic models and
+/// metrics that have no node in the result's lineage graph
+fn retain_graph_entities(result: &mut ParseResult) {
+    let nodes = &result.lineage.nodes;
+    let has_node = |node_type: LineageNodeType, name: &str| {
+        nodes
+            .iter()
+            .any(|n| n.node_type == node_type && n.name == name)
+    };
+    let semantic_models: HashSet<&str> = nodes
+        .iter()
+        .filter_map(|n| n.metadata.get("semantic_model")?.as_str())
+        .collect();
+
+    result
+        .models
+        .retain(|m| has_node(LineageNodeType::Model, &m.name));
+    result.sources.retain(|s| {
+        nodes.iter().any(|n| {
+            n.node_type == LineageNodeType::Source
+                && n.name == s.name
+                && n.metadata.get("source_name").and_then(|v| v.as_str())
+                    == Some(s.source_name.as_str())
+        })
+    });
+    result
+        .snapshots
+        .retain(|s| has_node(LineageNodeType::Snapshot, &s.name));
+    result
+        .seeds
+        .retain(|s| has_node(LineageNodeType::Seed, &s.name));
+    result
+        .tests
+        .retain(|t| has_node(LineageNodeType::Test, &t.name));
+    result
+        .semantic_models
+        .retain(|sm| semantic_models.contains(sm.name.as_str()));
+    result
+        .metrics
+        .retain(|m| has_node(LineageNodeType::Metric, &m.name));
+}
+
+/// Cut the lineage graph down to the models in `scope`, everything they are
+/// built from (including upstream models outside the scope), and the
+/// semantic layer and tests on top of them. Models outside the scope that
+/// read from a scoped model are dropped, as are derived metrics' inputs that
+/// are not built on a scoped model. The entity lists follow the graph.
+fn apply_scope(scope: &Scope, project_path: &Path, result: &mut ParseResult) {
+    let root = paths::normalize_path(project_path);
+    let in_scope: HashSet<&str> = result
+        .models
+        .iter()
+        .filter(|model| match scope {
+            Scope::Tags(tags) => model.tags.iter().any(|tag| tags.contains(tag)),
+            Scope::PathPrefix(prefix) => {
+                let relative = model
+                    .file_path
+                    .strip_prefix(&root)
+                    .unwrap_or(&model.file_path)
+                    .trim_start_matches('/');
+                relative.starts_with(prefix.trim_start_matches("./"))
+            }
+        })
+        .map(|model| model.name.as_str())
+        .collect();
+
+    let lineage = &result.lineage;
+    let node_types: HashMap<&str, &LineageNodeType> = lineage
+        .nodes
+        .iter()
+        .map(|n| (n.id.as_str(), &n.node_type))
+        .collect();
+    let mut upstream: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut downstream: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in &lineage.edges {
+        upstream.entry(&edge.source).or_default().push(&edge.target);
+        downstream
+            .entry(&edge.target)
+            .or_default()
+            .push(&edge.source);
+    }
+    let scoped: Vec<&str> = lineage
+        .nodes
+        .iter()
+        .filter(|n| n.node_type == LineageNodeType::Model && in_scope.contains(n.name.as_str()))
+        .map(|n| n.id.as_str())
+        .collect();
+    // A semantic model's nodes only link to its dbt model through an entity,
+    // so start from them directly as well
+    let scoped_semantic_models: HashSet<&str> = result
+        .semantic_models
+        .iter()
+        .filter(|sm| in_scope.contains(sm.model.as_str()))
+        .map(|sm| sm.name.as_str())
+        .collect();
+    let semantic: Vec<&str> = lineage
+        .nodes
+        .iter()
+        .filter(|n| {
+            n.metadata
+                .get("semantic_model")
+                .and_then(|v| v.as_str())
+                .is_some_and(|sm| scoped_semantic_models.contains(sm))
+        })
+        .map(|n| n.id.as_str())
+        .chain(scoped.iter().copied())
+        .collect();
+
+    // Walk dependencies all the way down, but dependents only as far as the
+    // semantic layer and tests, never into other models or snapshots
+    let mut keep: HashSet<String> = HashSet::new();
+    for (adjacency, start, crosses_models) in
+        [(&upstream, &scoped, true), (&downstream, &semantic, false)]
+    {
+        let mut visited: HashSet<&str> = start.iter().copied().collect();
+        let mut queue: VecDeque<&str> = start.iter().copied().collect();
+        while let Some(current) = queue.pop_front() {
+            for &next in adjacency.get(current).into_iter().flatten() {
+                let is_model = matches!(
+                    node_types.get(next),
+                    Some(LineageNodeType::Model | LineageNodeType::Snapshot)
+                );
+                if (crosses_models || !is_model) && visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        keep.extend(visited.into_iter().map(String::from));
+    }
+
+    result.lineage.nodes.retain(|n| keep.contains(&n.id));
+    result
+        .lineage
+        .edges
+        .retain(|e| keep.contains(&e.source) && keep.contains(&e.target));
+    retain_graph_entities(result);
+}
+
+/// Describe the part an upstream node plays in a metric's definition, based on
+/// the edge that led to it
+fn lineage_role(edge_type: &LineageEdgeType) -> &'static str {
+    match edge_type {
+        LineageEdgeType::MetricToMeasure => "measure",
+        LineageEdgeType::MetricToMetric => "input_metric",
+        LineageEdgeType::MetricToDimension
+        | LineageEdgeType::DimensionToEntity
+        | LineageEdgeType::MeasureToDimension => "dimension",
+        LineageEdgeType::MetricToEntity | LineageEdgeType::MeasureToEntity => "entity",
+        LineageEdgeType::EntityToModel
+        | LineageEdgeType::MeasureToModel
+        | LineageEdgeType::DimensionToModel => "base_model",
+        LineageEdgeType::ModelToModel | LineageEdgeType::SnapshotToModel => "upstream_model",
+        LineageEdgeType::ModelToSnapshot => "snapshot",
+        LineageEdgeType::ModelToSeed => "seed",
+        LineageEdgeType::ModelToMetric => "consumer_model",
+        LineageEdgeType::TestToModel => "tested_model",
+        LineageEdgeType::ModelToSource
+        | LineageEdgeType::MeasureToSource
+        | LineageEdgeType::DimensionToSource
+        | LineageEdgeType::SnapshotToSource => "source",
+    }
 }
 
 /// Get impact analysis for a model or source (downstream dependencies)
@@ -192,14 +1033,10 @@ pub async fn get_metric_lineage(
 pub async fn get_impact_analysis(
     parse_result: ParseResult,
     node_name: String,
-) -> Result<ParseResult, String> {
+) -> Result<ParseResult, CommandError> {
     // Find the node
-    let target_node = parse_result
-        .lineage
-        .nodes
-        .iter()
-        .find(|n| n.name == node_name)
-        .ok_or_else(|| format!("Node '{}' not found", node_name))?;
+    let target_node = find_node(&parse_result.lineage, None, &node_name)?
+        .ok_or_else(|| CommandError::NodeNotFound(format!("Node '{}' not found", node_name)))?;
 
     // Reverse BFS to find all downstream nodes (nodes that depend on this one)
     let mut visited = std::collections::HashSet::new();
@@ -222,43 +1059,2322 @@ pub async fn get_impact_analysis(
     }
 
     // Filter graph to only include relevant nodes and edges
-    let mut filtered_result = ParseResult::default();
-    filtered_result.success = true;
-    filtered_result.lineage.nodes = parse_result
+    let nodes = parse_result
         .lineage
         .nodes
-        .into_iter()
+        .iter()
         .filter(|n| relevant_node_ids.contains(&n.id))
+        .cloned()
         .collect();
 
-    filtered_result.lineage.edges = parse_result
+    let edges = parse_result
         .lineage
         .edges
-        .into_iter()
+        .iter()
         .filter(|e| relevant_node_ids.contains(&e.source) && relevant_node_ids.contains(&e.target))
+        .cloned()
         .collect();
 
-    Ok(filtered_result)
+    Ok(subgraph_result(
+        &parse_result,
+        LineageGraph { nodes, edges },
+    ))
 }
 
-/// Search for nodes by name
+/// Get a node with its direct upstream and downstream neighbors and the edges
+/// connecting them, for expanding the graph one click at a time
 #[tauri::command]
-pub fn search_nodes(
+pub fn node_neighbors(
     parse_result: ParseResult,
-    query: String,
-) -> Vec<crate::types::LineageNode> {
-    let query_lower = query.to_lowercase();
+    node_id: String,
+) -> Result<ParseResult, CommandError> {
+    if !parse_result.lineage.nodes.iter().any(|n| n.id == node_id) {
+        return Err(CommandError::NodeNotFound(format!(
+            "Node '{}' not found",
+            node_id
+        )));
+    }
 
-    parse_result
+    let mut filtered_result = ParseResult {
+        success: true,
+        ..Default::default()
+    };
+    filtered_result.lineage.edges = parse_result
+        .lineage
+        .edges
+        .into_iter()
+        .filter(|e| e.source == node_id || e.target == node_id)
+        .collect();
+
+    let neighborhood: HashSet<&str> = filtered_result
+        .lineage
+        .edges
+        .iter()
+        .flat_map(|e| [e.source.as_str(), e.target.as_str()])
+        .chain(std::iter::once(node_id.as_str()))
+        .collect();
+    filtered_result.lineage.nodes = parse_result
         .lineage
         .nodes
         .into_iter()
-        .filter(|n| {
-            n.name.to_lowercase().contains(&query_lower)
-                || n.description
-                    .as_ref()
-                    .map(|d| d.to_lowercase().contains(&query_lower))
-                    .unwrap_or(false)
+        .filter(|n| neighborhood.contains(n.id.as_str()))
+        .collect();
+
+    Ok(filtered_result)
+}
+
+/// Names of all metrics that depend, directly or transitively, on a source.
+///
+/// `source_name` is either the table name or `source_name.table_name`.
+#[tauri::command]
+pub fn impacted_metrics(
+    parse_result: ParseResult,
+    source_name: String,
+) -> Result<Vec<String>, CommandError> {
+    let lineage = &parse_result.lineage;
+    let source = lineage
+        .nodes
+        .iter()
+        .find(|n| {
+            n.node_type == LineageNodeType::Source
+                && (n.name == source_name
+                    || n.metadata
+                        .get("source_name")
+                        .and_then(|v| v.as_str())
+                        .is_some_and(|s| format!("{}.{}", s, n.name) == source_name))
         })
-        .collect()
+        .ok_or_else(|| CommandError::NodeNotFound(format!("Source '{}' not found", source_name)))?;
+
+    // Reverse BFS: everything that depends on the source
+    let mut visited = HashSet::new();
+    let mut queue = vec![source.id.as_str()];
+    while let Some(current) = queue.pop() {
+        if !visited.insert(current) {
+            continue;
+        }
+        for edge in &lineage.edges {
+            if edge.target == current && !visited.contains(edge.source.as_str()) {
+                queue.push(edge.source.as_str());
+            }
+        }
+    }
+
+    let mut metrics: Vec<String> = lineage
+        .nodes
+        .iter()
+        .filter(|n| n.node_type == LineageNodeType::Metric && visited.contains(n.id.as_str()))
+        .map(|n| n.name.clone())
+        .collect();
+    metrics.sort();
+    metrics.dedup();
+
+    Ok(metrics)
+}
+
+/// Number of edges on the shortest path between two metrics, ignoring edge
+/// direction, or `None` when they are not connected. Metrics sharing a
+/// measure are 2 apart.
+#[tauri::command]
+pub fn metric_distance(
+    parse_result: ParseResult,
+    a: String,
+    b: String,
+) -> Result<Option<usize>, CommandError> {
+    let lineage = &parse_result.lineage;
+    let metric_id = |name: &str| {
+        find_node(lineage, Some(&LineageNodeType::Metric), name)?
+            .map(|n| n.id.as_str())
+            .ok_or_else(|| CommandError::NodeNotFound(format!("Metric '{}' not found", name)))
+    };
+    let (start, goal) = (metric_id(&a)?, metric_id(&b)?);
+
+    let mut neighbors: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in &lineage.edges {
+        neighbors
+            .entry(&edge.source)
+            .or_default()
+            .push(&edge.target);
+        neighbors
+            .entry(&edge.target)
+            .or_default()
+            .push(&edge.source);
+    }
+
+    // Breadth-first, so the first time the goal is reached is the shortest path
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((current, distance)) = queue.pop_front() {
+        if current == goal {
+            return Ok(Some(distance));
+        }
+        for &next in neighbors.get(current).into_iter().flatten() {
+            if visited.insert(next) {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Default minimum similarity (0.0 - 1.0) for fuzzy search results
+const DEFAULT_FUZZY_THRESHOLD: f64 = 0.75;
+
+/// Search for nodes by name, description, display label and string `meta` values.
+///
+/// Results are ranked by relevance: an exact name match first, then names
+/// starting with the query, names containing it, and finally nodes matched
+/// only by description, label or `meta`.
+///
+/// With `fuzzy` set, names are also scored by edit distance over both the raw
+/// string and its sorted tokens, so typos and word-order differences still
+/// match; results are returned best match first.
+#[tauri::command]
+pub fn search_nodes(
+    parse_result: ParseResult,
+    query: String,
+    fuzzy: bool,
+    threshold: Option<f64>,
+) -> Vec<crate::types::LineageNode> {
+    let query_lower = query.to_lowercase();
+
+    if !fuzzy {
+        let mut ranked: Vec<(u8, crate::types::LineageNode)> = parse_result
+            .lineage
+            .nodes
+            .into_iter()
+            .map(|n| (relevance(&n, &query_lower), n))
+            .filter(|(rank, _)| *rank > 0)
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+        return ranked.into_iter().map(|(_, n)| n).collect();
+    }
+
+    let threshold = threshold.unwrap_or(DEFAULT_FUZZY_THRESHOLD);
+    let mut scored: Vec<(f64, u8, crate::types::LineageNode)> = parse_result
+        .lineage
+        .nodes
+        .into_iter()
+        .filter_map(|n| {
+            let rank = relevance(&n, &query_lower);
+            let score = if rank > 0 {
+                1.0
+            } else {
+                let label = n.metadata.get("label").and_then(|v| v.as_str());
+                std::iter::once(n.name.as_str())
+                    .chain(label)
+                    .map(|text| fuzzy_score(&query_lower, &text.to_lowercase()))
+                    .fold(0.0, f64::max)
+            };
+            (score >= threshold).then_some((score, rank, n))
+        })
+        .collect();
+
+    scored.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| b.1.cmp(&a.1))
+            .then_with(|| a.2.name.cmp(&b.2.name))
+    });
+    scored.into_iter().map(|(_, _, n)| n).collect()
+}
+
+/// How well a node matches a lowercased query: 4 for an exact name, 3 for a
+/// name prefix, 2 for a name substring, 1 for a description, label or `meta`
+/// match and 0 for no match
+fn relevance(node: &crate::types::LineageNode, query: &str) -> u8 {
+    let name = node.name.to_lowercase();
+    if name == query {
+        4
+    } else if name.starts_with(query) {
+        3
+    } else if name.contains(query) {
+        2
+    } else if node
+        .description
+        .as_ref()
+        .is_some_and(|d| d.to_lowercase().contains(query))
+        || metadata_text(node).any(|t| t.to_lowercase().contains(query))
+    {
+        1
+    } else {
+        0
+    }
+}
+
+/// The `label` and the string values of `meta` stored in a node's metadata
+fn metadata_text(node: &crate::types::LineageNode) -> impl Iterator<Item = &str> {
+    let label = node.metadata.get("label").and_then(|v| v.as_str());
+    let meta = node
+        .metadata
+        .get("meta")
+        .and_then(|v| v.as_object())
+        .into_iter()
+        .flat_map(|meta| meta.values().filter_map(|v| v.as_str()));
+    label.into_iter().chain(meta)
+}
+
+/// Similarity of two lowercased strings, the better of a plain and a token-set comparison
+fn fuzzy_score(query: &str, name: &str) -> f64 {
+    let sorted_tokens = |s: &str| {
+        let mut tokens: Vec<&str> = s
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|t| !t.is_empty())
+            .collect();
+        tokens.sort_unstable();
+        tokens.join(" ")
+    };
+
+    let plain = strsim::normalized_damerau_levenshtein(query, name);
+    let token_set =
+        strsim::normalized_damerau_levenshtein(&sorted_tokens(query), &sorted_tokens(name));
+    plain.max(token_set)
+}
+
+/// Replace the table nodes of each source with one node per source name,
+/// for a high-level view.
+///
+/// Edges into the tables are rewired to the source node and deduplicated,
+/// and audit issues on a table move to its source node. The source node keeps
+/// the table count and names in its `table_count` and `tables` metadata.
+#[tauri::command]
+pub fn collapse_sources(parse_result: ParseResult) -> Result<ParseResult, CommandError> {
+    let mut collapsed_result = parse_result;
+    let lineage = &mut collapsed_result.lineage;
+
+    // Table node id -> collapsed node id
+    let mut collapsed_ids: HashMap<String, String> = HashMap::new();
+    let mut groups: Vec<crate::types::LineageNode> = Vec::new();
+    let mut nodes = Vec::with_capacity(lineage.nodes.len());
+    for node in std::mem::take(&mut lineage.nodes) {
+        let source_name = match node.node_type {
+            LineageNodeType::Source => node
+                .metadata
+                .get("source_name")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            _ => None,
+        };
+        let Some(source_name) = source_name else {
+            nodes.push(node);
+            continue;
+        };
+
+        let group = match groups.iter_mut().find(|g| g.name == source_name) {
+            Some(group) => group,
+            None => {
+                let id = uuid::Uuid::new_v5(
+                    &uuid::Uuid::NAMESPACE_OID,
+                    format!("source.{}", source_name).as_bytes(),
+                )
+                .to_string();
+                let mut metadata = HashMap::new();
+                metadata.insert("source_name".to_string(), serde_json::json!(source_name));
+                groups.push(crate::types::LineageNode {
+                    id,
+                    node_type: LineageNodeType::Source,
+                    name: source_name,
+                    description: None,
+                    metadata,
+                });
+                groups.last_mut().unwrap()
+            }
+        };
+        let tables = group
+            .metadata
+            .entry("tables".to_string())
+            .or_insert_with(|| serde_json::json!([]));
+        if let Some(tables) = tables.as_array_mut() {
+            tables.push(serde_json::json!(node.name));
+        }
+        collapsed_ids.insert(node.id, group.id.clone());
+    }
+    for group in &mut groups {
+        let table_count = group.metadata["tables"].as_array().map_or(0, |t| t.len());
+        group
+            .metadata
+            .insert("table_count".to_string(), serde_json::json!(table_count));
+    }
+    nodes.extend(groups);
+    lineage.nodes = nodes;
+
+    // Only edges rewired onto a source node can have become duplicates
+    let mut seen: HashSet<(String, String, LineageEdgeType, Option<String>)> = HashSet::new();
+    let mut edges = Vec::with_capacity(lineage.edges.len());
+    for mut edge in std::mem::take(&mut lineage.edges) {
+        let mut rewired = false;
+        if let Some(id) = collapsed_ids.get(&edge.source) {
+            edge.source = id.clone();
+            rewired = true;
+        }
+        if let Some(id) = collapsed_ids.get(&edge.target) {
+            edge.target = id.clone();
+            rewired = true;
+        }
+        if edge.source == edge.target {
+            continue;
+        }
+        if rewired
+            && !seen.insert((
+                edge.source.clone(),
+                edge.target.clone(),
+                edge.edge_type,
+                edge.label.clone(),
+            ))
+        {
+            continue;
+        }
+        edges.push(edge);
+    }
+    lineage.edges = edges;
+
+    for issue in &mut collapsed_result.audit.issues {
+        if let Some(id) = issue.node_id.as_ref().and_then(|id| collapsed_ids.get(id)) {
+            issue.node_id = Some(id.clone());
+        }
+    }
+
+    Ok(collapsed_result)
+}
+
+/// Attach external data, such as SLA status from an observability tool, to
+/// nodes under their `external` metadata key.
+///
+/// Annotations are keyed by node id, by `type.name` (`metric.revenue`), or
+/// by bare node name, tried in that order. A bare name shared by several
+/// nodes, such as a model and a metric both called `orders`, is an error.
+/// Keys matching no node are listed in the result's warnings.
+#[tauri::command]
+pub fn annotate_nodes(
+    parse_result: ParseResult,
+    annotations: HashMap<String, serde_json::Value>,
+) -> Result<ParseResult, CommandError> {
+    let mut annotated_result = parse_result;
+    let mut unmatched: Vec<&String> = Vec::new();
+
+    for (key, value) in &annotations {
+        let nodes = &annotated_result.lineage.nodes;
+        let find = |matches: &dyn Fn(&LineageNode) -> bool| -> Vec<usize> {
+            (0..nodes.len()).filter(|&i| matches(&nodes[i])).collect()
+        };
+        let mut targets = find(&|n| n.id == *key);
+        if targets.is_empty() {
+            targets = find(&|n| format!("{:?}.{}", n.node_type, n.name).eq_ignore_ascii_case(key));
+        }
+        if targets.is_empty() {
+            targets = find(&|n| n.name == *key);
+            if targets.len() > 1 {
+                return Err(CommandError::AmbiguousNode(format!(
+                    "Annotation '{}' matches {} nodes; key it by node id or as `type.name`",
+                    key,
+                    targets.len()
+                )));
+            }
+        }
+
+        if targets.is_empty() {
+            unmatched.push(key);
+        }
+        for i in targets {
+            annotated_result.lineage.nodes[i]
+                .metadata
+                .insert("external".to_string(), value.clone());
+        }
+    }
+
+    unmatched.sort();
+    annotated_result.warnings.extend(
+        unmatched
+            .into_iter()
+            .map(|key| format!("No node matches annotation '{}'", key)),
+    );
+    Ok(annotated_result)
+}
+
+/// Keep only edges of the given types, dropping nodes left without any edge
+#[tauri::command]
+pub fn filter_edges(
+    parse_result: ParseResult,
+    edge_types: Vec<LineageEdgeType>,
+) -> Result<ParseResult, CommandError> {
+    let edge_types: HashSet<LineageEdgeType> = edge_types.into_iter().collect();
+
+    let mut filtered_result = parse_result;
+    filtered_result
+        .lineage
+        .edges
+        .retain(|e| edge_types.contains(&e.edge_type));
+
+    let connected: HashSet<String> = filtered_result
+        .lineage
+        .edges
+        .iter()
+        .flat_map(|e| [e.source.clone(), e.target.clone()])
+        .collect();
+    filtered_result
+        .lineage
+        .nodes
+        .retain(|n| connected.contains(&n.id));
+
+    Ok(filtered_result)
+}
+
+/// Get the audit issues attached to a single node
+#[tauri::command]
+pub fn node_issues(audit: AuditResult, node_id: String) -> Result<Vec<AuditIssue>, CommandError> {
+    Ok(audit
+        .issues
+        .into_iter()
+        .filter(|i| i.node_id.as_deref() == Some(node_id.as_str()))
+        .collect())
+}
+
+/// Get clusters of dead nodes (no path from any metric), largest first
+#[tauri::command]
+pub fn get_orphan_clusters(parse_result: ParseResult) -> Vec<Vec<String>> {
+    LineageAnalyzer::new().find_orphan_clusters(&parse_result.lineage)
+}
+
+/// Get the entry and exit points of the graph, sorted: the names of sources
+/// and seeds with no upstream (roots) and of metrics nothing depends on
+/// (leaves). Dimensions, entities and the like are never endpoints.
+#[tauri::command]
+pub fn graph_endpoints(parse_result: ParseResult) -> Result<GraphEndpoints, CommandError> {
+    let graph = &parse_result.lineage;
+    // Edges point from a node to its upstream dependency
+    let has_upstream: HashSet<&str> = graph.edges.iter().map(|e| e.source.as_str()).collect();
+    let has_downstream: HashSet<&str> = graph.edges.iter().map(|e| e.target.as_str()).collect();
+
+    let names = |node_types: &[LineageNodeType], exclude: &HashSet<&str>| {
+        let mut names: Vec<String> = graph
+            .nodes
+            .iter()
+            .filter(|n| node_types.contains(&n.node_type) && !exclude.contains(n.id.as_str()))
+            .map(|n| n.name.clone())
+            .collect();
+        names.sort();
+        names
+    };
+
+    Ok(GraphEndpoints {
+        roots: names(
+            &[LineageNodeType::Source, LineageNodeType::Seed],
+            &has_upstream,
+        ),
+        leaves: names(&[LineageNodeType::Metric], &has_downstream),
+    })
+}
+
+/// Get the names of metrics nothing downstream consumes, sorted.
+///
+/// Only consumers present in the lineage graph count: derived metrics and
+/// models querying the metric through `metric()`. Exposures and saved queries
+/// are not parsed into the graph, so a metric used only by those is listed too.
+#[tauri::command]
+pub fn unused_metrics(parse_result: ParseResult) -> Result<Vec<String>, CommandError> {
+    let graph = &parse_result.lineage;
+    let consumed: HashSet<&str> = graph.edges.iter().map(|e| e.target.as_str()).collect();
+
+    let mut names: Vec<String> = graph
+        .nodes
+        .iter()
+        .filter(|n| n.node_type == LineageNodeType::Metric && !consumed.contains(n.id.as_str()))
+        .map(|n| n.name.clone())
+        .collect();
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+/// Get the fraction of a source's columns that feed any metric
+#[tauri::command]
+pub fn get_source_utilization(
+    parse_result: ParseResult,
+    source_name: String,
+) -> Result<SourceUtilization, CommandError> {
+    let source = find_named(
+        &parse_result.sources,
+        &source_name,
+        |s, name| s.name == name || format!("{}.{}", s.source_name, s.name) == name,
+        |s| s.project.as_deref(),
+    )?
+    .ok_or_else(|| CommandError::NodeNotFound(format!("Source '{}' not found", source_name)))?;
+    let project = source.project.clone();
+    let source_name = format!("{}.{}", source.source_name, source.name);
+
+    // Only models of the source's own project read it
+    let mut parse_result = parse_result;
+    parse_result.models.retain(|m| m.project == project);
+    parse_result.sources.retain(|s| s.project == project);
+    // Utilization is read off model SQL, which is dropped by default, so load
+    // it back for the models reading this source
+    for model in parse_result.models.iter_mut().filter(|m| {
+        m.sources
+            .iter()
+            .any(|r| format!("{}.{}", r.source_name, r.table_name) == source_name)
+    }) {
+        load_model_sql(model)?;
+    }
+
+    LineageAnalyzer::new()
+        .source_utilization(
+            &parse_result.lineage,
+            &parse_result.models,
+            &parse_result.sources,
+            &source_name,
+        )
+        .ok_or_else(|| CommandError::NodeNotFound(format!("Source '{}' not found", source_name)))
+}
+
+/// Read a model's SQL from its file when the parse dropped it (the default
+/// unless `keep_raw_sql` is set)
+fn load_model_sql(model: &mut DbtModel) -> Result<(), CommandError> {
+    if model.raw_sql.is_none() {
+        let sql = read_sql(Path::new(&model.file_path)).map_err(|e| {
+            CommandError::ParseFailed(format!("Failed to read {}: {}", model.file_path, e))
+        })?;
+        model.raw_sql = Some(sql);
+    }
+    Ok(())
+}
+
+/// Get, per metric, whether it traces to a source and where its lineage breaks
+#[tauri::command]
+pub fn metric_completeness(
+    parse_result: ParseResult,
+) -> Result<Vec<MetricCompleteness>, CommandError> {
+    Ok(LineageAnalyzer::new().metric_completeness(&parse_result.lineage))
+}
+
+/// Get the dimensions a metric can be grouped by, including those joined in
+/// from other semantic models through shared entities
+#[tauri::command]
+pub fn dimension_availability(
+    parse_result: ParseResult,
+    metric_name: String,
+) -> Result<Vec<String>, CommandError> {
+    let metric = find_named(
+        &parse_result.metrics,
+        &metric_name,
+        |m, name| m.name == name,
+        |m| m.project.as_deref(),
+    )?
+    .ok_or_else(|| CommandError::NodeNotFound(format!("Metric '{}' not found", metric_name)))?;
+    let project = metric.project.clone();
+    let name = metric.name.clone();
+
+    // Semantic models only join within their own project
+    let mut parse_result = parse_result;
+    parse_result
+        .semantic_models
+        .retain(|sm| sm.project == project);
+    parse_result.metrics.retain(|m| m.project == project);
+    LineageAnalyzer::new()
+        .dimension_availability(&parse_result.semantic_models, &parse_result.metrics, &name)
+        .ok_or_else(|| CommandError::NodeNotFound(format!("Metric '{}' not found", metric_name)))
+}
+
+/// Rank metrics by upstream dependencies plus downstream consumers, most
+/// fragile first
+#[tauri::command]
+pub fn blast_radius(parse_result: ParseResult) -> Result<Vec<BlastRadius>, CommandError> {
+    Ok(LineageAnalyzer::new().blast_radius(&parse_result.lineage))
+}
+
+/// Get a metric's upstream dependencies as a nested tree
+#[tauri::command]
+pub fn metric_tree(
+    parse_result: ParseResult,
+    metric_name: String,
+) -> Result<MetricTreeNode, CommandError> {
+    let metric = find_node(
+        &parse_result.lineage,
+        Some(&LineageNodeType::Metric),
+        &metric_name,
+    )?
+    .ok_or_else(|| CommandError::NodeNotFound(format!("Metric '{}' not found", metric_name)))?;
+    LineageAnalyzer::new()
+        .metric_tree(&parse_result.lineage, &metric.id)
+        .ok_or_else(|| CommandError::NodeNotFound(format!("Metric '{}' not found", metric_name)))
+}
+
+/// Describe a metric's lineage in a plain-English sentence, for generated docs
+#[tauri::command]
+pub fn explain_metric(
+    parse_result: ParseResult,
+    metric_name: String,
+) -> Result<String, CommandError> {
+    let metric = find_node(
+        &parse_result.lineage,
+        Some(&LineageNodeType::Metric),
+        &metric_name,
+    )?
+    .ok_or_else(|| CommandError::NodeNotFound(format!("Metric '{}' not found", metric_name)))?;
+    LineageAnalyzer::new()
+        .explain_metric(&parse_result.lineage, &metric.id)
+        .ok_or_else(|| CommandError::NodeNotFound(format!("Metric '{}' not found", metric_name)))
+}
+
+/// Suggest metrics that are likely duplicates, judged by their names
+#[tauri::command]
+pub fn suggest_metric_consolidation(
+    parse_result: ParseResult,
+) -> Result<Vec<ConsolidationHint>, CommandError> {
+    Ok(LineageAnalyzer::new().consolidation_hints(&parse_result.metrics))
+}
+
+/// Get the metrics referencing each measure, most reused first
+#[tauri::command]
+pub fn measure_usage(parse_result: ParseResult) -> Result<Vec<MeasureUsage>, CommandError> {
+    Ok(LineageAnalyzer::new().measure_usage(&parse_result.lineage))
+}
+
+/// Get the columns of the underlying dbt model each measure reads
+#[tauri::command]
+pub fn measure_columns(parse_result: ParseResult) -> Result<Vec<MeasureColumn>, CommandError> {
+    Ok(LineageAnalyzer::new().measure_columns(&parse_result.semantic_models))
+}
+
+/// Get node and edge counts, component count and longest path of the lineage graph
+#[tauri::command]
+pub fn graph_stats(parse_result: ParseResult) -> Result<GraphStats, CommandError> {
+    Ok(LineageAnalyzer::new().graph_stats(&parse_result.lineage))
+}
+
+/// Get node names in dependency order, sources first
+#[tauri::command]
+pub fn topological_order(
+    parse_result: ParseResult,
+    include_semantic: Option<bool>,
+) -> Result<Vec<String>, CommandError> {
+    LineageAnalyzer::new()
+        .topological_order(&parse_result.lineage, include_semantic.unwrap_or(false))
+        .map_err(CommandError::CycleDetected)
+}
+
+/// Export audit issues as SARIF, for GitHub code scanning and similar tools,
+/// with file locations relative to `project_path`
+#[tauri::command]
+pub fn export_sarif(audit: AuditResult, project_path: String) -> Result<String, CommandError> {
+    let project_root = paths::normalize_path(Path::new(&project_path));
+    Ok(export::to_sarif(&audit, Some(&project_root)))
+}
+
+/// Get a model's SQL with `ref()` and `source()` calls replaced by the
+/// relations they resolve to, for debugging. Pass the project's
+/// `ref_macros` and `source_macros` so calls to custom macros resolve too.
+#[tauri::command]
+pub fn resolved_sql(
+    parse_result: ParseResult,
+    model_name: String,
+    ref_macros: Option<Vec<String>>,
+    source_macros: Option<Vec<String>>,
+) -> Result<String, CommandError> {
+    let mut model = find_named(
+        &parse_result.models,
+        &model_name,
+        |m, name| m.name == name,
+        |m| m.project.as_deref(),
+    )?
+    .cloned()
+    .ok_or_else(|| CommandError::NodeNotFound(format!("Model '{}' not found", model_name)))?;
+    load_model_sql(&mut model)?;
+    // Refs and sources resolve within the model's own project
+    let mut parse_result = parse_result;
+    parse_result.models.retain(|m| m.project == model.project);
+    parse_result.sources.retain(|s| s.project == model.project);
+
+    let ref_macros: Vec<String> = std::iter::once("ref".to_string())
+        .chain(ref_macros.unwrap_or_default())
+        .collect();
+    let source_macros: Vec<String> = std::iter::once("source".to_string())
+        .chain(source_macros.unwrap_or_default())
+        .collect();
+    Ok(resolve_relations(
+        model.raw_sql.as_deref().unwrap_or_default(),
+        &ref_macros,
+        &source_macros,
+        &parse_result.models,
+        &parse_result.sources,
+        &parse_result.seeds,
+    ))
+}
+
+/// Render the lineage graph as a static SVG image
+#[tauri::command]
+pub fn render_svg(parse_result: ParseResult) -> Result<String, CommandError> {
+    Ok(export::to_svg(&parse_result.lineage))
+}
+
+/// Export the parse result as dbt docs-style JSON, for dbt docs-like viewers
+#[tauri::command]
+pub fn export_dbt_docs(parse_result: ParseResult) -> Result<String, CommandError> {
+    Ok(export::to_dbt_docs(&parse_result))
+}
+
+/// Export a parse result in the given format, one entry point for all text
+/// exports. Any parse result works, including the subgraphs returned by
+/// `get_metric_lineage` or `get_impact_analysis`, which keep the entities and
+/// issues of their nodes. SARIF file locations are
+/// made relative to `project_path` when it is given.
+#[tauri::command]
+pub fn export(
+    parse_result: ParseResult,
+    format: ExportFormat,
+    project_path: Option<String>,
+) -> Result<String, CommandError> {
+    let project_root = project_path.map(|p| paths::normalize_path(Path::new(&p)));
+
+    Ok(match format {
+        ExportFormat::Dot => export::to_dot(&parse_result.lineage),
+        ExportFormat::GraphMl => export::to_graphml(&parse_result.lineage),
+        ExportFormat::Mermaid => export::to_mermaid(&parse_result.lineage),
+        ExportFormat::Svg => export::to_svg(&parse_result.lineage),
+        ExportFormat::DbtDocs => export::to_dbt_docs(&parse_result),
+        ExportFormat::Sarif => export::to_sarif(&parse_result.audit, project_root.as_deref()),
+    })
+}
+
+/// Export the lineage graph as GraphML for tools such as Gephi or yEd
+#[tauri::command]
+pub fn export_graphml(parse_result: ParseResult) -> Result<String, CommandError> {
+    Ok(export::to_graphml(&parse_result.lineage))
+}
+
+/// Write the lineage graph as node and edge Parquet tables into `out_path`
+#[tauri::command]
+pub fn export_lineage_parquet(
+    parse_result: ParseResult,
+    out_path: String,
+) -> Result<(), CommandError> {
+    export::write_parquet(&parse_result.lineage, Path::new(&out_path))
+        .map_err(|e| CommandError::Internal(format!("Failed to export Parquet: {:#}", e)))
+}
+
+/// Report lineage changes between two parse runs, e.g. main vs. a feature branch
+#[tauri::command]
+pub fn diff_lineage(before: ParseResult, after: ParseResult) -> Result<LineageDiff, CommandError> {
+    Ok(diff::diff_graphs(&before.lineage, &after.lineage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CompletenessPolicy, IssueType, LineageEdge, LineageNode, LineageNodeType};
+
+    fn node(id: &str, node_type: LineageNodeType) -> LineageNode {
+        LineageNode {
+            id: id.to_string(),
+            node_type,
+            name: id.to_string(),
+            description: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// A fresh `jaffle_shop` project in the temp directory, with an empty
+    /// `models` directory
+    fn temp_project() -> std::path::PathBuf {
+        let project_dir =
+            std::env::temp_dir().join(format!("semantic-tracer-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(project_dir.join("models")).unwrap();
+        std::fs::write(project_dir.join("dbt_project.yml"), "name: jaffle_shop\n").unwrap();
+        project_dir
+    }
+
+    /// Parse settings for a plain dbt project at `project_dir`, everything off
+    fn test_config(project_dir: &Path) -> ProjectConfig {
+        ProjectConfig {
+            dbt_project_path: project_dir.to_string_lossy().to_string(),
+            semantic_layer_path: None,
+            semantic_layer_type: SemanticLayerType::None,
+            strict: false,
+            profiles_path: None,
+            require_metric_owner: false,
+            severity_overrides: None,
+            use_cache: false,
+            prune_unreferenced: false,
+            allowed_aggs: None,
+            deterministic_ids: false,
+            keep_raw_sql: false,
+            ref_macros: None,
+            source_macros: None,
+            sql_extensions: None,
+            include_tests: false,
+            scope: None,
+            completeness_policy: CompletenessPolicy::default(),
+            fail_on: None,
+            prefer_manifest: false,
+        }
+    }
+
+    fn edge(source: &str, target: &str, edge_type: LineageEdgeType) -> LineageEdge {
+        LineageEdge {
+            id: format!("{}->{}", source, target),
+            source: source.to_string(),
+            target: target.to_string(),
+            edge_type,
+            label: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_metric_lineage_to_dot() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("order_total", LineageNodeType::Measure),
+            node("signups", LineageNodeType::Metric),
+            node("signup_count", LineageNodeType::Measure),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue", "order_total", LineageEdgeType::MetricToMeasure),
+            edge("signups", "signup_count", LineageEdgeType::MetricToMeasure),
+        ];
+
+        let lineage = get_metric_lineage(parse_result, "revenue".to_string())
+            .await
+            .unwrap();
+        let dot = export(lineage, ExportFormat::Dot, None).unwrap();
+
+        assert!(dot.starts_with("digraph lineage {"));
+        assert!(dot.contains("\"revenue\" -> \"order_total\" [label=\"MetricToMeasure\"];"));
+        assert!(!dot.contains("signup"));
+    }
+
+    #[tokio::test]
+    async fn test_metric_lineage_annotates_distance_and_role() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("order_total", LineageNodeType::Measure),
+            node("order_id", LineageNodeType::Entity),
+            node("fct_orders", LineageNodeType::Model),
+            node("raw_orders", LineageNodeType::Source),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue", "order_total", LineageEdgeType::MetricToMeasure),
+            edge("order_total", "order_id", LineageEdgeType::MeasureToEntity),
+            edge("order_id", "fct_orders", LineageEdgeType::EntityToModel),
+            edge("fct_orders", "raw_orders", LineageEdgeType::ModelToSource),
+        ];
+
+        let lineage = get_metric_lineage(parse_result, "revenue".to_string())
+            .await
+            .unwrap();
+        let find = |name: &str| lineage.lineage.nodes.iter().find(|n| n.name == name).unwrap();
+
+        assert_eq!(lineage.lineage.edges.len(), 4);
+        assert_eq!(find("order_total").metadata["distance"], 1);
+        assert_eq!(find("order_total").metadata["lineage_role"], "measure");
+        assert_eq!(find("fct_orders").metadata["lineage_role"], "base_model");
+        assert_eq!(find("raw_orders").metadata["lineage_role"], "source");
+        assert!(find("raw_orders").metadata["distance"].as_u64().unwrap() > 1);
+    }
+
+    #[test]
+    fn test_fuzzy_search_tolerates_typos() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("total_orders", LineageNodeType::Metric),
+            node("revenue", LineageNodeType::Metric),
+            node("customers", LineageNodeType::Model),
+        ];
+
+        let exact = search_nodes(parse_result.clone(), "reveune".to_string(), false, None);
+        assert!(exact.is_empty());
+
+        let fuzzy = search_nodes(parse_result.clone(), "reveune".to_string(), true, None);
+        assert_eq!(fuzzy.len(), 1);
+        assert_eq!(fuzzy[0].name, "revenue");
+
+        let reordered = search_nodes(parse_result, "order total".to_string(), true, None);
+        assert_eq!(reordered[0].name, "total_orders");
+    }
+
+    #[test]
+    fn test_search_ranks_exact_name_first() {
+        let mut parse_result = ParseResult::default();
+        let mut orders = node("orders", LineageNodeType::Model);
+        orders.description = Some("Feeds revenue reporting".to_string());
+        parse_result.lineage.nodes = vec![
+            node("revenue_by_region_daily", LineageNodeType::Metric),
+            orders,
+            node("net_revenue", LineageNodeType::Metric),
+            node("revenue", LineageNodeType::Metric),
+        ];
+
+        let results = search_nodes(parse_result, "Revenue".to_string(), false, None);
+
+        let names: Vec<&str> = results.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "revenue",
+                "revenue_by_region_daily",
+                "net_revenue",
+                "orders"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_matches_label_and_meta() {
+        let mut parse_result = ParseResult::default();
+        let mut mrr = node("mrr", LineageNodeType::Metric);
+        mrr.metadata.insert(
+            "label".to_string(),
+            serde_json::json!("Monthly Recurring Revenue"),
+        );
+        mrr.metadata.insert(
+            "meta".to_string(),
+            serde_json::json!({ "owner": "Finance Team", "tier": 1 }),
+        );
+        parse_result.lineage.nodes = vec![mrr, node("customers", LineageNodeType::Model)];
+
+        let by_label = search_nodes(parse_result.clone(), "recurring".to_string(), false, None);
+        assert_eq!(by_label.len(), 1);
+        assert_eq!(by_label[0].name, "mrr");
+
+        let by_meta = search_nodes(
+            parse_result.clone(),
+            "finance team".to_string(),
+            false,
+            None,
+        );
+        assert_eq!(by_meta.len(), 1);
+
+        let fuzzy = search_nodes(
+            parse_result,
+            "monthly recuring revenue".to_string(),
+            true,
+            None,
+        );
+        assert_eq!(fuzzy[0].name, "mrr");
+    }
+
+    #[test]
+    fn test_metric_distance_through_shared_measure() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("average_order_value", LineageNodeType::Metric),
+            node("order_total", LineageNodeType::Measure),
+            node("signups", LineageNodeType::Metric),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue", "order_total", LineageEdgeType::MetricToMeasure),
+            edge(
+                "average_order_value",
+                "order_total",
+                LineageEdgeType::MetricToMeasure,
+            ),
+        ];
+        let distance = |a: &str, b: &str| {
+            metric_distance(parse_result.clone(), a.to_string(), b.to_string()).unwrap()
+        };
+
+        assert_eq!(distance("revenue", "average_order_value"), Some(2));
+        assert_eq!(distance("revenue", "revenue"), Some(0));
+        assert_eq!(distance("revenue", "signups"), None);
+        assert!(matches!(
+            metric_distance(
+                parse_result.clone(),
+                "revenue".to_string(),
+                "churn".to_string()
+            ),
+            Err(CommandError::NodeNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_unused_metrics_skips_consumed_metrics() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("legacy_revenue", LineageNodeType::Metric),
+            node("revenue_growth", LineageNodeType::Metric),
+            node("revenue_report", LineageNodeType::Model),
+            node("fct_orders", LineageNodeType::Model),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue_growth", "revenue", LineageEdgeType::MetricToMetric),
+            edge(
+                "revenue_report",
+                "revenue_growth",
+                LineageEdgeType::ModelToMetric,
+            ),
+            edge(
+                "legacy_revenue",
+                "fct_orders",
+                LineageEdgeType::MetricToMeasure,
+            ),
+        ];
+
+        let unused = unused_metrics(parse_result).unwrap();
+
+        assert_eq!(unused, vec!["legacy_revenue"]);
+    }
+
+    #[test]
+    fn test_annotate_nodes_merges_external_data() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("orders", LineageNodeType::Metric),
+            node("orders", LineageNodeType::Model),
+        ];
+        let annotations = HashMap::from([
+            (
+                "revenue".to_string(),
+                serde_json::json!({ "sla": "breached", "freshness_hours": 30 }),
+            ),
+            (
+                "model.orders".to_string(),
+                serde_json::json!({ "sla": "ok" }),
+            ),
+            ("churn".to_string(), serde_json::json!({ "sla": "ok" })),
+        ]);
+
+        let annotated = annotate_nodes(parse_result, annotations).unwrap();
+
+        let find = |name: &str, node_type: LineageNodeType| {
+            annotated
+                .lineage
+                .nodes
+                .iter()
+                .find(|n| n.name == name && n.node_type == node_type)
+                .unwrap()
+        };
+        let revenue = find("revenue", LineageNodeType::Metric);
+        assert_eq!(revenue.metadata["external"]["sla"], "breached");
+        assert_eq!(revenue.metadata["external"]["freshness_hours"], 30);
+        assert_eq!(
+            find("orders", LineageNodeType::Model).metadata["external"]["sla"],
+            "ok"
+        );
+        assert!(!find("orders", LineageNodeType::Metric)
+            .metadata
+            .contains_key("external"));
+        assert_eq!(
+            annotated.warnings,
+            vec!["No node matches annotation 'churn'"]
+        );
+    }
+
+    #[test]
+    fn test_annotate_nodes_prefers_ids_and_rejects_ambiguous_names() {
+        let mut parse_result = ParseResult::default();
+        let mut orders_metric = node("orders", LineageNodeType::Metric);
+        orders_metric.id = "metric-1".to_string();
+        let mut orders_model = node("orders", LineageNodeType::Model);
+        orders_model.id = "model-1".to_string();
+        parse_result.lineage.nodes = vec![orders_metric, orders_model];
+
+        let by_id = annotate_nodes(
+            parse_result.clone(),
+            HashMap::from([("model-1".to_string(), serde_json::json!({ "sla": "ok" }))]),
+        )
+        .unwrap();
+        let ambiguous = annotate_nodes(
+            parse_result,
+            HashMap::from([("orders".to_string(), serde_json::json!({ "sla": "ok" }))]),
+        );
+
+        assert!(!by_id.lineage.nodes[0].metadata.contains_key("external"));
+        assert_eq!(by_id.lineage.nodes[1].metadata["external"]["sla"], "ok");
+        assert!(matches!(ambiguous, Err(CommandError::AmbiguousNode(_))));
+    }
+
+    #[test]
+    fn test_collapse_sources_merges_tables_of_one_source() {
+        let table = |id: &str, source_name: &str| {
+            let mut table = node(id, LineageNodeType::Source);
+            table
+                .metadata
+                .insert("source_name".to_string(), serde_json::json!(source_name));
+            table
+        };
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("stg_orders", LineageNodeType::Model),
+            node("stg_payments", LineageNodeType::Model),
+            table("orders", "shop"),
+            table("payments", "shop"),
+            table("events", "web"),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("stg_orders", "orders", LineageEdgeType::ModelToSource),
+            edge("stg_orders", "payments", LineageEdgeType::ModelToSource),
+            edge("stg_payments", "payments", LineageEdgeType::ModelToSource),
+        ];
+
+        let collapsed = collapse_sources(parse_result).unwrap();
+
+        let sources: Vec<_> = collapsed
+            .lineage
+            .nodes
+            .iter()
+            .filter(|n| n.node_type == LineageNodeType::Source)
+            .collect();
+        assert_eq!(sources.len(), 2);
+        let shop = sources.iter().find(|n| n.name == "shop").unwrap();
+        assert_eq!(shop.metadata["table_count"], 2);
+        assert_eq!(collapsed.lineage.nodes.len(), 4);
+
+        // stg_orders read both tables, which become one edge
+        assert_eq!(collapsed.lineage.edges.len(), 2);
+        assert!(collapsed.lineage.edges.iter().all(|e| e.target == shop.id));
+        assert!(collapsed
+            .lineage
+            .edges
+            .iter()
+            .any(|e| e.source == "stg_orders"));
+    }
+
+    #[test]
+    fn test_collapse_sources_keeps_unrelated_parallel_edges() {
+        let mut table = node("orders", LineageNodeType::Source);
+        table
+            .metadata
+            .insert("source_name".to_string(), serde_json::json!("shop"));
+        let labelled = |label: &str| LineageEdge {
+            label: Some(label.to_string()),
+            ..edge("revenue", "fct_orders", LineageEdgeType::MeasureToModel)
+        };
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Measure),
+            node("fct_orders", LineageNodeType::Model),
+            table,
+        ];
+        parse_result.lineage.edges = vec![
+            labelled("amount"),
+            labelled("tax"),
+            edge("fct_orders", "orders", LineageEdgeType::ModelToSource),
+        ];
+
+        let collapsed = collapse_sources(parse_result).unwrap();
+
+        let labels: Vec<_> = collapsed
+            .lineage
+            .edges
+            .iter()
+            .filter_map(|e| e.label.as_deref())
+            .collect();
+        assert_eq!(labels, vec!["amount", "tax"]);
+        assert_eq!(collapsed.lineage.edges.len(), 3);
+    }
+
+    #[test]
+    fn test_unchanged_project_is_served_from_cache() {
+        let project_dir = temp_project();
+        std::fs::write(project_dir.join("models/orders.sql"), "select 1 as id").unwrap();
+
+        let config = ProjectConfig {
+            use_cache: true,
+            ..test_config(&project_dir)
+        };
+
+        let first = parse_project_cached(&config, |_, _| {}).unwrap();
+        let second = parse_project_cached(&config, |_, _| {}).unwrap();
+        std::fs::write(project_dir.join("models/orders.sql"), "select 2 as id").unwrap();
+        let after_change = parse_project_cached(&config, |_, _| {}).unwrap();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        assert!(!first.from_cache);
+        assert!(second.from_cache);
+        assert_eq!(second.models.len(), first.models.len());
+        assert!(!after_change.from_cache);
+    }
+
+    #[test]
+    fn test_env_var_change_invalidates_cache() {
+        let project_dir = temp_project();
+        std::fs::write(
+            project_dir.join("dbt_project.yml"),
+            "name: jaffle_shop\nprofile: \"{{ env_var('SEMANTIC_TRACER_TEST_PROFILE') }}\"\n",
+        )
+        .unwrap();
+        let config = ProjectConfig {
+            use_cache: true,
+            ..test_config(&project_dir)
+        };
+
+        let unset = parse_project_cached(&config, |_, _| {}).unwrap();
+        std::env::set_var("SEMANTIC_TRACER_TEST_PROFILE", "jaffle_shop");
+        let set = parse_project_cached(&config, |_, _| {}).unwrap();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        assert!(unset
+            .warnings
+            .iter()
+            .any(|w| w.contains("'SEMANTIC_TRACER_TEST_PROFILE'")));
+        assert!(!set.from_cache);
+        assert_eq!(
+            set.dbt_project.unwrap().profile.as_deref(),
+            Some("jaffle_shop")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_impact_analysis_includes_metric_consumers() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("revenue_report", LineageNodeType::Model),
+            node("order_total", LineageNodeType::Measure),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue", "order_total", LineageEdgeType::MetricToMeasure),
+            edge("revenue_report", "revenue", LineageEdgeType::ModelToMetric),
+        ];
+
+        let impact = get_impact_analysis(parse_result, "revenue".to_string())
+            .await
+            .unwrap();
+
+        let names: Vec<&str> = impact
+            .lineage
+            .nodes
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        assert!(names.contains(&"revenue_report"));
+        assert!(!names.contains(&"order_total"));
+    }
+
+    #[tokio::test]
+    async fn test_impact_analysis_keeps_entities_and_issues_of_its_nodes() {
+        let project_dir = temp_project();
+        std::fs::write(project_dir.join("models/stg_orders.sql"), "select 1 as id").unwrap();
+        std::fs::write(
+            project_dir.join("models/fct_orders.sql"),
+            "select * from {{ ref('stg_orders') }}",
+        )
+        .unwrap();
+        std::fs::write(project_dir.join("models/customers.sql"), "select 1 as id").unwrap();
+
+        let parse_result = parse_project_cached(&test_config(&project_dir), |_, _| {}).unwrap();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+        let impact = get_impact_analysis(parse_result, "stg_orders".to_string())
+            .await
+            .unwrap();
+
+        let mut models: Vec<&str> = impact.models.iter().map(|m| m.name.as_str()).collect();
+        models.sort();
+        assert_eq!(models, vec!["fct_orders", "stg_orders"]);
+        assert_eq!(impact.dbt_project.as_ref().unwrap().name, "jaffle_shop");
+        assert!(!impact.audit.issues.is_empty());
+        assert!(impact.audit.issues.iter().all(|i| {
+            let id = i.node_id.as_deref().unwrap();
+            impact.lineage.nodes.iter().any(|n| n.id == id)
+        }));
+        assert_eq!(
+            impact.audit.issue_counts.values().sum::<usize>(),
+            impact.audit.issues.len()
+        );
+
+        let sarif: serde_json::Value =
+            serde_json::from_str(&export(impact.clone(), ExportFormat::Sarif, None).unwrap())
+                .unwrap();
+        assert_eq!(
+            sarif["runs"][0]["results"].as_array().unwrap().len(),
+            impact.audit.issues.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_project_flags_undefined_source() {
+        let project_dir = temp_project();
+        std::fs::write(
+            project_dir.join("models/stg_orders.sql"),
+            "select * from {{ source('raw', 'orders') }}",
+        )
+        .unwrap();
+
+        let config = test_config(&project_dir);
+
+        let report = validate_project(config).await.unwrap();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        assert!(!report.passed);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].issue_type, IssueType::MissingSource);
+        assert!(report.issues[0].message.contains("raw.orders"));
+    }
+
+    #[test]
+    fn test_raw_sql_dropped_unless_kept() {
+        let project_dir = temp_project();
+        std::fs::write(
+            project_dir.join("models/fct_orders.sql"),
+            "select * from {{ ref('stg_orders') }} join {{ source('shop', 'payments') }}",
+        )
+        .unwrap();
+        let mut config = test_config(&project_dir);
+
+        let dropped = parse_project_files(&config, |_, _| {}).unwrap();
+        config.keep_raw_sql = true;
+        let kept = parse_project_files(&config, |_, _| {}).unwrap();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        assert_eq!(dropped.models[0].refs, vec!["stg_orders"]);
+        assert!(dropped.models[0].raw_sql.is_none());
+        assert!(kept.models[0].raw_sql.is_some());
+    }
+
+    #[test]
+    fn test_source_utilization_loads_dropped_sql() {
+        let project_dir = temp_project();
+        std::fs::write(
+            project_dir.join("models/stg_payments.sql"),
+            "select payment_id, amount from {{ source('shop', 'payments') }}",
+        )
+        .unwrap();
+        std::fs::write(
+            project_dir.join("models/schema.yml"),
+            r#"
+sources:
+  - name: shop
+    tables:
+      - name: payments
+        columns:
+          - name: payment_id
+          - name: amount
+          - name: card_number
+semantic_models:
+  - name: payments
+    model: ref('stg_payments')
+    entities:
+      - name: payment
+        type: primary
+        expr: payment_id
+    measures:
+      - name: amount_paid
+        agg: sum
+        expr: amount
+metrics:
+  - name: amount_paid
+    type: simple
+    type_params:
+      measure: amount_paid
+"#,
+        )
+        .unwrap();
+        let config = ProjectConfig {
+            semantic_layer_type: SemanticLayerType::DbtSemanticLayer,
+            ..test_config(&project_dir)
+        };
+
+        let result = parse_project_files(&config, |_, _| {}).unwrap();
+        let utilization = get_source_utilization(result, "shop.payments".to_string());
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        let utilization = utilization.unwrap();
+        assert_eq!(utilization.used_columns, 2);
+        assert_eq!(utilization.unused_columns, vec!["card_number"]);
+    }
+
+    #[test]
+    fn test_resolved_sql_loads_dropped_sql() {
+        let project_dir = temp_project();
+        std::fs::write(
+            project_dir.join("models/stg_orders.sql"),
+            "select * from {{ source('shop', 'orders') }}",
+        )
+        .unwrap();
+        std::fs::write(
+            project_dir.join("models/orders.sql"),
+            "select * from {{ dbt_ref('stg_orders') }}",
+        )
+        .unwrap();
+        std::fs::write(
+            project_dir.join("models/sources.yml"),
+            "sources:\n  - name: shop\n    schema: raw\n    tables:\n      - name: orders\n",
+        )
+        .unwrap();
+
+        let result = parse_project_files(&test_config(&project_dir), |_, _| {}).unwrap();
+        let staging = resolved_sql(result.clone(), "stg_orders".to_string(), None, None);
+        let orders = resolved_sql(
+            result,
+            "orders".to_string(),
+            Some(vec!["dbt_ref".to_string()]),
+            None,
+        );
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        assert_eq!(staging.unwrap(), "select * from raw.orders");
+        assert_eq!(orders.unwrap(), "select * from stg_orders");
+    }
+
+    #[test]
+    fn test_column_test_becomes_test_node() {
+        let project_dir = temp_project();
+        std::fs::write(
+            project_dir.join("models/orders.sql"),
+            "select 1 as order_id",
+        )
+        .unwrap();
+        std::fs::write(
+            project_dir.join("models/schema.yml"),
+            r#"
+models:
+  - name: orders
+    columns:
+      - name: order_id
+        tests:
+          - not_null
+          - relationships:
+              to: ref('customers')
+              field: id
+"#,
+        )
+        .unwrap();
+        let mut config = test_config(&project_dir);
+
+        let without = parse_project_files(&config, |_, _| {}).unwrap();
+        config.include_tests = true;
+        let with = parse_project_files(&config, |_, _| {}).unwrap();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        let is_test = |n: &&LineageNode| n.node_type == LineageNodeType::Test;
+        assert!(!without.lineage.nodes.iter().any(|n| is_test(&n)));
+
+        let tests: Vec<&LineageNode> = with.lineage.nodes.iter().filter(is_test).collect();
+        assert_eq!(tests.len(), 2);
+        let not_null = tests
+            .iter()
+            .find(|n| n.name == "not_null_orders_order_id")
+            .unwrap();
+        let model = with
+            .lineage
+            .nodes
+            .iter()
+            .find(|n| n.name == "orders")
+            .unwrap();
+        let edge = with
+            .lineage
+            .edges
+            .iter()
+            .find(|e| e.source == not_null.id)
+            .unwrap();
+        assert_eq!(edge.target, model.id);
+        assert_eq!(edge.edge_type, LineageEdgeType::TestToModel);
+        assert_eq!(edge.label.as_deref(), Some("order_id"));
+
+        // Tests neither need descriptions nor count as using the model
+        let flagged = |issue_type: IssueType| -> Vec<Option<String>> {
+            with.audit
+                .issues
+                .iter()
+                .filter(|i| i.issue_type == issue_type)
+                .map(|i| i.node_id.clone())
+                .collect()
+        };
+        assert!(!flagged(IssueType::MissingDescription)
+            .iter()
+            .any(|id| id.as_deref() == Some(not_null.id.as_str())));
+        assert!(flagged(IssueType::OrphanedModel).contains(&Some(model.id.clone())));
+    }
+
+    #[test]
+    fn test_scope_to_tag_keeps_only_tagged_models() {
+        let project_dir = temp_project();
+        std::fs::write(
+            project_dir.join("models/fct_revenue.sql"),
+            "select * from {{ source('billing', 'invoices') }}",
+        )
+        .unwrap();
+        std::fs::write(
+            project_dir.join("models/fct_sessions.sql"),
+            "select * from {{ source('web', 'events') }}",
+        )
+        .unwrap();
+        std::fs::write(
+            project_dir.join("models/schema.yml"),
+            r#"
+models:
+  - name: fct_revenue
+    tags: [finance]
+  - name: fct_sessions
+    tags: [marketing]
+sources:
+  - name: billing
+    tables:
+      - name: invoices
+  - name: web
+    tables:
+      - name: events
+semantic_models:
+  - name: revenue
+    model: ref('fct_revenue')
+    measures:
+      - name: invoice_total
+        agg: sum
+  - name: sessions
+    model: ref('fct_sessions')
+    measures:
+      - name: session_count
+        agg: count
+metrics:
+  - name: revenue
+    type: simple
+    type_params:
+      measure: invoice_total
+  - name: sessions
+    type: simple
+    type_params:
+      measure: session_count
+  - name: revenue_per_session
+    type: derived
+    type_params:
+      expr: revenue / sessions
+      metrics: [revenue, sessions]
+"#,
+        )
+        .unwrap();
+        let config = ProjectConfig {
+            semantic_layer_type: SemanticLayerType::DbtSemanticLayer,
+            scope: Some(Scope::Tags(vec!["finance".to_string()])),
+            ..test_config(&project_dir)
+        };
+
+        let result = parse_project_files(&config, |_, _| {}).unwrap();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        let names = |node_type: LineageNodeType| -> Vec<String> {
+            let mut names: Vec<String> = result
+                .lineage
+                .nodes
+                .iter()
+                .filter(|n| n.node_type == node_type)
+                .map(|n| n.name.clone())
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(names(LineageNodeType::Model), vec!["fct_revenue"]);
+        assert_eq!(names(LineageNodeType::Source), vec!["invoices"]);
+        // The derived metric still has one input in scope
+        assert_eq!(
+            names(LineageNodeType::Metric),
+            vec!["revenue", "revenue_per_session"]
+        );
+    }
+
+    #[test]
+    fn test_scope_to_path_keeps_upstream_models_outside_it() {
+        let project_dir = temp_project();
+        std::fs::create_dir_all(project_dir.join("models/staging")).unwrap();
+        std::fs::create_dir_all(project_dir.join("models/marts")).unwrap();
+        std::fs::write(
+            project_dir.join("models/staging/stg_orders.sql"),
+            "select * from {{ source('shop', 'orders') }}",
+        )
+        .unwrap();
+        std::fs::write(
+            project_dir.join("models/marts/fct_orders.sql"),
+            "select * from {{ ref('stg_orders') }}",
+        )
+        .unwrap();
+        std::fs::write(
+            project_dir.join("models/staging/stg_order_audit.sql"),
+            "select * from {{ ref('fct_orders') }}",
+        )
+        .unwrap();
+        std::fs::write(
+            project_dir.join("models/sources.yml"),
+            "sources:\n  - name: shop\n    tables:\n      - name: orders\n",
+        )
+        .unwrap();
+        let config = ProjectConfig {
+            scope: Some(Scope::PathPrefix("models/marts".to_string())),
+            ..test_config(&project_dir)
+        };
+
+        let result = parse_project_files(&config, |_, _| {}).unwrap();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        let mut models: Vec<&str> = result.models.iter().map(|m| m.name.as_str()).collect();
+        models.sort();
+        assert_eq!(models, vec!["fct_orders", "stg_orders"]);
+        assert_eq!(result.sources.len(), 1);
+        assert_eq!(result.lineage.edges.len(), 2);
+        assert!(result
+            .lineage
+            .nodes
+            .iter()
+            .all(|n| n.name != "stg_order_audit"));
+    }
+
+    #[test]
+    fn test_blast_radius_ranks_deep_upstream_first() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("order_count", LineageNodeType::Metric),
+            node("fct_orders", LineageNodeType::Model),
+            node("int_orders", LineageNodeType::Model),
+            node("stg_orders", LineageNodeType::Model),
+            node("raw_orders", LineageNodeType::Source),
+            node("raw_events", LineageNodeType::Source),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue", "fct_orders", LineageEdgeType::MetricToMeasure),
+            edge("fct_orders", "int_orders", LineageEdgeType::ModelToModel),
+            edge("int_orders", "stg_orders", LineageEdgeType::ModelToModel),
+            edge("stg_orders", "raw_orders", LineageEdgeType::ModelToSource),
+            edge(
+                "order_count",
+                "raw_events",
+                LineageEdgeType::MetricToMeasure,
+            ),
+        ];
+
+        let ranking = blast_radius(parse_result).unwrap();
+
+        assert_eq!(ranking[0].metric_name, "revenue");
+        assert_eq!(ranking[0].upstream_count, 4);
+        assert_eq!(ranking[1].metric_name, "order_count");
+        assert_eq!(ranking[1].upstream_count, 1);
+    }
+
+    #[test]
+    fn test_explain_metric_names_measure_model_and_source() {
+        let with = |mut node: LineageNode, key: &str, value: &str| {
+            node.metadata
+                .insert(key.to_string(), serde_json::json!(value));
+            node
+        };
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            with(
+                node("revenue", LineageNodeType::Metric),
+                "metric_type",
+                "simple",
+            ),
+            with(
+                node("margin", LineageNodeType::Metric),
+                "metric_type",
+                "derived",
+            ),
+            with(
+                node("cost", LineageNodeType::Metric),
+                "metric_type",
+                "simple",
+            ),
+            with(
+                node("order_total", LineageNodeType::Measure),
+                "semantic_model",
+                "orders",
+            ),
+            node("order_id", LineageNodeType::Entity),
+            node("fct_orders", LineageNodeType::Model),
+            with(
+                node("orders", LineageNodeType::Source),
+                "source_name",
+                "raw",
+            ),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue", "order_total", LineageEdgeType::MetricToMeasure),
+            edge("order_total", "order_id", LineageEdgeType::MeasureToEntity),
+            edge("order_id", "fct_orders", LineageEdgeType::EntityToModel),
+            edge("fct_orders", "orders", LineageEdgeType::ModelToSource),
+            edge("margin", "revenue", LineageEdgeType::MetricToMetric),
+            edge("margin", "cost", LineageEdgeType::MetricToMetric),
+        ];
+
+        let revenue = explain_metric(parse_result.clone(), "revenue".to_string()).unwrap();
+        assert_eq!(
+            revenue,
+            "revenue is a simple metric derived from measure order_total in semantic model \
+             orders, built on model fct_orders from source raw.orders."
+        );
+
+        let margin = explain_metric(parse_result.clone(), "margin".to_string()).unwrap();
+        assert_eq!(
+            margin,
+            "margin is a derived metric computed from metrics revenue and cost."
+        );
+
+        assert!(matches!(
+            explain_metric(parse_result, "missing".to_string()),
+            Err(CommandError::NodeNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_metric_tree_nests_upstream_and_refs_shared_nodes() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("order_total", LineageNodeType::Measure),
+            node("ordered_at", LineageNodeType::Dimension),
+            node("order_id", LineageNodeType::Entity),
+            node("fct_orders", LineageNodeType::Model),
+            node("raw_orders", LineageNodeType::Source),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue", "order_total", LineageEdgeType::MetricToMeasure),
+            edge("order_total", "order_id", LineageEdgeType::MeasureToEntity),
+            edge("order_id", "fct_orders", LineageEdgeType::EntityToModel),
+            edge("fct_orders", "raw_orders", LineageEdgeType::ModelToSource),
+            edge("revenue", "ordered_at", LineageEdgeType::MetricToDimension),
+            edge("ordered_at", "order_id", LineageEdgeType::DimensionToEntity),
+        ];
+
+        let tree = metric_tree(parse_result, "revenue".to_string()).unwrap();
+
+        fn depth(node: &MetricTreeNode) -> usize {
+            1 + node.children.iter().map(depth).max().unwrap_or(0)
+        }
+        assert_eq!(tree.node_type, LineageNodeType::Metric);
+        assert!(!tree.is_ref);
+        assert_eq!(depth(&tree), 5);
+
+        let measure = &tree.children[0];
+        assert_eq!(measure.children[0].node_type, LineageNodeType::Entity);
+        assert_eq!(measure.children[0].children[0].name, "fct_orders");
+
+        let shared = &tree.children[1].children[0];
+        assert_eq!(shared.name, "order_id");
+        assert_eq!(shared.node_type, LineageNodeType::Entity);
+        assert!(shared.is_ref);
+        assert!(shared.children.is_empty());
+    }
+
+    #[test]
+    fn test_graph_endpoints() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("order_count", LineageNodeType::Metric),
+            node("fct_orders", LineageNodeType::Model),
+            node("raw_orders", LineageNodeType::Source),
+            node("raw_customers", LineageNodeType::Source),
+            node("ordered_at", LineageNodeType::Dimension),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue", "fct_orders", LineageEdgeType::MetricToMeasure),
+            edge("order_count", "fct_orders", LineageEdgeType::MetricToMeasure),
+            edge("fct_orders", "raw_orders", LineageEdgeType::ModelToSource),
+            edge(
+                "fct_orders",
+                "raw_customers",
+                LineageEdgeType::ModelToSource,
+            ),
+            edge(
+                "ordered_at",
+                "fct_orders",
+                LineageEdgeType::DimensionToModel,
+            ),
+        ];
+
+        let endpoints = graph_endpoints(parse_result).unwrap();
+
+        assert_eq!(endpoints.roots, vec!["raw_customers", "raw_orders"]);
+        assert_eq!(endpoints.leaves, vec!["order_count", "revenue"]);
+    }
+
+    #[test]
+    fn test_topological_order_linear_chain() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("fct_orders", LineageNodeType::Model),
+            node("stg_orders", LineageNodeType::Model),
+            node("raw_orders", LineageNodeType::Source),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue", "fct_orders", LineageEdgeType::MetricToMeasure),
+            edge("fct_orders", "stg_orders", LineageEdgeType::ModelToModel),
+            edge("stg_orders", "raw_orders", LineageEdgeType::ModelToSource),
+        ];
+
+        let order = topological_order(parse_result.clone(), None).unwrap();
+        assert_eq!(order, vec!["raw_orders", "stg_orders", "fct_orders"]);
+
+        let with_semantic = topological_order(parse_result, Some(true)).unwrap();
+        assert_eq!(with_semantic.last().map(|s| s.as_str()), Some("revenue"));
+    }
+
+    #[test]
+    fn test_topological_order_reports_cycle() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("downstream", LineageNodeType::Model),
+            node("a", LineageNodeType::Model),
+            node("b", LineageNodeType::Model),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("downstream", "a", LineageEdgeType::ModelToModel),
+            edge("a", "b", LineageEdgeType::ModelToModel),
+            edge("b", "a", LineageEdgeType::ModelToModel),
+        ];
+
+        let err = topological_order(parse_result, None).unwrap_err();
+        assert!(matches!(err, CommandError::CycleDetected(_)));
+        assert!(
+            err.message().contains("'a'") || err.message().contains("'b'"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_metric_completeness_reports_missing_link() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("order_total", LineageNodeType::Measure),
+            node("fct_orders", LineageNodeType::Model),
+            node("raw_orders", LineageNodeType::Source),
+            node("churn", LineageNodeType::Metric),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue", "order_total", LineageEdgeType::MetricToMeasure),
+            edge("order_total", "fct_orders", LineageEdgeType::MeasureToModel),
+            edge("fct_orders", "raw_orders", LineageEdgeType::ModelToSource),
+        ];
+
+        let completeness = metric_completeness(parse_result).unwrap();
+        let find = |name: &str| completeness.iter().find(|c| c.metric_name == name).unwrap();
+
+        assert!(find("revenue").has_source_path);
+        assert_eq!(find("revenue").missing_link, None);
+        assert!(!find("churn").has_source_path);
+        assert_eq!(
+            find("churn").missing_link.as_deref(),
+            Some("measure not found")
+        );
+    }
+
+    #[test]
+    fn test_node_neighbors_is_one_hop() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("order_total", LineageNodeType::Measure),
+            node("fct_orders", LineageNodeType::Model),
+            node("stg_orders", LineageNodeType::Model),
+            node("raw_orders", LineageNodeType::Source),
+            node("customer_count", LineageNodeType::Metric),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue", "order_total", LineageEdgeType::MetricToMeasure),
+            edge("order_total", "fct_orders", LineageEdgeType::MeasureToModel),
+            edge("fct_orders", "stg_orders", LineageEdgeType::ModelToModel),
+            edge("stg_orders", "raw_orders", LineageEdgeType::ModelToSource),
+        ];
+
+        let neighbors = node_neighbors(parse_result.clone(), "fct_orders".to_string()).unwrap();
+
+        let mut names: Vec<&str> = neighbors
+            .lineage
+            .nodes
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["fct_orders", "order_total", "stg_orders"]);
+        assert_eq!(neighbors.lineage.edges.len(), 2);
+        assert!(matches!(
+            node_neighbors(parse_result, "missing".to_string()),
+            Err(CommandError::NodeNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_impacted_metrics_for_source() {
+        let mut parse_result = ParseResult::default();
+        let mut raw_orders = node("orders", LineageNodeType::Source);
+        raw_orders
+            .metadata
+            .insert("source_name".to_string(), serde_json::json!("raw"));
+        parse_result.lineage.nodes = vec![
+            raw_orders,
+            node("fct_orders", LineageNodeType::Model),
+            node("order_total", LineageNodeType::Measure),
+            node("revenue", LineageNodeType::Metric),
+            node("average_order_value", LineageNodeType::Metric),
+            node("customer_count", LineageNodeType::Metric),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("fct_orders", "orders", LineageEdgeType::ModelToSource),
+            edge("order_total", "fct_orders", LineageEdgeType::MeasureToModel),
+            edge("revenue", "order_total", LineageEdgeType::MetricToMeasure),
+            edge("average_order_value", "order_total", LineageEdgeType::MetricToMeasure),
+        ];
+
+        let metrics = impacted_metrics(parse_result.clone(), "raw.orders".to_string()).unwrap();
+
+        assert_eq!(metrics, vec!["average_order_value", "revenue"]);
+        assert!(impacted_metrics(parse_result, "raw.customers".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_node_issues_for_metric() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("fct_orders", LineageNodeType::Model),
+        ];
+        let audit = LineageAnalyzer::new().analyze(&parse_result.lineage, &[], &[], &[], &[]);
+        assert_eq!(audit.issues.len(), 3);
+
+        let issues = node_issues(audit, "revenue".to_string()).unwrap();
+
+        let mut issue_types: Vec<_> = issues
+            .iter()
+            .map(|i| format!("{:?}", i.issue_type))
+            .collect();
+        issue_types.sort();
+        assert_eq!(issue_types, vec!["MissingDescription", "OrphanedMetric"]);
+        assert!(issues.iter().all(|i| i.message.contains("'revenue'")));
+    }
+
+    #[test]
+    fn test_filter_edges_keeps_metric_to_metric() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("profit", LineageNodeType::Metric),
+            node("revenue", LineageNodeType::Metric),
+            node("order_total", LineageNodeType::Measure),
+            node("fct_orders", LineageNodeType::Model),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("profit", "revenue", LineageEdgeType::MetricToMetric),
+            edge("revenue", "order_total", LineageEdgeType::MetricToMeasure),
+            edge("order_total", "fct_orders", LineageEdgeType::MeasureToModel),
+        ];
+
+        let filtered = filter_edges(parse_result, vec![LineageEdgeType::MetricToMetric]).unwrap();
+
+        assert_eq!(filtered.lineage.edges.len(), 1);
+        assert_eq!(
+            filtered.lineage.edges[0].edge_type,
+            LineageEdgeType::MetricToMetric
+        );
+        let mut names: Vec<&str> = filtered
+            .lineage
+            .nodes
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["profit", "revenue"]);
+    }
+
+    #[test]
+    fn test_measure_usage_counts_shared_measure() {
+        let mut parse_result = ParseResult::default();
+        parse_result.lineage.nodes = vec![
+            node("revenue", LineageNodeType::Metric),
+            node("revenue_growth", LineageNodeType::Metric),
+            node("order_total", LineageNodeType::Measure),
+            node("order_count", LineageNodeType::Measure),
+        ];
+        parse_result.lineage.edges = vec![
+            edge("revenue", "order_total", LineageEdgeType::MetricToMeasure),
+            edge("revenue_growth", "order_total", LineageEdgeType::MetricToMeasure),
+        ];
+
+        let usage = measure_usage(parse_result).unwrap();
+
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].measure_name, "order_total");
+        assert_eq!(usage[0].usage_count, 2);
+        assert_eq!(usage[0].metrics, vec!["revenue", "revenue_growth"]);
+        assert_eq!(usage[1].usage_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_parse_projects_namespaces_and_merges_by_name() {
+        let write_project = |name: &str, mart: &str| {
+            let project_dir = temp_project();
+            std::fs::write(
+                project_dir.join("dbt_project.yml"),
+                format!("name: {}\n", name),
+            )
+            .unwrap();
+            std::fs::write(
+                project_dir.join("models/stg_orders.sql"),
+                "select * from {{ source('shop', 'orders') }}",
+            )
+            .unwrap();
+            std::fs::write(
+                project_dir.join(format!("models/{}.sql", mart)),
+                "select * from {{ ref('stg_orders') }}",
+            )
+            .unwrap();
+            std::fs::write(
+                project_dir.join("models/sources.yml"),
+                "sources:\n  - name: shop\n    tables:\n      - name: orders\n",
+            )
+            .unwrap();
+            ProjectConfig {
+                deterministic_ids: true,
+                ..test_config(&project_dir)
+            }
+        };
+        let configs = vec![
+            write_project("finance", "fct_invoices"),
+            write_project("marketing", "fct_campaigns"),
+        ];
+        let checkouts = vec![
+            write_project("finance", "fct_invoices"),
+            write_project("finance", "fct_invoices"),
+        ];
+
+        let separate = parse_projects(configs.clone(), None).await.unwrap();
+        let merged = parse_projects(configs.clone(), Some(true)).await.unwrap();
+        let both_checkouts = parse_projects(checkouts.clone(), None).await.unwrap();
+        for config in configs.iter().chain(&checkouts) {
+            std::fs::remove_dir_all(&config.dbt_project_path).unwrap();
+        }
+
+        assert!(separate.success);
+        assert_eq!(separate.models.len(), 4);
+        assert_eq!(separate.lineage.nodes.len(), 6);
+        assert_eq!(separate.lineage.edges.len(), 4);
+        let ids: HashSet<&str> = separate
+            .lineage
+            .nodes
+            .iter()
+            .map(|n| n.id.as_str())
+            .collect();
+        assert_eq!(ids.len(), 6);
+        assert!(ids.iter().any(|id| id.starts_with("0::")));
+        assert!(ids.iter().any(|id| id.starts_with("1::")));
+
+        // Two checkouts of one project keep their nodes apart
+        let checkout_ids: HashSet<&str> = both_checkouts
+            .lineage
+            .nodes
+            .iter()
+            .map(|n| n.id.as_str())
+            .collect();
+        assert_eq!(checkout_ids.len(), 6);
+
+        // The shared staging model and source collapse into the finance ones
+        assert_eq!(merged.lineage.nodes.len(), 4);
+        assert_eq!(merged.lineage.edges.len(), 3);
+        let stg_orders = merged
+            .lineage
+            .nodes
+            .iter()
+            .find(|n| n.name == "stg_orders")
+            .unwrap();
+        assert_eq!(stg_orders.metadata["project"], "finance");
+        assert_eq!(
+            merged
+                .lineage
+                .edges
+                .iter()
+                .filter(|e| e.target == stg_orders.id)
+                .count(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merged_lookups_take_project_qualified_names() {
+        let write_project = |name: &str, schema: &str| {
+            let project_dir = temp_project();
+            std::fs::write(
+                project_dir.join("dbt_project.yml"),
+                format!("name: {}\n", name),
+            )
+            .unwrap();
+            std::fs::write(
+                project_dir.join("models/stg_orders.sql"),
+                "select * from {{ source('shop', 'orders') }}",
+            )
+            .unwrap();
+            std::fs::write(
+                project_dir.join("models/sources.yml"),
+                format!(
+                    "sources:\n  - name: shop\n    schema: {}\n    tables:\n      - name: orders\n",
+                    schema
+                ),
+            )
+            .unwrap();
+            test_config(&project_dir)
+        };
+        let configs = vec![
+            write_project("finance", "billing"),
+            write_project("marketing", "crm"),
+        ];
+
+        let merged = parse_projects(configs.clone(), None).await.unwrap();
+        let ambiguous = resolved_sql(merged.clone(), "stg_orders".to_string(), None, None);
+        let marketing = resolved_sql(
+            merged.clone(),
+            "marketing::stg_orders".to_string(),
+            None,
+            None,
+        );
+        for config in &configs {
+            std::fs::remove_dir_all(&config.dbt_project_path).unwrap();
+        }
+
+        assert!(merged
+            .models
+            .iter()
+            .any(|m| m.project.as_deref() == Some("marketing")));
+        assert!(matches!(ambiguous, Err(CommandError::AmbiguousNode(_))));
+        assert_eq!(marketing.unwrap(), "select * from crm.orders");
+        assert!(matches!(
+            get_impact_analysis(merged.clone(), "stg_orders".to_string()).await,
+            Err(CommandError::AmbiguousNode(_))
+        ));
+        let impact = get_impact_analysis(merged, "finance::stg_orders".to_string())
+            .await
+            .unwrap();
+        assert!(impact
+            .lineage
+            .nodes
+            .iter()
+            .all(|n| n.metadata["project"] == "finance"));
+    }
+
+    #[test]
+    fn test_fail_on_marks_parse_failed() {
+        let project_dir = temp_project();
+        std::fs::write(
+            project_dir.join("models/fct_orders.sql"),
+            "select 1 as amount",
+        )
+        .unwrap();
+        std::fs::write(
+            project_dir.join("models/schema.yml"),
+            r#"
+semantic_models:
+  - name: orders
+    model: ref('fct_orders')
+    measures:
+      - name: order_total
+        agg: summ
+"#,
+        )
+        .unwrap();
+        let mut config = ProjectConfig {
+            semantic_layer_type: SemanticLayerType::DbtSemanticLayer,
+            ..test_config(&project_dir)
+        };
+
+        let lenient = parse_project_files(&config, |_, _| {}).unwrap();
+        config.fail_on = Some(IssueSeverity::Warning);
+        let gated = parse_project_files(&config, |_, _| {}).unwrap();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        assert!(lenient.success);
+        assert!(lenient
+            .audit
+            .issues
+            .iter()
+            .any(|i| i.issue_type == IssueType::InvalidAggregation));
+        assert!(!gated.success);
+        assert!(gated
+            .errors
+            .iter()
+            .any(|e| e.contains("at or above Warning severity")));
+    }
+
+    #[test]
+    fn test_unreadable_model_file_is_reported() {
+        let project_dir = temp_project();
+        std::fs::write(project_dir.join("models/orders.sql"), "select 1 as id").unwrap();
+        std::fs::write(
+            project_dir.join("models/legacy.sql"),
+            [0xff, 0xfe, 0x00, 0x80],
+        )
+        .unwrap();
+        std::fs::create_dir_all(project_dir.join("seeds")).unwrap();
+        std::fs::write(project_dir.join("seeds/seeds.yml"), [0xff, 0xfe]).unwrap();
+        std::fs::write(project_dir.join("seeds/broken.yml"), "seeds: [").unwrap();
+        std::fs::create_dir_all(project_dir.join("snapshots")).unwrap();
+        std::fs::write(project_dir.join("snapshots/legacy.sql"), [0xff, 0xfe]).unwrap();
+        std::fs::create_dir_all(project_dir.join("tests")).unwrap();
+        std::fs::write(project_dir.join("tests/legacy_check.sql"), [0xff, 0xfe]).unwrap();
+        std::fs::write(
+            project_dir.join("tests/orders_check.sql"),
+            "select * from {{ ref('orders') }} where id is null",
+        )
+        .unwrap();
+        let config = ProjectConfig {
+            include_tests: true,
+            ..test_config(&project_dir)
+        };
+
+        let result = parse_project_files(&config, |_, _| {}).unwrap();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        assert_eq!(result.models.len(), 1);
+        assert_eq!(result.tests.len(), 1);
+        for file in [
+            "models/legacy.sql",
+            "seeds/seeds.yml",
+            "seeds/broken.yml",
+            "snapshots/legacy.sql",
+            "tests/legacy_check.sql",
+        ] {
+            assert!(
+                result
+                    .warnings
+                    .iter()
+                    .any(|w| w.contains(file) && w.contains("could not be read or parsed")),
+                "{}",
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn test_same_model_name_in_two_model_paths() {
+        let project_dir = temp_project();
+        std::fs::create_dir_all(project_dir.join("legacy")).unwrap();
+        std::fs::write(
+            project_dir.join("dbt_project.yml"),
+            "name: jaffle_shop\nmodel-paths: [\"models\", \"legacy\"]\n",
+        )
+        .unwrap();
+        std::fs::write(project_dir.join("models/stg_orders.sql"), "select 1 as id").unwrap();
+        std::fs::write(project_dir.join("legacy/stg_orders.sql"), "select 2 as id").unwrap();
+        std::fs::write(
+            project_dir.join("models/fct_orders.sql"),
+            "select * from {{ ref('stg_orders') }}",
+        )
+        .unwrap();
+        let config = test_config(&project_dir);
+
+        let result = parse_project_files(&config, |_, _| {}).unwrap();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        assert_eq!(result.models.len(), 3);
+        let stg_orders: Vec<_> = result
+            .lineage
+            .nodes
+            .iter()
+            .filter(|n| n.name == "stg_orders")
+            .collect();
+        assert_eq!(stg_orders.len(), 1);
+        assert_eq!(result.lineage.edges.len(), 1);
+
+        let duplicate = result
+            .audit
+            .issues
+            .iter()
+            .find(|i| i.issue_type == IssueType::DuplicateDefinition)
+            .unwrap();
+        assert_eq!(duplicate.severity, IssueSeverity::Error);
+        assert_eq!(
+            duplicate.node_id.as_deref(),
+            Some(stg_orders[0].id.as_str())
+        );
+        assert!(duplicate.message.contains("models/stg_orders.sql"));
+        assert!(duplicate.message.contains("legacy/stg_orders.sql"));
+    }
 }
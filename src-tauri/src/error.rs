@@ -0,0 +1,64 @@
+//! Errors returned by Tauri commands
+
+use serde::Serialize;
+use std::fmt;
+
+/// Error returned to the frontend, serialized as
+/// `{ "kind": "NodeNotFound", "message": "Node 'x' not found" }` so the UI
+/// can branch on `kind` and show `message`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "message")]
+pub enum CommandError {
+    /// A configured path does not exist or cannot be resolved
+    PathNotFound(String),
+    /// A project file could not be read or parsed
+    ParseFailed(String),
+    /// The requested node is not in the lineage graph
+    NodeNotFound(String),
+    /// A node name matches more than one node in the lineage graph
+    AmbiguousNode(String),
+    /// The lineage graph contains a cycle where none is allowed
+    CycleDetected(String),
+    /// Anything else
+    Internal(String),
+}
+
+impl CommandError {
+    /// Human-readable description of the error
+    pub fn message(&self) -> &str {
+        match self {
+            CommandError::PathNotFound(message)
+            | CommandError::ParseFailed(message)
+            | CommandError::NodeNotFound(message)
+            | CommandError::AmbiguousNode(message)
+            | CommandError::CycleDetected(message)
+            | CommandError::Internal(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_not_found_serializes_as_tagged_object() {
+        let error = CommandError::NodeNotFound("Node 'revenue' not found".to_string());
+
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "kind": "NodeNotFound",
+                "message": "Node 'revenue' not found"
+            })
+        );
+    }
+}
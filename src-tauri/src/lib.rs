@@ -1,11 +1,22 @@
 //! Semantic Layer Metrics Lineage Tracer - Tauri Backend
 
+pub mod cache;
 pub mod commands;
+pub mod error;
 pub mod lineage;
 pub mod parsers;
+pub mod paths;
 pub mod types;
 
-use commands::{get_impact_analysis, get_metric_lineage, parse_project, search_nodes};
+use commands::{
+    annotate_nodes, blast_radius, collapse_sources, diff_lineage, dimension_availability,
+    explain_metric, export, export_dbt_docs, export_graphml, export_lineage_parquet, export_sarif,
+    filter_edges, get_impact_analysis, get_metric_lineage, get_orphan_clusters,
+    get_source_utilization, graph_endpoints, graph_stats, impacted_metrics, measure_columns,
+    measure_usage, metric_completeness, metric_distance, metric_tree, node_issues, node_neighbors,
+    parse_project, parse_projects, render_svg, resolved_sql, search_nodes,
+    suggest_metric_consolidation, topological_order, unused_metrics, validate_project,
+};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -27,6 +38,37 @@ pub fn run() {
             get_metric_lineage,
             get_impact_analysis,
             search_nodes,
+            get_orphan_clusters,
+            get_source_utilization,
+            export_graphml,
+            diff_lineage,
+            measure_usage,
+            topological_order,
+            validate_project,
+            metric_completeness,
+            filter_edges,
+            node_issues,
+            impacted_metrics,
+            node_neighbors,
+            dimension_availability,
+            export_lineage_parquet,
+            graph_endpoints,
+            blast_radius,
+            metric_tree,
+            suggest_metric_consolidation,
+            export_sarif,
+            render_svg,
+            resolved_sql,
+            parse_projects,
+            measure_columns,
+            graph_stats,
+            export_dbt_docs,
+            collapse_sources,
+            explain_metric,
+            annotate_nodes,
+            unused_metrics,
+            metric_distance,
+            export,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
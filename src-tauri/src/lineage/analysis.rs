@@ -1,16 +1,92 @@
 //! Lineage analysis and audit functionality
 
+use crate::parsers::{parse_expr_identifiers, parse_filter_references};
 use crate::types::{
-    AuditIssue, AuditResult, AuditSummary, DbtModel, DbtSource, IssueSeverity, IssueType,
-    LineageGraph, LineageNodeType, Metric, SemanticModel,
+    AuditIssue, AuditResult, AuditSummary, BlastRadius, CompletenessPolicy, ConsolidationHint,
+    DbtColumn, DbtFreshnessRule, DbtModel, DbtSeed, DbtSnapshot, DbtSource, FilterReferenceKind,
+    GraphStats, IssueSeverity, IssueType, LineageEdgeType, LineageGraph, LineageNode,
+    LineageNodeType, MeasureColumn, MeasureUsage, Metric, MetricCompleteness, MetricTreeNode,
+    SemanticModel, SourceUtilization,
 };
-use std::collections::HashSet;
+use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-pub struct LineageAnalyzer;
+/// Aggregations MetricFlow accepts for a measure's `agg`
+const METRICFLOW_AGGS: &[&str] = &[
+    "sum",
+    "max",
+    "min",
+    "count",
+    "count_distinct",
+    "sum_boolean",
+    "average",
+    "percentile",
+    "median",
+];
+
+/// Time grains from finest to coarsest
+const TIME_GRAINS: &[&str] = &[
+    "nanosecond",
+    "microsecond",
+    "millisecond",
+    "second",
+    "minute",
+    "hour",
+    "day",
+    "week",
+    "month",
+    "quarter",
+    "year",
+];
+
+pub struct LineageAnalyzer {
+    require_metric_owner: bool,
+    severity_overrides: HashMap<IssueType, IssueSeverity>,
+    allowed_aggs: Option<Vec<String>>,
+    diagnostics: Vec<AuditIssue>,
+    completeness_policy: CompletenessPolicy,
+}
 
 impl LineageAnalyzer {
     pub fn new() -> Self {
-        Self
+        Self {
+            require_metric_owner: false,
+            severity_overrides: HashMap::new(),
+            allowed_aggs: None,
+            diagnostics: Vec::new(),
+            completeness_policy: CompletenessPolicy::default(),
+        }
+    }
+
+    /// Replace the default severity assigned to the given issue types
+    pub fn with_severity_overrides(mut self, overrides: HashMap<IssueType, IssueSeverity>) -> Self {
+        self.severity_overrides = overrides;
+        self
+    }
+
+    /// Warn about measures whose `agg` is not in `allowed` (no restriction when `None`)
+    pub fn with_allowed_aggs(mut self, allowed: Option<Vec<String>>) -> Self {
+        self.allowed_aggs = allowed;
+        self
+    }
+
+    /// Include issues found while building the graph in the audit
+    pub fn with_diagnostics(mut self, diagnostics: Vec<AuditIssue>) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    /// Change what a metric's lineage needs to count towards the completeness
+    /// score (by default, only a path to a source)
+    pub fn with_completeness_policy(mut self, policy: CompletenessPolicy) -> Self {
+        self.completeness_policy = policy;
+        self
+    }
+
+    /// Flag metrics that do not declare `meta.owner`
+    pub fn with_required_owner(mut self, required: bool) -> Self {
+        self.require_metric_owner = required;
+        self
     }
 
     /// Analyze the lineage graph and generate audit results
@@ -22,7 +98,7 @@ impl LineageAnalyzer {
         semantic_models: &[SemanticModel],
         metrics: &[Metric],
     ) -> AuditResult {
-        let mut issues = Vec::new();
+        let mut issues = self.diagnostics.clone();
 
         // Check for missing descriptions
         issues.extend(self.check_missing_descriptions(graph));
@@ -34,28 +110,134 @@ impl LineageAnalyzer {
         issues.extend(self.check_orphaned_metrics(graph, metrics));
 
         // Check for missing sources
-        issues.extend(self.check_missing_sources(models, sources));
+        issues.extend(self.check_missing_sources(Some(graph), models, sources));
+
+        // Check source freshness rules can actually fire
+        issues.extend(self.check_stale_source_thresholds(graph, sources));
 
         // Check for undocumented columns
-        issues.extend(self.check_undocumented_columns(models));
+        issues.extend(self.check_undocumented_columns(graph, models));
+
+        // Check for columns listed twice in a model's schema.yml
+        issues.extend(self.check_duplicate_columns(graph, models));
 
         // Check for models without tests
-        issues.extend(self.check_models_without_tests(models));
+        issues.extend(self.check_models_without_tests(graph, models));
+
+        // Check incremental models declare a unique_key
+        issues.extend(self.check_incremental_without_unique_key(graph, models));
+
+        // Check for refs to private models from other groups
+        issues.extend(self.check_access_violations(graph, models));
+
+        // Check for source columns no model selects
+        issues.extend(self.check_unused_source_columns(graph, models, sources));
+
+        // Check non-additive measures point at a real time dimension
+        issues.extend(self.check_non_additive_dimensions(graph, semantic_models));
+
+        // Check shared foreign entities have a model they are unique in
+        issues.extend(self.check_entity_cardinality(graph, semantic_models));
+
+        // Check entities shared across semantic models are keyed the same way
+        issues.extend(self.check_entity_expr_consistency(graph, semantic_models));
+
+        // Check measure aggregations are valid and allowed
+        issues.extend(self.check_measure_aggs(graph, semantic_models));
+
+        // Check for measures no metric consumes
+        issues.extend(self.check_unused_measures(graph, semantic_models, metrics));
+
+        // Check metrics do not request a grain finer than their time dimension
+        issues.extend(self.check_grain_consistency(graph, semantic_models, metrics));
+
+        // Check cumulative metrics set a valid window or grain_to_date, not both
+        issues.extend(self.check_cumulative_params(graph, metrics));
+
+        // Check cumulative metrics aggregate a measure joined to the time spine
+        issues.extend(self.check_cumulative_timespine(graph, semantic_models, metrics));
+
+        // Check nothing still depends on a metric marked `meta.deprecated`
+        issues.extend(self.check_deprecated_usage(graph, metrics));
+
+        // Check for derived metric inputs used in `expr` but not declared
+        issues.extend(self.check_derived_metric_refs(graph, metrics));
+
+        // Check measure filters only reference defined dimensions and entities
+        issues.extend(self.check_measure_filters(graph, semantic_models, metrics));
+
+        // Check for metrics without an owner
+        if self.require_metric_owner {
+            issues.extend(self.check_missing_owners(graph, metrics));
+        }
+
+        self.apply_severity_overrides(&mut issues);
+        self.attach_source_locations(graph, models, semantic_models, metrics, &mut issues);
 
         // Calculate summary
         let summary = self.calculate_summary(models, sources, semantic_models, metrics, &issues);
 
         // Calculate scores
-        let completeness_score = self.calculate_completeness_score(graph, metrics, semantic_models);
+        let completeness_score =
+            self.calculate_completeness_score(graph, models, metrics, semantic_models);
         let documentation_coverage = self.calculate_documentation_coverage(graph);
+        let column_documentation_coverage =
+            self.calculate_column_documentation_coverage(models, sources);
         let model_coverage = self.calculate_model_coverage(models, semantic_models);
+        let ownership_coverage = self.calculate_ownership_coverage(metrics);
+
+        // Count issues by type and severity so the UI can show badges without
+        // walking the issue list
+        let mut issue_counts: HashMap<String, usize> = HashMap::new();
+        let mut severity_counts: HashMap<String, usize> = HashMap::new();
+        for issue in &issues {
+            *issue_counts
+                .entry(format!("{:?}", issue.issue_type))
+                .or_default() += 1;
+            *severity_counts
+                .entry(format!("{:?}", issue.severity))
+                .or_default() += 1;
+        }
 
         AuditResult {
             completeness_score,
             documentation_coverage,
+            column_documentation_coverage,
             model_coverage,
+            ownership_coverage,
             issues,
             summary,
+            issue_counts,
+            severity_counts,
+        }
+    }
+
+    /// Run only the structural checks that need no lineage graph: undefined
+    /// model refs and sources, duplicate names and metrics whose inputs do not exist
+    pub fn validate(
+        &self,
+        models: &[DbtModel],
+        sources: &[DbtSource],
+        snapshots: &[DbtSnapshot],
+        seeds: &[DbtSeed],
+        semantic_models: &[SemanticModel],
+        metrics: &[Metric],
+    ) -> Vec<AuditIssue> {
+        let mut issues = Vec::new();
+        issues.extend(self.check_missing_model_refs(models, snapshots, seeds, semantic_models));
+        issues.extend(self.check_missing_sources(None, models, sources));
+        issues.extend(self.check_duplicate_names(models, semantic_models, metrics));
+        issues.extend(self.check_unresolved_metric_inputs(semantic_models, metrics));
+
+        self.apply_severity_overrides(&mut issues);
+        issues
+    }
+
+    fn apply_severity_overrides(&self, issues: &mut [AuditIssue]) {
+        for issue in issues {
+            if let Some(severity) = self.severity_overrides.get(&issue.issue_type) {
+                issue.severity = severity.clone();
+            }
         }
     }
 
@@ -63,6 +245,8 @@ impl LineageAnalyzer {
         graph
             .nodes
             .iter()
+            // Tests are checks on a node, not something users look up
+            .filter(|node| node.node_type != LineageNodeType::Test)
             .filter(|node| node.description.is_none())
             .map(|node| AuditIssue {
                 severity: match node.node_type {
@@ -77,6 +261,7 @@ impl LineageAnalyzer {
                     "Add a description to help users understand what '{}' represents",
                     node.name
                 )),
+                source_location: None,
             })
             .collect()
     }
@@ -93,6 +278,8 @@ impl LineageAnalyzer {
         let referenced_models: HashSet<_> = graph
             .edges
             .iter()
+            // A test on a model doesn't make it used
+            .filter(|e| e.edge_type != LineageEdgeType::TestToModel)
             .filter_map(|e| {
                 graph
                     .nodes
@@ -115,6 +302,7 @@ impl LineageAnalyzer {
                     .find(|n| n.name == m.name && n.node_type == LineageNodeType::Model)
                     .map(|n| n.id.clone()),
                 suggestion: Some("Consider removing unused models or documenting their purpose".to_string()),
+                source_location: None,
             })
             .collect()
     }
@@ -145,11 +333,74 @@ impl LineageAnalyzer {
                 message: format!("Metric '{}' has no connection to any measure", n.name),
                 node_id: Some(n.id.clone()),
                 suggestion: Some("Check the metric definition - it may be missing a measure reference".to_string()),
+                source_location: None,
             })
             .collect()
     }
 
-    fn check_missing_sources(&self, models: &[DbtModel], sources: &[DbtSource]) -> Vec<AuditIssue> {
+    /// Point each issue at the YAML definition of the node it is about, so the
+    /// UI can jump to it. Measures, dimensions and entities resolve to their
+    /// semantic model.
+    fn attach_source_locations(
+        &self,
+        graph: &LineageGraph,
+        models: &[DbtModel],
+        semantic_models: &[SemanticModel],
+        metrics: &[Metric],
+        issues: &mut [AuditIssue],
+    ) {
+        for issue in issues.iter_mut().filter(|i| i.source_location.is_none()) {
+            let Some(node) = issue
+                .node_id
+                .as_ref()
+                .and_then(|id| graph.nodes.iter().find(|n| &n.id == id))
+            else {
+                continue;
+            };
+
+            issue.source_location = match node.node_type {
+                LineageNodeType::Model => models
+                    .iter()
+                    .find(|m| m.name == node.name)
+                    .and_then(|m| m.source_location.clone()),
+                LineageNodeType::Metric => metrics
+                    .iter()
+                    .find(|m| m.name == node.name)
+                    .and_then(|m| m.source_location.clone()),
+                LineageNodeType::Measure | LineageNodeType::Dimension | LineageNodeType::Entity => {
+                    let owner = node.metadata.get("semantic_model").and_then(|v| v.as_str());
+                    semantic_models
+                        .iter()
+                        .find(|sm| Some(sm.name.as_str()) == owner)
+                        .and_then(|sm| sm.source_location.clone())
+                }
+                _ => None,
+            };
+        }
+    }
+
+    /// Id of the graph node of `node_type` named `name`, if there is one
+    fn node_id(
+        &self,
+        graph: &LineageGraph,
+        node_type: LineageNodeType,
+        name: &str,
+    ) -> Option<String> {
+        graph
+            .nodes
+            .iter()
+            .find(|n| n.node_type == node_type && n.name == name)
+            .map(|n| n.id.clone())
+    }
+
+    /// `graph` is `None` when validating without a lineage graph, in which
+    /// case the issues carry no node id
+    fn check_missing_sources(
+        &self,
+        graph: Option<&LineageGraph>,
+        models: &[DbtModel],
+        sources: &[DbtSource],
+    ) -> Vec<AuditIssue> {
         let source_names: HashSet<_> = sources
             .iter()
             .map(|s| format!("{}.{}", s.source_name, s.name))
@@ -168,11 +419,13 @@ impl LineageAnalyzer {
                             "Model '{}' references undefined source '{}'",
                             model.name, key
                         ),
-                        node_id: None,
+                        node_id: graph
+                            .and_then(|g| self.node_id(g, LineageNodeType::Model, &model.name)),
                         suggestion: Some(format!(
                             "Define source '{}' in a schema.yml file",
                             key
                         )),
+                        source_location: None,
                     });
                 }
             }
@@ -181,7 +434,261 @@ impl LineageAnalyzer {
         issues
     }
 
-    fn check_undocumented_columns(&self, models: &[DbtModel]) -> Vec<AuditIssue> {
+    /// Warn about freshness rules that can never alert as intended: an
+    /// `error_after` without a `loaded_at_field` to measure it against, or a
+    /// `warn_after` period longer than the `error_after` period
+    fn check_stale_source_thresholds(
+        &self,
+        graph: &LineageGraph,
+        sources: &[DbtSource],
+    ) -> Vec<AuditIssue> {
+        let minutes = |rule: &DbtFreshnessRule| {
+            let unit = match rule.period.to_lowercase().as_str() {
+                "minute" => 1,
+                "hour" => 60,
+                "day" => 60 * 24,
+                _ => return None,
+            };
+            Some(i64::from(rule.count) * unit)
+        };
+
+        let mut issues = Vec::new();
+
+        for source in sources {
+            let Some(ref freshness) = source.freshness else {
+                continue;
+            };
+            let key = format!("{}.{}", source.source_name, source.name);
+            let node_id = graph
+                .nodes
+                .iter()
+                .find(|n| {
+                    n.node_type == LineageNodeType::Source
+                        && n.name == source.name
+                        && n.metadata.get("source_name").and_then(|v| v.as_str())
+                            == Some(source.source_name.as_str())
+                })
+                .map(|n| n.id.clone());
+
+            if freshness.error_after.is_some() && source.loaded_at_field.is_none() {
+                issues.push(AuditIssue {
+                    severity: IssueSeverity::Warning,
+                    issue_type: IssueType::InvalidFreshness,
+                    message: format!(
+                        "Source '{}' declares error_after but no loaded_at_field",
+                        key
+                    ),
+                    node_id: node_id.clone(),
+                    suggestion: Some(format!(
+                        "Set loaded_at_field on '{}' so freshness can be checked",
+                        key
+                    )),
+                    source_location: None,
+                });
+            }
+
+            if let (Some(warn), Some(error)) = (&freshness.warn_after, &freshness.error_after) {
+                if let (Some(warn_minutes), Some(error_minutes)) = (minutes(warn), minutes(error)) {
+                    if warn_minutes > error_minutes {
+                        issues.push(AuditIssue {
+                            severity: IssueSeverity::Warning,
+                            issue_type: IssueType::InvalidFreshness,
+                            message: format!(
+                                "Source '{}' warns after {} {} but errors after {} {}",
+                                key, warn.count, warn.period, error.count, error.period
+                            ),
+                            node_id,
+                            suggestion: Some(
+                                "Make warn_after shorter than error_after".to_string(),
+                            ),
+                            source_location: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        issues
+    }
+
+    fn check_missing_model_refs(
+        &self,
+        models: &[DbtModel],
+        snapshots: &[DbtSnapshot],
+        seeds: &[DbtSeed],
+        semantic_models: &[SemanticModel],
+    ) -> Vec<AuditIssue> {
+        let known: HashSet<&str> = models
+            .iter()
+            .map(|m| m.name.as_str())
+            .chain(snapshots.iter().map(|s| s.name.as_str()))
+            .chain(seeds.iter().map(|s| s.name.as_str()))
+            .collect();
+
+        let model_refs = models
+            .iter()
+            .flat_map(|m| m.refs.iter().map(move |r| (format!("Model '{}'", m.name), r)));
+        let semantic_refs = semantic_models
+            .iter()
+            .map(|sm| (format!("Semantic model '{}'", sm.name), &sm.model));
+
+        model_refs
+            .chain(semantic_refs)
+            .filter(|(_, r)| !known.contains(r.as_str()))
+            .map(|(owner, r)| AuditIssue {
+                severity: IssueSeverity::Error,
+                issue_type: IssueType::MissingModelRef,
+                message: format!("{} references undefined model '{}'", owner, r),
+                node_id: None,
+                suggestion: Some(format!("Create model '{}' or fix the ref", r)),
+                source_location: None,
+            })
+            .collect()
+    }
+
+    fn check_duplicate_names(
+        &self,
+        models: &[DbtModel],
+        semantic_models: &[SemanticModel],
+        metrics: &[Metric],
+    ) -> Vec<AuditIssue> {
+        let names = models
+            .iter()
+            .map(|m| ("Model", m.name.as_str()))
+            .chain(semantic_models.iter().map(|sm| ("Semantic model", sm.name.as_str())))
+            .chain(metrics.iter().map(|m| ("Metric", m.name.as_str())));
+
+        let mut counts: HashMap<(&str, &str), usize> = HashMap::new();
+        for name in names {
+            *counts.entry(name).or_default() += 1;
+        }
+
+        let mut duplicates: Vec<_> = counts.into_iter().filter(|(_, count)| *count > 1).collect();
+        duplicates.sort();
+
+        duplicates
+            .into_iter()
+            .map(|((kind, name), count)| AuditIssue {
+                severity: IssueSeverity::Error,
+                issue_type: IssueType::DuplicateName,
+                message: format!("{} name '{}' is defined {} times", kind, name, count),
+                node_id: None,
+                suggestion: Some("Rename or remove the duplicate definitions".to_string()),
+                source_location: None,
+            })
+            .collect()
+    }
+
+    /// Graph-free counterpart of `check_orphaned_metrics`: flags metrics whose
+    /// measure or input metrics are not defined anywhere
+    fn check_unresolved_metric_inputs(
+        &self,
+        semantic_models: &[SemanticModel],
+        metrics: &[Metric],
+    ) -> Vec<AuditIssue> {
+        let measures: HashSet<&str> = semantic_models
+            .iter()
+            .flat_map(|sm| sm.measures.iter().map(|m| m.name.as_str()))
+            .collect();
+        let metric_names: HashSet<&str> = metrics.iter().map(|m| m.name.as_str()).collect();
+
+        let mut issues = Vec::new();
+        for metric in metrics {
+            let params = &metric.type_params;
+            let missing: Vec<&str> = params
+                .measure
+                .iter()
+                .map(|m| m.name.as_str())
+                .filter(|name| !measures.contains(name))
+                .chain(
+                    params
+                        .metrics
+                        .iter()
+                        .flatten()
+                        .map(|r| r.name.as_str())
+                        .filter(|name| !metric_names.contains(name)),
+                )
+                .collect();
+
+            for input in missing {
+                issues.push(AuditIssue {
+                    severity: IssueSeverity::Error,
+                    issue_type: IssueType::OrphanedMetric,
+                    message: format!(
+                        "Metric '{}' references undefined input '{}'",
+                        metric.name, input
+                    ),
+                    node_id: None,
+                    suggestion: Some(
+                        "Check the metric definition - the measure or metric may be misspelled"
+                            .to_string(),
+                    ),
+                    source_location: None,
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// Flag refs to `private` models from outside the model's group.
+    ///
+    /// `protected` models may be ref'd from anywhere in the same project, and
+    /// only a single project is parsed, so only `private` can be violated here.
+    fn check_access_violations(
+        &self,
+        graph: &LineageGraph,
+        models: &[DbtModel],
+    ) -> Vec<AuditIssue> {
+        let by_name: HashMap<&str, &DbtModel> =
+            models.iter().map(|m| (m.name.as_str(), m)).collect();
+        let mut issues = Vec::new();
+
+        for model in models {
+            for ref_name in &model.refs {
+                let Some(target) = by_name.get(ref_name.as_str()) else {
+                    continue;
+                };
+                if target.access.as_deref() != Some("private") || target.group == model.group {
+                    continue;
+                }
+
+                issues.push(AuditIssue {
+                    severity: IssueSeverity::Error,
+                    issue_type: IssueType::AccessViolation,
+                    message: format!(
+                        "Model '{}' ({}) refs private model '{}' of group '{}'",
+                        model.name,
+                        model
+                            .group
+                            .as_deref()
+                            .map(|g| format!("group '{}'", g))
+                            .unwrap_or_else(|| "no group".to_string()),
+                        target.name,
+                        target.group.as_deref().unwrap_or("")
+                    ),
+                    node_id: graph
+                        .nodes
+                        .iter()
+                        .find(|n| n.name == model.name && n.node_type == LineageNodeType::Model)
+                        .map(|n| n.id.clone()),
+                    suggestion: Some(format!(
+                        "Make '{}' protected or public, or move '{}' into its group",
+                        target.name, model.name
+                    )),
+                    source_location: None,
+                });
+            }
+        }
+
+        issues
+    }
+
+    fn check_undocumented_columns(
+        &self,
+        graph: &LineageGraph,
+        models: &[DbtModel],
+    ) -> Vec<AuditIssue> {
         models
             .iter()
             .flat_map(|model| {
@@ -196,137 +703,1807 @@ impl LineageAnalyzer {
                             "Column '{}' in model '{}' is not documented",
                             col.name, model.name
                         ),
-                        node_id: None,
+                        node_id: self.node_id(graph, LineageNodeType::Model, &model.name),
                         suggestion: Some("Add a description to help users understand this column".to_string()),
+                        source_location: None,
                     })
             })
             .collect()
     }
 
-    fn check_models_without_tests(&self, models: &[DbtModel]) -> Vec<AuditIssue> {
+    fn check_duplicate_columns(
+        &self,
+        graph: &LineageGraph,
+        models: &[DbtModel],
+    ) -> Vec<AuditIssue> {
         models
             .iter()
-            .filter(|m| {
-                m.columns.iter().all(|c| c.tests.is_empty())
+            .flat_map(|model| {
+                model
+                    .duplicate_columns
+                    .iter()
+                    .map(move |column| AuditIssue {
+                        severity: IssueSeverity::Warning,
+                        issue_type: IssueType::DuplicateName,
+                        message: format!(
+                            "Column '{}' is defined more than once in model '{}'",
+                            column, model.name
+                        ),
+                        node_id: self.node_id(graph, LineageNodeType::Model, &model.name),
+                        suggestion: Some(
+                            "Remove the repeated column entry from schema.yml".to_string(),
+                        ),
+                        source_location: None,
+                    })
             })
+            .collect()
+    }
+
+    fn check_models_without_tests(
+        &self,
+        graph: &LineageGraph,
+        models: &[DbtModel],
+    ) -> Vec<AuditIssue> {
+        models
+            .iter()
+            .filter(|m| !is_tested(m))
             .map(|m| AuditIssue {
                 severity: IssueSeverity::Warning,
                 issue_type: IssueType::NoTests,
                 message: format!("Model '{}' has no tests defined", m.name),
-                node_id: None,
+                node_id: self.node_id(graph, LineageNodeType::Model, &m.name),
                 suggestion: Some("Add tests for key columns (unique, not_null, accepted_values)".to_string()),
+                source_location: None,
             })
             .collect()
     }
 
-    fn calculate_summary(
+    /// Incremental models without a `unique_key` append on every run, so
+    /// re-processed rows end up duplicated
+    fn check_incremental_without_unique_key(
         &self,
+        graph: &LineageGraph,
         models: &[DbtModel],
-        sources: &[DbtSource],
-        semantic_models: &[SemanticModel],
-        metrics: &[Metric],
-        issues: &[AuditIssue],
-    ) -> AuditSummary {
-        let total_measures: usize = semantic_models.iter().map(|sm| sm.measures.len()).sum();
-
-        let documented_metrics = metrics
+    ) -> Vec<AuditIssue> {
+        models
             .iter()
-            .filter(|m| m.description.is_some())
-            .count();
+            .filter(|m| m.materialization.as_deref() == Some("incremental"))
+            .filter(|m| m.unique_key.is_empty())
+            .map(|m| AuditIssue {
+                severity: IssueSeverity::Warning,
+                issue_type: IssueType::IncrementalWithoutUniqueKey,
+                message: format!("Incremental model '{}' has no unique_key", m.name),
+                node_id: self.node_id(graph, LineageNodeType::Model, &m.name),
+                suggestion: Some(
+                    "Set unique_key so reruns merge instead of duplicating rows".to_string(),
+                ),
+                source_location: None,
+            })
+            .collect()
+    }
 
-        let documented_models = models
-            .iter()
-            .filter(|m| m.description.is_some())
-            .count();
+    /// Flag source columns that no model selecting from the source mentions.
+    ///
+    /// This is a heuristic: a column counts as used when its name appears as a
+    /// whole word in the SQL of a model with a `source()` to that table. Models
+    /// that `SELECT *` mention no column names, so their columns are reported
+    /// as unused even though they flow downstream.
+    fn check_unused_source_columns(
+        &self,
+        graph: &LineageGraph,
+        models: &[DbtModel],
+        sources: &[DbtSource],
+    ) -> Vec<AuditIssue> {
+        let mut issues = Vec::new();
 
-        let tested_models = models
-            .iter()
-            .filter(|m| m.columns.iter().any(|c| !c.tests.is_empty()))
-            .count();
+        for source in sources {
+            let referencing_sql: Vec<String> = models
+                .iter()
+                .filter(|m| {
+                    m.sources
+                        .iter()
+                        .any(|r| r.source_name == source.source_name && r.table_name == source.name)
+                })
+                .filter_map(|m| m.raw_sql.as_ref().map(|sql| sql.to_lowercase()))
+                .collect();
 
-        let orphaned_models = issues
-            .iter()
-            .filter(|i| matches!(i.issue_type, IssueType::OrphanedModel))
-            .count();
+            let node_id = graph
+                .nodes
+                .iter()
+                .find(|n| {
+                    n.node_type == LineageNodeType::Source
+                        && n.name == source.name
+                        && n.metadata.get("source_name")
+                            == Some(&serde_json::json!(source.source_name))
+                })
+                .map(|n| n.id.clone());
 
-        AuditSummary {
-            total_metrics: metrics.len(),
-            total_measures,
-            total_models: models.len(),
-            total_sources: sources.len(),
-            documented_metrics,
-            documented_models,
-            tested_models,
-            orphaned_models,
+            for column in &source.columns {
+                if referencing_sql.iter().any(|sql| sql_mentions_column(sql, &column.name)) {
+                    continue;
+                }
+
+                issues.push(AuditIssue {
+                    severity: IssueSeverity::Info,
+                    issue_type: IssueType::UnusedColumn,
+                    message: format!(
+                        "Column '{}' in source '{}.{}' is not used by any model",
+                        column.name, source.source_name, source.name
+                    ),
+                    node_id: node_id.clone(),
+                    suggestion: Some(
+                        "Remove the column from the source definition if it is no longer needed"
+                            .to_string(),
+                    ),
+                    source_location: None,
+                });
+            }
         }
+
+        issues
     }
 
-    fn calculate_completeness_score(
+    /// Warn about entities that are `foreign` in two or more semantic models
+    /// but `primary`, `unique` or `natural` in none, since joining those
+    /// models through the entity is many-to-many and fans out measure values
+    fn check_entity_cardinality(
         &self,
         graph: &LineageGraph,
-        metrics: &[Metric],
         semantic_models: &[SemanticModel],
-    ) -> f64 {
-        if metrics.is_empty() {
-            return 100.0;
+    ) -> Vec<AuditIssue> {
+        let mut foreign_in: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut keyed: HashSet<&str> = HashSet::new();
+        for sm in semantic_models {
+            for entity in &sm.entities {
+                match entity.entity_type.to_lowercase().as_str() {
+                    "foreign" => foreign_in.entry(&entity.name).or_default().push(&sm.name),
+                    "primary" | "unique" | "natural" => {
+                        keyed.insert(&entity.name);
+                    }
+                    _ => {}
+                }
+            }
         }
 
-        // A metric is complete if it has a full lineage path to a source
-        let metric_nodes: Vec<_> = graph
-            .nodes
-            .iter()
-            .filter(|n| n.node_type == LineageNodeType::Metric)
+        let mut shared: Vec<(&str, Vec<&str>)> = foreign_in
+            .into_iter()
+            .filter(|(name, models)| models.len() > 1 && !keyed.contains(name))
             .collect();
+        shared.sort();
 
-        let complete_metrics = metric_nodes
-            .iter()
-            .filter(|m| self.has_complete_lineage(graph, &m.id))
-            .count();
-
-        (complete_metrics as f64 / metric_nodes.len() as f64) * 100.0
+        shared
+            .into_iter()
+            .map(|(name, models)| AuditIssue {
+                severity: IssueSeverity::Warning,
+                issue_type: IssueType::ManyToManyJoin,
+                message: format!(
+                    "Entity '{}' is foreign in {} but primary or unique in no semantic model, \
+                     so joining them is many-to-many",
+                    name,
+                    models.join(", ")
+                ),
+                node_id: self.node_id(graph, LineageNodeType::Entity, name),
+                suggestion: Some(format!(
+                    "Declare '{}' as primary or unique in the semantic model it identifies",
+                    name
+                )),
+                source_location: None,
+            })
+            .collect()
     }
 
-    fn has_complete_lineage(&self, graph: &LineageGraph, start_id: &str) -> bool {
-        // BFS to find if there's a path to a source
-        let mut visited = HashSet::new();
-        let mut queue = vec![start_id.to_string()];
-
-        while let Some(current) = queue.pop() {
-            if visited.contains(&current) {
-                continue;
+    /// Warn about entities whose join key differs between semantic models when
+    /// at least one side is derived, e.g. a raw `customer_id` in one model and
+    /// `cast(customer_id as varchar)` in another, since joins on them may
+    /// silently mismatch. Keys that are plain columns of different names are
+    /// the usual way to map an entity onto each table and are not flagged.
+    fn check_entity_expr_consistency(
+        &self,
+        graph: &LineageGraph,
+        semantic_models: &[SemanticModel],
+    ) -> Vec<AuditIssue> {
+        // Entity name -> (semantic model, key expression); without `expr` the
+        // key is the column named after the entity
+        let mut keys: HashMap<&str, Vec<(&str, &str)>> = HashMap::new();
+        for sm in semantic_models {
+            for entity in &sm.entities {
+                let expr = entity.expr.as_deref().unwrap_or(&entity.name);
+                keys.entry(&entity.name).or_default().push((&sm.name, expr));
             }
-            visited.insert(current.clone());
+        }
 
-            // Check if current node is a source
-            if let Some(node) = graph.nodes.iter().find(|n| n.id == current) {
-                if node.node_type == LineageNodeType::Source {
-                    return true;
-                }
+        let normalize =
+            |expr: &str| -> String { expr.split_whitespace().collect::<String>().to_lowercase() };
+        let is_column = |expr: &str| {
+            expr.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        };
+
+        let mut keys: Vec<(&str, Vec<(&str, &str)>)> = keys.into_iter().collect();
+        keys.sort();
+
+        let mut issues = Vec::new();
+        for (name, models) in keys {
+            let distinct: HashSet<String> = models.iter().map(|(_, e)| normalize(e)).collect();
+            if distinct.len() < 2 || models.iter().all(|(_, e)| is_column(e.trim())) {
+                continue;
+            }
+
+            let declared: Vec<String> = models
+                .iter()
+                .map(|(sm, expr)| format!("{} uses `{}`", sm, expr.trim()))
+                .collect();
+            issues.push(AuditIssue {
+                severity: IssueSeverity::Warning,
+                issue_type: IssueType::EntityExprMismatch,
+                message: format!(
+                    "Entity '{}' is keyed differently across semantic models: {}",
+                    name,
+                    declared.join(", ")
+                ),
+                node_id: self.node_id(graph, LineageNodeType::Entity, name),
+                suggestion: Some(
+                    "Make the entity's `expr` produce the same type and format in every \
+                     semantic model so joins match"
+                        .to_string(),
+                ),
+                source_location: None,
+            });
+        }
+
+        issues
+    }
+
+    fn check_non_additive_dimensions(
+        &self,
+        graph: &LineageGraph,
+        semantic_models: &[SemanticModel],
+    ) -> Vec<AuditIssue> {
+        let mut issues = Vec::new();
+
+        for sm in semantic_models {
+            for measure in &sm.measures {
+                let Some(ref non_additive) = measure.non_additive_dimension else {
+                    continue;
+                };
+                let node_id = graph
+                    .nodes
+                    .iter()
+                    .find(|n| {
+                        n.node_type == LineageNodeType::Measure
+                            && n.name == measure.name
+                            && n.metadata.get("semantic_model").and_then(|v| v.as_str())
+                                == Some(sm.name.as_str())
+                    })
+                    .map(|n| n.id.clone());
+
+                let is_time_dimension = sm
+                    .dimensions
+                    .iter()
+                    .any(|d| d.name == non_additive.name && d.dimension_type == "time");
+                if !is_time_dimension {
+                    issues.push(AuditIssue {
+                        severity: IssueSeverity::Warning,
+                        issue_type: IssueType::InvalidNonAdditiveDimension,
+                        message: format!(
+                            "Measure '{}.{}' has non_additive_dimension '{}', which is not a time dimension of the semantic model",
+                            sm.name, measure.name, non_additive.name
+                        ),
+                        node_id: node_id.clone(),
+                        suggestion: Some(format!(
+                            "Declare '{}' as a time dimension on '{}' or fix the name",
+                            non_additive.name, sm.name
+                        )),
+                        source_location: None,
+                    });
+                }
+
+                if let Some(ref window_choice) = non_additive.window_choice {
+                    if !matches!(window_choice.as_str(), "min" | "max") {
+                        issues.push(AuditIssue {
+                            severity: IssueSeverity::Warning,
+                            issue_type: IssueType::InvalidNonAdditiveDimension,
+                            message: format!(
+                                "Measure '{}.{}' has invalid window_choice '{}'",
+                                sm.name, measure.name, window_choice
+                            ),
+                            node_id,
+                            suggestion: Some("Set window_choice to `min` or `max`".to_string()),
+                            source_location: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        issues
+    }
+
+    /// Flag measures whose `agg` is not a MetricFlow aggregation (an error) or,
+    /// when an allowlist is configured, is not on it (a warning)
+    fn check_measure_aggs(
+        &self,
+        graph: &LineageGraph,
+        semantic_models: &[SemanticModel],
+    ) -> Vec<AuditIssue> {
+        let mut issues = Vec::new();
+
+        for sm in semantic_models {
+            for measure in &sm.measures {
+                let agg = measure.agg.to_lowercase();
+                let node_id = graph
+                    .nodes
+                    .iter()
+                    .find(|n| {
+                        n.node_type == LineageNodeType::Measure
+                            && n.name == measure.name
+                            && n.metadata.get("semantic_model").and_then(|v| v.as_str())
+                                == Some(sm.name.as_str())
+                    })
+                    .map(|n| n.id.clone());
+
+                if !METRICFLOW_AGGS.contains(&agg.as_str()) {
+                    issues.push(AuditIssue {
+                        severity: IssueSeverity::Error,
+                        issue_type: IssueType::InvalidAggregation,
+                        message: format!(
+                            "Measure '{}.{}' has unknown agg '{}'",
+                            sm.name, measure.name, measure.agg
+                        ),
+                        node_id,
+                        suggestion: Some(format!("Use one of: {}", METRICFLOW_AGGS.join(", "))),
+                        source_location: None,
+                    });
+                    continue;
+                }
+
+                let Some(ref allowed) = self.allowed_aggs else {
+                    continue;
+                };
+                if !allowed.iter().any(|a| a.eq_ignore_ascii_case(&agg)) {
+                    issues.push(AuditIssue {
+                        severity: IssueSeverity::Warning,
+                        issue_type: IssueType::DisallowedAggregation,
+                        message: format!(
+                            "Measure '{}.{}' uses agg '{}', which is not in the allowed list",
+                            sm.name, measure.name, measure.agg
+                        ),
+                        node_id,
+                        suggestion: Some(format!("Use one of: {}", allowed.join(", "))),
+                        source_location: None,
+                    });
+                }
+            }
+        }
+
+        issues
+    }
+
+    /// Flag measures no metric consumes and that do not set `create_metric`
+    fn check_unused_measures(
+        &self,
+        graph: &LineageGraph,
+        semantic_models: &[SemanticModel],
+        metrics: &[Metric],
+    ) -> Vec<AuditIssue> {
+        let consumed_ids: HashSet<&str> = graph
+            .edges
+            .iter()
+            .filter(|e| e.edge_type == LineageEdgeType::MetricToMeasure)
+            .map(|e| e.target.as_str())
+            .collect();
+        // Conversion metrics name their measures without a MetricToMeasure edge
+        let consumed_names: HashSet<&str> = metrics
+            .iter()
+            .flat_map(|m| {
+                let conversion = m.type_params.conversion_type_params.as_ref();
+                m.type_params
+                    .measure
+                    .iter()
+                    .chain(conversion.and_then(|c| c.base_measure.as_ref()))
+                    .chain(conversion.and_then(|c| c.conversion_measure.as_ref()))
+                    .map(|r| r.name.as_str())
+            })
+            .collect();
+
+        let mut issues = Vec::new();
+        for sm in semantic_models {
+            for measure in &sm.measures {
+                if measure.create_metric == Some(true)
+                    || consumed_names.contains(measure.name.as_str())
+                {
+                    continue;
+                }
+                let node_id = graph
+                    .nodes
+                    .iter()
+                    .find(|n| {
+                        n.node_type == LineageNodeType::Measure
+                            && n.name == measure.name
+                            && n.metadata.get("semantic_model").and_then(|v| v.as_str())
+                                == Some(sm.name.as_str())
+                    })
+                    .map(|n| n.id.clone());
+                if node_id
+                    .as_deref()
+                    .is_some_and(|id| consumed_ids.contains(id))
+                {
+                    continue;
+                }
+
+                issues.push(AuditIssue {
+                    severity: IssueSeverity::Info,
+                    issue_type: IssueType::UnusedMeasure,
+                    message: format!(
+                        "Measure '{}.{}' is not used by any metric",
+                        sm.name, measure.name
+                    ),
+                    node_id,
+                    suggestion: Some(
+                        "Reference it from a metric, set `create_metric: true`, or remove it"
+                            .to_string(),
+                    ),
+                    source_location: None,
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// Flag cumulative metrics that set both `window` and `grain_to_date` (an
+    /// error, as MetricFlow rejects them) or whose `window` is not a count and
+    /// a time grain such as `7 days` (a warning)
+    fn check_cumulative_params(&self, graph: &LineageGraph, metrics: &[Metric]) -> Vec<AuditIssue> {
+        let mut issues = Vec::new();
+
+        for metric in metrics.iter().filter(|m| m.metric_type == "cumulative") {
+            let params = &metric.type_params;
+            let node_id = self.node_id(graph, LineageNodeType::Metric, &metric.name);
+
+            if let (Some(window), Some(grain)) = (&params.window, &params.grain_to_date) {
+                issues.push(AuditIssue {
+                    severity: IssueSeverity::Error,
+                    issue_type: IssueType::InvalidCumulativeParams,
+                    message: format!(
+                        "Cumulative metric '{}' sets both window '{}' and grain_to_date '{}'",
+                        metric.name, window, grain
+                    ),
+                    node_id,
+                    suggestion: Some(
+                        "Use `window` for a rolling total or `grain_to_date` for a \
+                         period-to-date total, not both"
+                            .to_string(),
+                    ),
+                    source_location: None,
+                });
+                continue;
+            }
+
+            if let Some(ref window) = params.window {
+                if !is_valid_window(window) {
+                    issues.push(AuditIssue {
+                        severity: IssueSeverity::Warning,
+                        issue_type: IssueType::InvalidCumulativeParams,
+                        message: format!(
+                            "Cumulative metric '{}' has unparseable window '{}'",
+                            metric.name, window
+                        ),
+                        node_id,
+                        suggestion: Some(format!(
+                            "Write the window as a count and a grain, e.g. `7 days`; grains are {}",
+                            TIME_GRAINS.join(", ")
+                        )),
+                        source_location: None,
+                    });
+                }
+            }
+        }
+
+        issues
+    }
+
+    /// Warn when a cumulative metric's measure is not joined to the time spine,
+    /// since time buckets without rows then drop out of the running total.
+    /// `join_to_timespine` on the metric's measure input wins over the one on
+    /// the measure definition.
+    fn check_cumulative_timespine(
+        &self,
+        graph: &LineageGraph,
+        semantic_models: &[SemanticModel],
+        metrics: &[Metric],
+    ) -> Vec<AuditIssue> {
+        let mut issues = Vec::new();
+
+        for metric in metrics.iter().filter(|m| m.metric_type == "cumulative") {
+            let Some(ref measure_ref) = metric.type_params.measure else {
+                continue;
+            };
+            let Some(measure) = semantic_models
+                .iter()
+                .flat_map(|sm| &sm.measures)
+                .find(|m| m.name == measure_ref.name)
+            else {
+                continue;
+            };
+            if measure_ref.join_to_timespine.or(measure.join_to_timespine) == Some(true) {
+                continue;
+            }
+
+            issues.push(AuditIssue {
+                severity: IssueSeverity::Warning,
+                issue_type: IssueType::MissingTimeSpineJoin,
+                message: format!(
+                    "Cumulative metric '{}' uses measure '{}', which is not joined to the time spine",
+                    metric.name, measure.name
+                ),
+                node_id: self.node_id(graph, LineageNodeType::Metric, &metric.name),
+                suggestion: Some(format!(
+                    "Set `join_to_timespine: true` on '{}' or on the metric's measure input so empty periods are kept",
+                    measure.name
+                )),
+                source_location: None,
+            });
+        }
+
+        issues
+    }
+
+    /// Warn for each derived metric or model still consuming a metric marked
+    /// `meta.deprecated: true`, attached to the consumer
+    fn check_deprecated_usage(&self, graph: &LineageGraph, metrics: &[Metric]) -> Vec<AuditIssue> {
+        let deprecated: HashMap<String, &str> = metrics
+            .iter()
+            .filter(|m| m.meta.get("deprecated").and_then(|v| v.as_bool()) == Some(true))
+            .filter_map(|m| {
+                self.node_id(graph, LineageNodeType::Metric, &m.name)
+                    .map(|id| (id, m.name.as_str()))
+            })
+            .collect();
+        if deprecated.is_empty() {
+            return Vec::new();
+        }
+
+        let nodes: HashMap<&str, &LineageNode> =
+            graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        let mut issues = Vec::new();
+        // A derived metric can use the same input twice, e.g. with and
+        // without an offset; report each consumer once per deprecated metric
+        let mut reported: HashSet<(&str, &str)> = HashSet::new();
+
+        for edge in &graph.edges {
+            if !matches!(
+                edge.edge_type,
+                LineageEdgeType::MetricToMetric | LineageEdgeType::ModelToMetric
+            ) || !reported.insert((edge.source.as_str(), edge.target.as_str()))
+            {
+                continue;
+            }
+            let (Some(name), Some(consumer)) = (
+                deprecated.get(&edge.target),
+                nodes.get(edge.source.as_str()),
+            ) else {
+                continue;
+            };
+
+            let consumer_kind = match consumer.node_type {
+                LineageNodeType::Metric => "Metric",
+                _ => "Model",
+            };
+            issues.push(AuditIssue {
+                severity: IssueSeverity::Warning,
+                issue_type: IssueType::DeprecatedMetricUsage,
+                message: format!(
+                    "{} '{}' depends on deprecated metric '{}'",
+                    consumer_kind, consumer.name, name
+                ),
+                node_id: Some(consumer.id.clone()),
+                suggestion: Some(format!(
+                    "Move '{}' off '{}' before the metric is removed",
+                    consumer.name, name
+                )),
+                source_location: None,
+            });
+        }
+
+        issues
+    }
+
+    /// Warn when a metric's `grain_to_date` is finer than the declared
+    /// `time_granularity` of the aggregation time dimension of its measure
+    fn check_grain_consistency(
+        &self,
+        graph: &LineageGraph,
+        semantic_models: &[SemanticModel],
+        metrics: &[Metric],
+    ) -> Vec<AuditIssue> {
+        let grain_rank = |grain: &str| {
+            TIME_GRAINS
+                .iter()
+                .position(|g| g.eq_ignore_ascii_case(grain))
+        };
+        let mut issues = Vec::new();
+
+        for metric in metrics {
+            let (Some(requested), Some(measure_ref)) = (
+                &metric.type_params.grain_to_date,
+                &metric.type_params.measure,
+            ) else {
+                continue;
+            };
+            let Some((sm, measure)) = semantic_models.iter().find_map(|sm| {
+                sm.measures
+                    .iter()
+                    .find(|m| m.name == measure_ref.name)
+                    .map(|m| (sm, m))
+            }) else {
+                continue;
+            };
+
+            let time_dimensions: Vec<_> = sm
+                .dimensions
+                .iter()
+                .filter(|d| d.dimension_type == "time")
+                .collect();
+            let agg_time_dimension = match measure.agg_time_dimension.as_ref().or_else(|| {
+                sm.defaults
+                    .as_ref()
+                    .and_then(|d| d.agg_time_dimension.as_ref())
+            }) {
+                Some(name) => time_dimensions.iter().find(|d| &d.name == name),
+                None if time_dimensions.len() == 1 => time_dimensions.first(),
+                None => None,
+            };
+            let Some(dimension) = agg_time_dimension else {
+                continue;
+            };
+            let Some(declared) = dimension
+                .type_params
+                .as_ref()
+                .and_then(|p| p.time_granularity.as_ref())
+            else {
+                continue;
+            };
+
+            if let (Some(requested_rank), Some(declared_rank)) =
+                (grain_rank(requested), grain_rank(declared))
+            {
+                if requested_rank < declared_rank {
+                    issues.push(AuditIssue {
+                        severity: IssueSeverity::Warning,
+                        issue_type: IssueType::GrainMismatch,
+                        message: format!(
+                            "Metric '{}' uses grain_to_date '{}', finer than the '{}' grain of time dimension '{}.{}'",
+                            metric.name, requested, declared, sm.name, dimension.name
+                        ),
+                        node_id: graph
+                            .nodes
+                            .iter()
+                            .find(|n| n.name == metric.name && n.node_type == LineageNodeType::Metric)
+                            .map(|n| n.id.clone()),
+                        suggestion: Some(format!(
+                            "Use a grain of '{}' or coarser, or declare a finer time_granularity",
+                            declared
+                        )),
+                        source_location: None,
+                    });
+                }
+            }
+        }
+
+        issues
+    }
+
+    fn check_derived_metric_refs(&self, graph: &LineageGraph, metrics: &[Metric]) -> Vec<AuditIssue> {
+        let mut issues = Vec::new();
+
+        for metric in metrics.iter().filter(|m| m.metric_type == "derived") {
+            let Some(ref expr) = metric.type_params.expr else {
+                continue;
+            };
+
+            // Inputs can be referenced in `expr` by name or by alias
+            let declared: HashSet<&str> = metric
+                .type_params
+                .metrics
+                .iter()
+                .flatten()
+                .flat_map(|r| std::iter::once(r.name.as_str()).chain(r.alias.as_deref()))
+                .collect();
+
+            for name in parse_expr_identifiers(expr) {
+                if declared.contains(name.as_str()) {
+                    continue;
+                }
+                issues.push(AuditIssue {
+                    severity: IssueSeverity::Warning,
+                    issue_type: IssueType::MissingMetricRef,
+                    message: format!(
+                        "Derived metric '{}' uses '{}' in expr but does not list it in metrics",
+                        metric.name, name
+                    ),
+                    node_id: graph
+                        .nodes
+                        .iter()
+                        .find(|n| n.name == metric.name && n.node_type == LineageNodeType::Metric)
+                        .map(|n| n.id.clone()),
+                    suggestion: Some(format!("Add '{}' to type_params.metrics", name)),
+                    source_location: None,
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// A metric's measure can carry its own filter; warn when it references a
+    /// dimension or entity that no semantic model defines
+    fn check_measure_filters(
+        &self,
+        graph: &LineageGraph,
+        semantic_models: &[SemanticModel],
+        metrics: &[Metric],
+    ) -> Vec<AuditIssue> {
+        let dimensions: HashSet<&str> = semantic_models
+            .iter()
+            .flat_map(|sm| sm.dimensions.iter().map(|d| d.name.as_str()))
+            .collect();
+        let entities: HashSet<&str> = semantic_models
+            .iter()
+            .flat_map(|sm| sm.entities.iter().map(|e| e.name.as_str()))
+            .chain(
+                semantic_models
+                    .iter()
+                    .filter_map(|sm| sm.primary_entity.as_deref()),
+            )
+            .collect();
+
+        let mut issues = Vec::new();
+        for metric in metrics {
+            let Some(ref measure_ref) = metric.type_params.measure else {
+                continue;
+            };
+            let Some(ref filter) = measure_ref.filter else {
+                continue;
+            };
+
+            for reference in parse_filter_references(filter) {
+                let (defined, kind) = match reference.kind {
+                    FilterReferenceKind::Dimension => {
+                        (dimensions.contains(reference.name.as_str()), "dimension")
+                    }
+                    // metric_time is built in and always available
+                    FilterReferenceKind::TimeDimension => (
+                        reference.name == "metric_time"
+                            || dimensions.contains(reference.name.as_str()),
+                        "time dimension",
+                    ),
+                    FilterReferenceKind::Entity => {
+                        (entities.contains(reference.name.as_str()), "entity")
+                    }
+                    FilterReferenceKind::Metric => continue,
+                };
+                if defined {
+                    continue;
+                }
+
+                issues.push(AuditIssue {
+                    severity: IssueSeverity::Warning,
+                    issue_type: IssueType::MissingDimension,
+                    message: format!(
+                        "Metric '{}' filters measure '{}' on undefined {} '{}'",
+                        metric.name, measure_ref.name, kind, reference.name
+                    ),
+                    node_id: self.node_id(graph, LineageNodeType::Metric, &metric.name),
+                    suggestion: Some(format!(
+                        "Define '{}' in a semantic model or fix the filter",
+                        reference.name
+                    )),
+                    source_location: None,
+                });
+            }
+        }
+
+        issues
+    }
+
+    fn check_missing_owners(&self, graph: &LineageGraph, metrics: &[Metric]) -> Vec<AuditIssue> {
+        metrics
+            .iter()
+            .filter(|m| !m.meta.contains_key("owner"))
+            .map(|m| AuditIssue {
+                severity: IssueSeverity::Warning,
+                issue_type: IssueType::MissingOwner,
+                message: format!("Metric '{}' has no owner", m.name),
+                node_id: graph
+                    .nodes
+                    .iter()
+                    .find(|n| n.name == m.name && n.node_type == LineageNodeType::Metric)
+                    .map(|n| n.id.clone()),
+                suggestion: Some("Add an `owner` key to the metric's meta block".to_string()),
+                source_location: None,
+            })
+            .collect()
+    }
+
+    fn calculate_summary(
+        &self,
+        models: &[DbtModel],
+        sources: &[DbtSource],
+        semantic_models: &[SemanticModel],
+        metrics: &[Metric],
+        issues: &[AuditIssue],
+    ) -> AuditSummary {
+        let total_measures: usize = semantic_models.iter().map(|sm| sm.measures.len()).sum();
+
+        let documented_metrics = metrics
+            .iter()
+            .filter(|m| m.description.is_some())
+            .count();
+
+        let documented_models = models
+            .iter()
+            .filter(|m| m.description.is_some())
+            .count();
+
+        let tested_models = models.iter().filter(|m| is_tested(m)).count();
+
+        let orphaned_models = issues
+            .iter()
+            .filter(|i| matches!(i.issue_type, IssueType::OrphanedModel))
+            .count();
+
+        let owned_metrics = metrics
+            .iter()
+            .filter(|m| m.meta.contains_key("owner"))
+            .count();
+
+        let mut metrics_by_type: HashMap<String, usize> = HashMap::new();
+        for metric in metrics {
+            *metrics_by_type
+                .entry(metric.metric_type.clone())
+                .or_default() += 1;
+        }
+
+        let mut measures_by_agg: HashMap<String, usize> = HashMap::new();
+        for measure in semantic_models.iter().flat_map(|sm| &sm.measures) {
+            *measures_by_agg.entry(measure.agg.clone()).or_default() += 1;
+        }
+
+        AuditSummary {
+            total_metrics: metrics.len(),
+            total_measures,
+            total_models: models.len(),
+            total_sources: sources.len(),
+            documented_metrics,
+            documented_models,
+            tested_models,
+            orphaned_models,
+            owned_metrics,
+            metrics_by_type,
+            measures_by_agg,
+        }
+    }
+
+    fn calculate_completeness_score(
+        &self,
+        graph: &LineageGraph,
+        models: &[DbtModel],
+        metrics: &[Metric],
+        semantic_models: &[SemanticModel],
+    ) -> f64 {
+        if metrics.is_empty() {
+            return 100.0;
+        }
+
+        // A metric is complete if its lineage meets the completeness policy
+        let metric_nodes: Vec<_> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.node_type == LineageNodeType::Metric)
+            .collect();
+
+        let mut upstream: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in &graph.edges {
+            upstream.entry(&edge.source).or_default().push(&edge.target);
+        }
+        let complete_metrics = metric_nodes
+            .iter()
+            .filter(|m| self.has_complete_lineage(graph, &upstream, models, &m.id))
+            .count();
+
+        (complete_metrics as f64 / metric_nodes.len() as f64) * 100.0
+    }
+
+    /// Whether the lineage upstream of `start_id` meets the completeness
+    /// policy; `upstream` maps each node id to its dependencies
+    fn has_complete_lineage(
+        &self,
+        graph: &LineageGraph,
+        upstream: &HashMap<&str, Vec<&str>>,
+        models: &[DbtModel],
+        start_id: &str,
+    ) -> bool {
+        let policy = &self.completeness_policy;
+        if policy.require_source_path {
+            let reached = self.upstream_node_types(graph, start_id);
+            if !reached.contains(&LineageNodeType::Source)
+                && !reached.contains(&LineageNodeType::Seed)
+            {
+                return false;
+            }
+        }
+        if !policy.require_tests && !policy.require_descriptions {
+            return true;
+        }
+
+        reachable(upstream, start_id)
+            .into_iter()
+            .filter_map(|id| {
+                graph
+                    .nodes
+                    .iter()
+                    .find(|n| n.id == id && n.node_type == LineageNodeType::Model)
+            })
+            .all(|node| {
+                let tested = || models.iter().any(|m| m.name == node.name && is_tested(m));
+                let described = || {
+                    node.description
+                        .as_deref()
+                        .is_some_and(|d| !d.trim().is_empty())
+                };
+                (!policy.require_tests || tested()) && (!policy.require_descriptions || described())
+            })
+    }
+
+    /// Node types reachable from `start_id` by following edges upstream
+    fn upstream_node_types(
+        &self,
+        graph: &LineageGraph,
+        start_id: &str,
+    ) -> HashSet<LineageNodeType> {
+        let mut visited = HashSet::new();
+        let mut reached = HashSet::new();
+        let mut queue = vec![start_id.to_string()];
+
+        while let Some(current) = queue.pop() {
+            if visited.contains(&current) {
+                continue;
+            }
+            visited.insert(current.clone());
+
+            if let Some(node) = graph.nodes.iter().find(|n| n.id == current) {
+                reached.insert(node.node_type.clone());
             }
 
             // Add connected nodes
             for edge in &graph.edges {
-                if edge.source == current && !visited.contains(&edge.target) {
-                    queue.push(edge.target.clone());
+                if edge.source == current && !visited.contains(&edge.target) {
+                    queue.push(edge.target.clone());
+                }
+            }
+        }
+
+        reached
+    }
+
+    /// Report, per metric, whether it traces back to a source and otherwise
+    /// the first link missing from its metric -> measure -> model -> source chain
+    pub fn metric_completeness(&self, graph: &LineageGraph) -> Vec<MetricCompleteness> {
+        graph
+            .nodes
+            .iter()
+            .filter(|n| n.node_type == LineageNodeType::Metric)
+            .map(|metric| {
+                let reached = self.upstream_node_types(graph, &metric.id);
+                let has = |t: LineageNodeType| reached.contains(&t);
+
+                let missing_link = if has(LineageNodeType::Source) || has(LineageNodeType::Seed) {
+                    None
+                } else if !has(LineageNodeType::Measure) {
+                    Some("measure not found")
+                } else if !has(LineageNodeType::Model) && !has(LineageNodeType::Snapshot) {
+                    Some("semantic model has no dbt model")
+                } else {
+                    Some("model has no source")
+                };
+
+                MetricCompleteness {
+                    metric_name: metric.name.clone(),
+                    has_source_path: missing_link.is_none(),
+                    missing_link: missing_link.map(|s| s.to_string()),
+                }
+            })
+            .collect()
+    }
+
+    fn calculate_documentation_coverage(&self, graph: &LineageGraph) -> f64 {
+        let nodes: Vec<&LineageNode> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.node_type != LineageNodeType::Test)
+            .collect();
+        if nodes.is_empty() {
+            return 100.0;
+        }
+
+        let documented = nodes.iter().filter(|n| n.description.is_some()).count();
+
+        (documented as f64 / nodes.len() as f64) * 100.0
+    }
+
+    fn calculate_column_documentation_coverage(
+        &self,
+        models: &[DbtModel],
+        sources: &[DbtSource],
+    ) -> f64 {
+        let columns: Vec<&DbtColumn> = models
+            .iter()
+            .flat_map(|m| &m.columns)
+            .chain(sources.iter().flat_map(|s| &s.columns))
+            .collect();
+        if columns.is_empty() {
+            return 100.0;
+        }
+
+        let documented = columns.iter().filter(|c| c.description.is_some()).count();
+
+        (documented as f64 / columns.len() as f64) * 100.0
+    }
+
+    /// Collect the IDs of every node some metric depends on.
+    ///
+    /// A node is reachable when a metric reaches it by following edges
+    /// downstream, or when it is a dimension attached to a reachable entity.
+    fn metric_reachable_ids<'a>(&self, graph: &'a LineageGraph) -> HashSet<&'a str> {
+        let mut alive: HashSet<&str> = HashSet::new();
+        let mut queue: Vec<&str> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.node_type == LineageNodeType::Metric)
+            .map(|n| n.id.as_str())
+            .collect();
+
+        while let Some(current) = queue.pop() {
+            if !alive.insert(current) {
+                continue;
+            }
+            for edge in &graph.edges {
+                if edge.source == current && !alive.contains(edge.target.as_str()) {
+                    queue.push(edge.target.as_str());
+                }
+            }
+        }
+
+        // Dimensions are consumed through their entity rather than by an edge
+        // from a metric, so they live as long as that entity does
+        let live_dimensions: Vec<&str> = graph
+            .edges
+            .iter()
+            .filter(|e| {
+                e.edge_type == LineageEdgeType::DimensionToEntity
+                    && alive.contains(e.target.as_str())
+            })
+            .map(|e| e.source.as_str())
+            .collect();
+        alive.extend(live_dimensions);
+        alive
+    }
+
+    /// Group dead nodes (those no metric depends on) into connected clusters.
+    ///
+    /// Dead nodes are split into connected components (ignoring edge
+    /// direction) so whole dead subtrees can be removed at once. Clusters hold
+    /// node IDs and are sorted by size, largest first.
+    pub fn find_orphan_clusters(&self, graph: &LineageGraph) -> Vec<Vec<String>> {
+        let alive = self.metric_reachable_ids(graph);
+
+        let dead: Vec<&str> = graph
+            .nodes
+            .iter()
+            .map(|n| n.id.as_str())
+            .filter(|id| !alive.contains(id))
+            .collect();
+        let dead_set: HashSet<&str> = dead.iter().copied().collect();
+
+        let mut neighbors: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in &graph.edges {
+            if dead_set.contains(edge.source.as_str()) && dead_set.contains(edge.target.as_str()) {
+                neighbors.entry(&edge.source).or_default().push(&edge.target);
+                neighbors.entry(&edge.target).or_default().push(&edge.source);
+            }
+        }
+
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut clusters = Vec::new();
+
+        for id in dead {
+            if visited.contains(id) {
+                continue;
+            }
+
+            let mut cluster = Vec::new();
+            let mut stack = vec![id];
+            while let Some(current) = stack.pop() {
+                if !visited.insert(current) {
+                    continue;
+                }
+                cluster.push(current.to_string());
+                if let Some(adjacent) = neighbors.get(current) {
+                    stack.extend(adjacent.iter().filter(|n| !visited.contains(*n)));
+                }
+            }
+            clusters.push(cluster);
+        }
+
+        clusters.sort_by_key(|c| std::cmp::Reverse(c.len()));
+        clusters
+    }
+
+    /// Count nodes, edges and weakly connected components, and find the
+    /// longest dependency chain.
+    ///
+    /// Both walks are iterative, so deep graphs cannot overflow the stack.
+    /// The longest path is counted in edges and is `None` when the graph has
+    /// a cycle.
+    pub fn graph_stats(&self, graph: &LineageGraph) -> GraphStats {
+        let ids: HashSet<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.source.as_str(), e.target.as_str()))
+            .filter(|(from, to)| ids.contains(from) && ids.contains(to))
+            .collect();
+
+        let mut neighbors: HashMap<&str, Vec<&str>> = HashMap::new();
+        for &(from, to) in &edges {
+            neighbors.entry(from).or_default().push(to);
+            neighbors.entry(to).or_default().push(from);
+        }
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut component_count = 0;
+        for node in &graph.nodes {
+            if visited.contains(node.id.as_str()) {
+                continue;
+            }
+            component_count += 1;
+            let mut stack = vec![node.id.as_str()];
+            while let Some(current) = stack.pop() {
+                if !visited.insert(current) {
+                    continue;
+                }
+                if let Some(adjacent) = neighbors.get(current) {
+                    stack.extend(adjacent.iter().filter(|n| !visited.contains(*n)));
+                }
+            }
+        }
+
+        // Kahn's algorithm from the nodes nothing depends on; a node's depth
+        // is the longest chain of edges leading to it
+        let mut pending: HashMap<&str, usize> = ids.iter().map(|id| (*id, 0)).collect();
+        let mut dependencies: HashMap<&str, Vec<&str>> = HashMap::new();
+        for &(from, to) in &edges {
+            *pending.get_mut(to).unwrap() += 1;
+            dependencies.entry(from).or_default().push(to);
+        }
+        let mut queue: VecDeque<&str> = pending
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut depth: HashMap<&str, usize> = HashMap::new();
+        let mut processed = 0;
+        while let Some(id) = queue.pop_front() {
+            processed += 1;
+            let current = *depth.entry(id).or_default();
+            for dependency in dependencies.get(id).into_iter().flatten() {
+                let entry = depth.entry(dependency).or_default();
+                *entry = (*entry).max(current + 1);
+                let remaining = pending.get_mut(dependency).unwrap();
+                *remaining -= 1;
+                if *remaining == 0 {
+                    queue.push_back(dependency);
+                }
+            }
+        }
+        let has_cycle = processed < ids.len();
+
+        let mut nodes_by_type: HashMap<String, usize> = HashMap::new();
+        for node in &graph.nodes {
+            *nodes_by_type
+                .entry(format!("{:?}", node.node_type))
+                .or_default() += 1;
+        }
+
+        GraphStats {
+            node_count: graph.nodes.len(),
+            edge_count: graph.edges.len(),
+            component_count,
+            longest_path: if has_cycle {
+                None
+            } else {
+                Some(depth.values().copied().max().unwrap_or(0))
+            },
+            has_cycle,
+            average_fan_out: if graph.nodes.is_empty() {
+                0.0
+            } else {
+                graph.edges.len() as f64 / graph.nodes.len() as f64
+            },
+            nodes_by_type,
+        }
+    }
+
+    /// Order node names so every node comes after everything it depends on
+    /// (sources first), using Kahn's algorithm.
+    ///
+    /// Only models, snapshots, seeds and sources are included unless
+    /// `include_semantic` is set. A cycle is reported as an error naming one
+    /// of the nodes on it.
+    pub fn topological_order(
+        &self,
+        graph: &LineageGraph,
+        include_semantic: bool,
+    ) -> Result<Vec<String>, String> {
+        let included: HashMap<&str, &str> = graph
+            .nodes
+            .iter()
+            .filter(|n| {
+                include_semantic
+                    || matches!(
+                        n.node_type,
+                        LineageNodeType::Model
+                            | LineageNodeType::Snapshot
+                            | LineageNodeType::Seed
+                            | LineageNodeType::Source
+                    )
+            })
+            .map(|n| (n.id.as_str(), n.name.as_str()))
+            .collect();
+
+        // Edges point from a node to what it depends on
+        let mut pending_deps: HashMap<&str, usize> = included.keys().map(|id| (*id, 0)).collect();
+        let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut dependencies: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in &graph.edges {
+            let (from, to) = (edge.source.as_str(), edge.target.as_str());
+            if from == to || !included.contains_key(from) || !included.contains_key(to) {
+                continue;
+            }
+            *pending_deps.entry(from).or_default() += 1;
+            dependents.entry(to).or_default().push(from);
+            dependencies.entry(from).or_default().push(to);
+        }
+
+        let mut queue: VecDeque<&str> = graph
+            .nodes
+            .iter()
+            .map(|n| n.id.as_str())
+            .filter(|id| pending_deps.get(id) == Some(&0))
+            .collect();
+        let mut order = Vec::with_capacity(included.len());
+
+        while let Some(id) = queue.pop_front() {
+            order.push(included[id].to_string());
+            for dependent in dependents.get(id).into_iter().flatten() {
+                let remaining = pending_deps.get_mut(dependent).unwrap();
+                *remaining -= 1;
+                if *remaining == 0 {
+                    queue.push_back(dependent);
+                }
+            }
+        }
+
+        if order.len() == included.len() {
+            return Ok(order);
+        }
+
+        // Every unresolved node still waits on another unresolved node, so
+        // walking those dependencies must eventually revisit a node on the cycle
+        let mut current = pending_deps
+            .iter()
+            .find(|(_, remaining)| **remaining > 0)
+            .map(|(id, _)| *id)
+            .unwrap();
+        let mut seen = HashSet::new();
+        while seen.insert(current) {
+            current = dependencies[current]
+                .iter()
+                .copied()
+                .find(|dep| pending_deps[dep] > 0)
+                .unwrap();
+        }
+
+        Err(format!(
+            "Cycle detected in lineage involving '{}'",
+            included[current]
+        ))
+    }
+
+    /// Measure how many of a source's columns feed a metric.
+    ///
+    /// There is no column-level lineage yet, so this is a model-level
+    /// approximation: a column counts as used when it is mentioned in the SQL
+    /// of a metric-backing model that selects from the source directly.
+    /// `source_name` is either the table name or `source_name.table_name`.
+    pub fn source_utilization(
+        &self,
+        graph: &LineageGraph,
+        models: &[DbtModel],
+        sources: &[DbtSource],
+        source_name: &str,
+    ) -> Option<SourceUtilization> {
+        let source = sources.iter().find(|s| {
+            s.name == source_name || format!("{}.{}", s.source_name, s.name) == source_name
+        })?;
+
+        let alive = self.metric_reachable_ids(graph);
+        let backing_sql: Vec<String> = models
+            .iter()
+            .filter(|m| {
+                m.sources
+                    .iter()
+                    .any(|r| r.source_name == source.source_name && r.table_name == source.name)
+            })
+            .filter(|m| {
+                graph.nodes.iter().any(|n| {
+                    n.node_type == LineageNodeType::Model
+                        && n.name == m.name
+                        && alive.contains(n.id.as_str())
+                })
+            })
+            .filter_map(|m| m.raw_sql.as_ref().map(|sql| sql.to_lowercase()))
+            .collect();
+
+        let (used, unused): (Vec<_>, Vec<_>) = source
+            .columns
+            .iter()
+            .partition(|c| backing_sql.iter().any(|sql| sql_mentions_column(sql, &c.name)));
+
+        let total_columns = source.columns.len();
+        Some(SourceUtilization {
+            source_name: format!("{}.{}", source.source_name, source.name),
+            total_columns,
+            used_columns: used.len(),
+            utilization: if total_columns == 0 {
+                0.0
+            } else {
+                used.len() as f64 / total_columns as f64
+            },
+            unused_columns: unused.into_iter().map(|c| c.name.clone()).collect(),
+            column_level: false,
+        })
+    }
+
+    /// Dimensions that can be grouped by alongside a metric, named
+    /// `entity__dimension` as in MetricFlow queries.
+    ///
+    /// Starts from the semantic models defining the metric's measures
+    /// (following derived metric inputs) and joins, through any number of
+    /// hops, to every semantic model with a primary, unique or natural entity
+    /// named like an entity of a model already reached. Returns `None` when
+    /// the metric does not exist.
+    pub fn dimension_availability(
+        &self,
+        semantic_models: &[SemanticModel],
+        metrics: &[Metric],
+        metric_name: &str,
+    ) -> Option<Vec<String>> {
+        metrics.iter().find(|m| m.name == metric_name)?;
+
+        // Measures the metric aggregates, directly or through its inputs
+        let mut measures = HashSet::new();
+        let mut seen_metrics = HashSet::new();
+        let mut stack = vec![metric_name];
+        while let Some(name) = stack.pop() {
+            if !seen_metrics.insert(name) {
+                continue;
+            }
+            let Some(metric) = metrics.iter().find(|m| m.name == name) else {
+                continue;
+            };
+            let params = &metric.type_params;
+            measures.extend(params.measure.iter().map(|m| m.name.as_str()));
+            if let Some(ref conversion) = params.conversion_type_params {
+                measures.extend(conversion.base_measure.iter().map(|m| m.name.as_str()));
+                measures.extend(
+                    conversion
+                        .conversion_measure
+                        .iter()
+                        .map(|m| m.name.as_str()),
+                );
+            }
+            stack.extend(params.metrics.iter().flatten().map(|r| r.name.as_str()));
+        }
+
+        let primary_entity = |sm: &SemanticModel| {
+            sm.entities
+                .iter()
+                .find(|e| e.entity_type == "primary")
+                .map(|e| e.name.clone())
+                .or_else(|| sm.primary_entity.clone())
+        };
+        let is_join_target =
+            |entity_type: &str| matches!(entity_type, "primary" | "unique" | "natural");
+
+        // Walk the entity join graph, remembering the entity each model was reached through
+        let mut reached: Vec<(usize, Option<String>)> = Vec::new();
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        for (i, sm) in semantic_models.iter().enumerate() {
+            if sm
+                .measures
+                .iter()
+                .any(|m| measures.contains(m.name.as_str()))
+            {
+                reached.push((i, primary_entity(sm)));
+                queue.push_back(i);
+            }
+        }
+        while let Some(i) = queue.pop_front() {
+            for entity in &semantic_models[i].entities {
+                for (j, other) in semantic_models.iter().enumerate() {
+                    if reached.iter().any(|(r, _)| *r == j) {
+                        continue;
+                    }
+                    if other
+                        .entities
+                        .iter()
+                        .any(|e| e.name == entity.name && is_join_target(&e.entity_type))
+                    {
+                        reached.push((j, Some(entity.name.clone())));
+                        queue.push_back(j);
+                    }
+                }
+            }
+        }
+
+        let mut dimensions: Vec<String> = reached
+            .iter()
+            .flat_map(|(i, entity)| {
+                semantic_models[*i]
+                    .dimensions
+                    .iter()
+                    .map(move |d| match entity {
+                        Some(entity) => format!("{}__{}", entity, d.name),
+                        None => d.name.clone(),
+                    })
+            })
+            .collect();
+        dimensions.sort();
+        dimensions.dedup();
+
+        Some(dimensions)
+    }
+
+    /// List the metrics referencing each measure, most reused first
+    pub fn measure_usage(&self, graph: &LineageGraph) -> Vec<MeasureUsage> {
+        let node_names: HashMap<&str, &str> = graph
+            .nodes
+            .iter()
+            .map(|n| (n.id.as_str(), n.name.as_str()))
+            .collect();
+
+        let mut usage: Vec<MeasureUsage> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.node_type == LineageNodeType::Measure)
+            .map(|measure| {
+                let mut metrics: Vec<String> = graph
+                    .edges
+                    .iter()
+                    .filter(|e| {
+                        e.edge_type == LineageEdgeType::MetricToMeasure && e.target == measure.id
+                    })
+                    .filter_map(|e| node_names.get(e.source.as_str()).map(|n| n.to_string()))
+                    .collect();
+                metrics.sort();
+                metrics.dedup();
+
+                MeasureUsage {
+                    measure_name: measure.name.clone(),
+                    semantic_model: measure
+                        .metadata
+                        .get("semantic_model")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string()),
+                    usage_count: metrics.len(),
+                    metrics,
+                }
+            })
+            .collect();
+
+        usage.sort_by(|a, b| {
+            b.usage_count
+                .cmp(&a.usage_count)
+                .then_with(|| a.measure_name.cmp(&b.measure_name))
+        });
+        usage
+    }
+
+    /// Resolve each measure to the columns of its semantic model's dbt model it reads
+    ///
+    /// A measure without `expr` reads the column named after it. Expressions
+    /// are tokenized and every identifier that is not a function, keyword, cast
+    /// type or `extract` field is taken as a column, which is best effort for
+    /// anything beyond a bare column.
+    pub fn measure_columns(&self, semantic_models: &[SemanticModel]) -> Vec<MeasureColumn> {
+        semantic_models
+            .iter()
+            .flat_map(|sm| {
+                sm.measures.iter().map(|measure| MeasureColumn {
+                    measure_name: measure.name.clone(),
+                    semantic_model: sm.name.clone(),
+                    model_name: sm.model.clone(),
+                    columns: match measure.expr {
+                        Some(ref expr) => parse_expr_identifiers(expr),
+                        None => vec![measure.name.clone()],
+                    },
+                })
+            })
+            .collect()
+    }
+
+    /// Group metrics whose names are the same set of `_`-separated words,
+    /// ignoring case and order, as candidates for merging
+    pub fn consolidation_hints(&self, metrics: &[Metric]) -> Vec<ConsolidationHint> {
+        let mut clusters: HashMap<String, Vec<&Metric>> = HashMap::new();
+        for metric in metrics {
+            let mut tokens: Vec<String> = metric
+                .name
+                .to_lowercase()
+                .split('_')
+                .filter(|t| !t.is_empty())
+                .map(|t| t.to_string())
+                .collect();
+            tokens.sort();
+            clusters.entry(tokens.join("_")).or_default().push(metric);
+        }
+
+        let mut hints: Vec<ConsolidationHint> = clusters
+            .into_iter()
+            .filter(|(_, cluster)| cluster.len() > 1)
+            .map(|(normalized_name, cluster)| {
+                let measures: HashSet<Option<&str>> = cluster
+                    .iter()
+                    .map(|m| m.type_params.measure.as_ref().map(|r| r.name.as_str()))
+                    .collect();
+                let shared_measure = match measures.into_iter().collect::<Vec<_>>()[..] {
+                    [Some(measure)] => Some(measure.to_string()),
+                    _ => None,
+                };
+
+                let mut metrics: Vec<String> = cluster.iter().map(|m| m.name.clone()).collect();
+                metrics.sort();
+                ConsolidationHint {
+                    normalized_name,
+                    metrics,
+                    shared_measure,
+                }
+            })
+            .collect();
+
+        hints.sort_by(|a, b| a.normalized_name.cmp(&b.normalized_name));
+        hints
+    }
+
+    /// Rank metrics by how many models and sources they read from plus how
+    /// many nodes consume them, most fragile first
+    pub fn blast_radius(&self, graph: &LineageGraph) -> Vec<BlastRadius> {
+        let mut upstream: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut downstream: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in &graph.edges {
+            upstream.entry(&edge.source).or_default().push(&edge.target);
+            downstream
+                .entry(&edge.target)
+                .or_default()
+                .push(&edge.source);
+        }
+        let node_types: HashMap<&str, &LineageNodeType> = graph
+            .nodes
+            .iter()
+            .map(|n| (n.id.as_str(), &n.node_type))
+            .collect();
+
+        let mut ranking: Vec<BlastRadius> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.node_type == LineageNodeType::Metric)
+            .map(|metric| {
+                let upstream_count = reachable(&upstream, &metric.id)
+                    .into_iter()
+                    .filter(|id| {
+                        matches!(
+                            node_types.get(id),
+                            Some(LineageNodeType::Model)
+                                | Some(LineageNodeType::Snapshot)
+                                | Some(LineageNodeType::Seed)
+                                | Some(LineageNodeType::Source)
+                        )
+                    })
+                    .count();
+                let downstream_consumer_count = reachable(&downstream, &metric.id).len();
+
+                BlastRadius {
+                    metric_name: metric.name.clone(),
+                    project: metric
+                        .metadata
+                        .get("project")
+                        .and_then(|v| v.as_str())
+                        .map(|p| p.to_string()),
+                    upstream_count,
+                    downstream_consumer_count,
+                    score: upstream_count + downstream_consumer_count,
+                }
+            })
+            .collect();
+
+        ranking.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.metric_name.cmp(&b.metric_name))
+                .then_with(|| a.project.cmp(&b.project))
+        });
+        ranking
+    }
+
+    /// Nest a metric's upstream dependencies (metric → measures → entities →
+    /// models → sources) into a tree. Each node is expanded once; later
+    /// occurrences, including cycle back-edges, become leaves marked `is_ref`.
+    /// Returns `None` when no metric node has id `metric_id`.
+    pub fn metric_tree(&self, graph: &LineageGraph, metric_id: &str) -> Option<MetricTreeNode> {
+        let metric = graph
+            .nodes
+            .iter()
+            .find(|n| n.node_type == LineageNodeType::Metric && n.id == metric_id)?;
+
+        let nodes: HashMap<&str, &LineageNode> =
+            graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        let mut upstream: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in &graph.edges {
+            upstream.entry(&edge.source).or_default().push(&edge.target);
+        }
+
+        let mut expanded = HashSet::new();
+        Some(tree_node(metric, &nodes, &upstream, &mut expanded))
+    }
+
+    /// Describe a metric's lineage in one plain-English sentence, e.g.
+    /// "revenue is a simple metric derived from measure order_total in semantic
+    /// model orders, built on model fct_orders from source raw.orders."
+    /// Returns `None` when no metric node has id `metric_id`.
+    pub fn explain_metric(&self, graph: &LineageGraph, metric_id: &str) -> Option<String> {
+        let metric = graph
+            .nodes
+            .iter()
+            .find(|n| n.node_type == LineageNodeType::Metric && n.id == metric_id)?;
+        let nodes: HashMap<&str, &LineageNode> =
+            graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        let direct = |edge_type: LineageEdgeType| -> Vec<&LineageNode> {
+            graph
+                .edges
+                .iter()
+                .filter(|e| {
+                    e.source == metric.id
+                        && e.edge_type == edge_type
+                        && e.label.as_deref() != Some("filter")
+                })
+                .filter_map(|e| nodes.get(e.target.as_str()).copied())
+                .collect()
+        };
+
+        let metric_type = metric
+            .metadata
+            .get("metric_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("simple");
+        let article = if metric_type.starts_with(['a', 'e', 'i', 'o', 'u']) {
+            "an"
+        } else {
+            "a"
+        };
+        let mut sentence = format!("{} is {} {} metric", metric.name, article, metric_type);
+
+        let inputs: Vec<String> = direct(LineageEdgeType::MetricToMetric)
+            .iter()
+            .map(|n| n.name.clone())
+            .collect();
+        if !inputs.is_empty() {
+            sentence.push_str(&format!(
+                " computed from {} {}",
+                plural("metric", inputs.len()),
+                join_names(&inputs)
+            ));
+        }
+
+        let measures = direct(LineageEdgeType::MetricToMeasure);
+        if !measures.is_empty() {
+            let described: Vec<String> = measures
+                .iter()
+                .map(
+                    |m| match m.metadata.get("semantic_model").and_then(|v| v.as_str()) {
+                        Some(sm) => format!("{} in semantic model {}", m.name, sm),
+                        None => m.name.clone(),
+                    },
+                )
+                .collect();
+            sentence.push_str(&format!(
+                "{} derived from {} {}",
+                if inputs.is_empty() { "" } else { " and" },
+                plural("measure", measures.len()),
+                join_names(&described)
+            ));
+
+            // Walk up from the measures without crossing back into metrics
+            let mut upstream: HashMap<&str, Vec<&str>> = HashMap::new();
+            for edge in &graph.edges {
+                if edge.edge_type != LineageEdgeType::ModelToMetric {
+                    upstream.entry(&edge.source).or_default().push(&edge.target);
                 }
             }
+            let mut reached: HashSet<&str> = HashSet::new();
+            for measure in &measures {
+                reached.insert(&measure.id);
+                reached.extend(reachable(&upstream, &measure.id));
+            }
+
+            let mut models: Vec<String> = graph
+                .edges
+                .iter()
+                .filter(|e| {
+                    matches!(
+                        e.edge_type,
+                        LineageEdgeType::EntityToModel | LineageEdgeType::MeasureToModel
+                    ) && reached.contains(e.source.as_str())
+                })
+                .filter_map(|e| nodes.get(e.target.as_str()).map(|n| n.name.clone()))
+                .collect();
+            models.sort();
+            models.dedup();
+            let mut sources: Vec<String> = reached
+                .iter()
+                .filter_map(|id| nodes.get(id))
+                .filter(|n| n.node_type == LineageNodeType::Source)
+                .map(
+                    |n| match n.metadata.get("source_name").and_then(|v| v.as_str()) {
+                        Some(source_name) => format!("{}.{}", source_name, n.name),
+                        None => n.name.clone(),
+                    },
+                )
+                .collect();
+            sources.sort();
+            sources.dedup();
+
+            if !models.is_empty() {
+                sentence.push_str(&format!(
+                    ", built on {} {}",
+                    plural("model", models.len()),
+                    join_names(&models)
+                ));
+            }
+            if !sources.is_empty() {
+                sentence.push_str(&format!(
+                    " from {} {}",
+                    plural("source", sources.len()),
+                    join_names(&sources)
+                ));
+            }
         }
 
-        false
+        sentence.push('.');
+        Some(sentence)
     }
 
-    fn calculate_documentation_coverage(&self, graph: &LineageGraph) -> f64 {
-        if graph.nodes.is_empty() {
+    fn calculate_ownership_coverage(&self, metrics: &[Metric]) -> f64 {
+        if metrics.is_empty() {
             return 100.0;
         }
 
-        let documented = graph
-            .nodes
+        let owned = metrics
             .iter()
-            .filter(|n| n.description.is_some())
+            .filter(|m| m.meta.contains_key("owner"))
             .count();
 
-        (documented as f64 / graph.nodes.len() as f64) * 100.0
+        (owned as f64 / metrics.len() as f64) * 100.0
     }
 
     fn calculate_model_coverage(&self, models: &[DbtModel], semantic_models: &[SemanticModel]) -> f64 {
@@ -348,8 +2525,1137 @@ impl LineageAnalyzer {
     }
 }
 
+/// A model counts as tested with a data test on any column or any unit test
+fn is_tested(model: &DbtModel) -> bool {
+    model.unit_test_count > 0 || model.columns.iter().any(|c| !c.tests.is_empty())
+}
+
+/// Whether a cumulative `window` is a positive count and a time grain,
+/// singular or plural, such as `1 month` or `7 days`
+fn is_valid_window(window: &str) -> bool {
+    let mut parts = window.split_whitespace();
+    let (Some(count), Some(grain), None) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    let grain = grain.to_lowercase();
+    let grain = grain.strip_suffix('s').unwrap_or(&grain);
+    count.parse::<u32>().is_ok_and(|c| c > 0) && TIME_GRAINS.contains(&grain)
+}
+
+/// `a`, `a and b`, or `a, b and c`
+fn join_names(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+fn plural(noun: &str, count: usize) -> String {
+    if count == 1 {
+        noun.to_string()
+    } else {
+        format!("{}s", noun)
+    }
+}
+
+/// Nodes reachable from `start` through `adjacency`, excluding `start` itself
+fn reachable<'a>(adjacency: &HashMap<&'a str, Vec<&'a str>>, start: &'a str) -> HashSet<&'a str> {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(current) = queue.pop_front() {
+        for &next in adjacency.get(current).into_iter().flatten() {
+            if next != start && visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    visited
+}
+
+fn tree_node<'a>(
+    node: &'a LineageNode,
+    nodes: &HashMap<&str, &'a LineageNode>,
+    upstream: &HashMap<&str, Vec<&'a str>>,
+    expanded: &mut HashSet<&'a str>,
+) -> MetricTreeNode {
+    if !expanded.insert(node.id.as_str()) {
+        return MetricTreeNode {
+            id: node.id.clone(),
+            name: node.name.clone(),
+            node_type: node.node_type.clone(),
+            is_ref: true,
+            children: Vec::new(),
+        };
+    }
+
+    let children = upstream
+        .get(node.id.as_str())
+        .into_iter()
+        .flatten()
+        .filter_map(|id| nodes.get(id))
+        .map(|child| tree_node(child, nodes, upstream, expanded))
+        .collect();
+
+    MetricTreeNode {
+        id: node.id.clone(),
+        name: node.name.clone(),
+        node_type: node.node_type.clone(),
+        is_ref: false,
+        children,
+    }
+}
+
+/// Check whether lowercased SQL mentions a column as a whole identifier
+fn sql_mentions_column(sql: &str, column: &str) -> bool {
+    let pattern = format!(r"\b{}\b", regex::escape(&column.to_lowercase()));
+    Regex::new(&pattern).is_ok_and(|re| re.is_match(sql))
+}
+
 impl Default for LineageAnalyzer {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        DbtFreshness, DbtSourceRef, Dimension, DimensionTypeParams, LineageEdge, Measure,
+        MeasureRef, MetricRef, MetricTypeParams, NonAdditiveDimension, SemanticEntity,
+    };
+
+    fn column(name: &str) -> DbtColumn {
+        DbtColumn {
+            name: name.to_string(),
+            description: None,
+            data_type: None,
+            meta: HashMap::new(),
+            tests: Vec::new(),
+        }
+    }
+
+    fn source(source_name: &str, name: &str, columns: Vec<DbtColumn>) -> DbtSource {
+        DbtSource {
+            unique_id: format!("source.{}.{}", source_name, name),
+            source_name: source_name.to_string(),
+            name: name.to_string(),
+            schema: None,
+            database: None,
+            qualified_name: None,
+            description: None,
+            columns,
+            loader: None,
+            freshness: None,
+            loaded_at_field: None,
+            tags: Vec::new(),
+            project: None,
+        }
+    }
+
+    fn model(name: &str, sql: &str, sources: Vec<DbtSourceRef>) -> DbtModel {
+        DbtModel {
+            unique_id: format!("model.{}", name),
+            name: name.to_string(),
+            schema: None,
+            database: None,
+            qualified_name: None,
+            alias: None,
+            description: None,
+            columns: Vec::new(),
+            depends_on: Vec::new(),
+            refs: Vec::new(),
+            sources,
+            metric_refs: Vec::new(),
+            file_path: format!("models/{}.sql", name),
+            raw_sql: Some(sql.to_string()),
+            materialization: None,
+            tags: Vec::new(),
+            access: None,
+            group: None,
+            unit_test_count: 0,
+            unique_key: Vec::new(),
+            duplicate_columns: Vec::new(),
+            source_location: None,
+            project: None,
+        }
+    }
+
+    fn metric(name: &str, owner: Option<&str>) -> Metric {
+        let mut meta = HashMap::new();
+        if let Some(owner) = owner {
+            meta.insert("owner".to_string(), serde_json::json!(owner));
+        }
+
+        Metric {
+            name: name.to_string(),
+            description: None,
+            metric_type: "simple".to_string(),
+            group: None,
+            type_params: MetricTypeParams {
+                measure: None,
+                expr: None,
+                metrics: None,
+                window: None,
+                grain_to_date: None,
+                conversion_type_params: None,
+            },
+            filter: None,
+            filter_references: Vec::new(),
+            label: None,
+            meta,
+            config: None,
+            source_location: None,
+            project: None,
+        }
+    }
+
+    #[test]
+    fn test_consolidation_groups_reordered_names() {
+        let with_measure = |name: &str, measure: &str| {
+            let mut m = metric(name, None);
+            m.type_params.measure = Some(MeasureRef {
+                name: measure.to_string(),
+                filter: None,
+                alias: None,
+                fill_nulls_with: None,
+                join_to_timespine: None,
+            });
+            m
+        };
+        let metrics = vec![
+            with_measure("total_revenue", "revenue"),
+            with_measure("Revenue_Total", "revenue"),
+            with_measure("revenue", "revenue"),
+            with_measure("order_count", "orders"),
+            with_measure("count_order", "order_lines"),
+        ];
+
+        let hints = LineageAnalyzer::new().consolidation_hints(&metrics);
+
+        assert_eq!(hints.len(), 2);
+        assert_eq!(hints[0].normalized_name, "count_order");
+        assert_eq!(hints[0].shared_measure, None);
+        assert_eq!(hints[1].normalized_name, "revenue_total");
+        assert_eq!(hints[1].metrics, vec!["Revenue_Total", "total_revenue"]);
+        assert_eq!(hints[1].shared_measure.as_deref(), Some("revenue"));
+    }
+
+    fn balance_model(non_additive_name: &str, window_choice: &str) -> SemanticModel {
+        SemanticModel {
+            name: "accounts".to_string(),
+            description: None,
+            model: "ref('fct_accounts')".to_string(),
+            node_relation: None,
+            group: None,
+            primary_entity: None,
+            defaults: None,
+            entities: Vec::new(),
+            measures: vec![Measure {
+                name: "balance".to_string(),
+                agg: "sum".to_string(),
+                expr: None,
+                description: None,
+                label: None,
+                create_metric: None,
+                non_additive_dimension: Some(NonAdditiveDimension {
+                    name: non_additive_name.to_string(),
+                    window_choice: Some(window_choice.to_string()),
+                }),
+                agg_time_dimension: None,
+                fill_nulls_with: None,
+                join_to_timespine: None,
+            }],
+            dimensions: vec![Dimension {
+                name: "balance_date".to_string(),
+                dimension_type: "time".to_string(),
+                expr: None,
+                description: None,
+                label: None,
+                type_params: None,
+            }],
+            source_location: None,
+            meta: HashMap::new(),
+            project: None,
+        }
+    }
+
+    fn empty_graph() -> LineageGraph {
+        LineageGraph {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_summary_breaks_down_metric_types_and_aggs() {
+        let mut revenue_growth = metric("revenue_growth", None);
+        revenue_growth.metric_type = "derived".to_string();
+        let mut cumulative_revenue = metric("cumulative_revenue", None);
+        cumulative_revenue.metric_type = "cumulative".to_string();
+        let metrics = vec![
+            metric("revenue", None),
+            metric("order_count", None),
+            revenue_growth,
+            cumulative_revenue,
+        ];
+        let mut accounts = balance_model("balance_date", "max");
+        let mut account_count = accounts.measures[0].clone();
+        account_count.name = "account_count".to_string();
+        account_count.agg = "count_distinct".to_string();
+        accounts.measures.push(account_count);
+
+        let audit = LineageAnalyzer::new().analyze(&empty_graph(), &[], &[], &[accounts], &metrics);
+
+        let by_type = &audit.summary.metrics_by_type;
+        assert_eq!(by_type.len(), 3);
+        assert_eq!(by_type["simple"], 2);
+        assert_eq!(by_type["derived"], 1);
+        assert_eq!(by_type["cumulative"], 1);
+        let by_agg = &audit.summary.measures_by_agg;
+        assert_eq!(by_agg.len(), 2);
+        assert_eq!(by_agg["sum"], 1);
+        assert_eq!(by_agg["count_distinct"], 1);
+    }
+
+    #[test]
+    fn test_measure_filter_on_undefined_dimension() {
+        let mut accounts = balance_model("balance_date", "max");
+        accounts.dimensions.push(Dimension {
+            name: "account_type".to_string(),
+            dimension_type: "categorical".to_string(),
+            expr: None,
+            description: None,
+            label: None,
+            type_params: None,
+        });
+        let filtered = |name: &str, filter: &str| {
+            let mut m = metric(name, None);
+            m.type_params.measure = Some(MeasureRef {
+                name: "balance".to_string(),
+                filter: Some(filter.to_string()),
+                alias: None,
+                fill_nulls_with: None,
+                join_to_timespine: None,
+            });
+            m
+        };
+        let metrics = vec![
+            filtered(
+                "checking_balance",
+                "{{ Dimension('account__account_type') }} = 'checking'",
+            ),
+            filtered(
+                "regional_balance",
+                "{{ Dimension('account__region') }} = 'EU'",
+            ),
+        ];
+
+        let issues =
+            LineageAnalyzer::new().check_measure_filters(&empty_graph(), &[accounts], &metrics);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::MissingDimension);
+        assert!(issues[0].message.contains("'regional_balance'"));
+        assert!(issues[0].message.contains("'region'"));
+    }
+
+    fn chain_graph(model_description: Option<&str>, with_source: bool) -> LineageGraph {
+        let node = |id: &str, node_type: LineageNodeType, description: Option<&str>| LineageNode {
+            id: id.to_string(),
+            node_type,
+            name: id.to_string(),
+            description: description.map(|d| d.to_string()),
+            metadata: HashMap::new(),
+        };
+        let edge = |source: &str, target: &str, edge_type| LineageEdge {
+            id: format!("{}->{}", source, target),
+            source: source.to_string(),
+            target: target.to_string(),
+            edge_type,
+            label: None,
+        };
+
+        let mut graph = LineageGraph {
+            nodes: vec![
+                node("revenue", LineageNodeType::Metric, Some("Revenue")),
+                node("fct_orders", LineageNodeType::Model, model_description),
+                node("raw_orders", LineageNodeType::Source, None),
+            ],
+            edges: vec![edge(
+                "revenue",
+                "fct_orders",
+                LineageEdgeType::MetricToMeasure,
+            )],
+        };
+        if with_source {
+            graph.edges.push(edge(
+                "fct_orders",
+                "raw_orders",
+                LineageEdgeType::ModelToSource,
+            ));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_graph_stats_counts_isolated_node_as_component() {
+        let mut graph = chain_graph(None, false);
+
+        let stats = LineageAnalyzer::new().graph_stats(&graph);
+        assert_eq!(stats.node_count, 3);
+        assert_eq!(stats.edge_count, 1);
+        assert_eq!(stats.component_count, 2);
+        assert_eq!(stats.longest_path, Some(1));
+        assert_eq!(stats.nodes_by_type["Source"], 1);
+
+        graph.edges.push(LineageEdge {
+            id: "fct_orders->revenue".to_string(),
+            source: "fct_orders".to_string(),
+            target: "revenue".to_string(),
+            edge_type: LineageEdgeType::ModelToMetric,
+            label: None,
+        });
+        let cyclic = LineageAnalyzer::new().graph_stats(&graph);
+        assert!(cyclic.has_cycle);
+        assert_eq!(cyclic.longest_path, None);
+    }
+
+    #[test]
+    fn test_column_documentation_coverage() {
+        let mut documented = column("order_id");
+        documented.description = Some("Primary key".to_string());
+        let mut orders = model("fct_orders", "select 1", Vec::new());
+        orders.columns = vec![documented, column("amount")];
+        let raw_orders = source("shop", "orders", vec![column("id")]);
+        let mut raw_customers = source("shop", "customers", vec![column("id")]);
+        raw_customers.columns[0].description = Some("Customer key".to_string());
+
+        let audit = LineageAnalyzer::new().analyze(
+            &empty_graph(),
+            &[orders],
+            &[raw_orders, raw_customers],
+            &[],
+            &[],
+        );
+
+        assert_eq!(audit.column_documentation_coverage, 50.0);
+        assert_eq!(audit.documentation_coverage, 100.0);
+        assert_eq!(
+            LineageAnalyzer::new().calculate_column_documentation_coverage(&[], &[]),
+            100.0
+        );
+    }
+
+    fn completeness(policy: CompletenessPolicy, graph: &LineageGraph, models: &[DbtModel]) -> f64 {
+        LineageAnalyzer::new()
+            .with_completeness_policy(policy)
+            .analyze(graph, models, &[], &[], &[metric("revenue", None)])
+            .completeness_score
+    }
+
+    #[test]
+    fn test_completeness_policy_source_path() {
+        let models = [model("fct_orders", "select 1", Vec::new())];
+        let lenient = CompletenessPolicy {
+            require_source_path: false,
+            ..CompletenessPolicy::default()
+        };
+
+        assert_eq!(
+            completeness(
+                CompletenessPolicy::default(),
+                &chain_graph(None, true),
+                &models
+            ),
+            100.0
+        );
+        assert_eq!(
+            completeness(
+                CompletenessPolicy::default(),
+                &chain_graph(None, false),
+                &models
+            ),
+            0.0
+        );
+        assert_eq!(
+            completeness(lenient, &chain_graph(None, false), &models),
+            100.0
+        );
+    }
+
+    #[test]
+    fn test_completeness_policy_requires_tests() {
+        let untested = [model("fct_orders", "select 1", Vec::new())];
+        let mut tested = untested.clone();
+        tested[0].columns = vec![column("order_id")];
+        tested[0].columns[0].tests = vec!["unique".to_string()];
+        let policy = CompletenessPolicy {
+            require_tests: true,
+            ..CompletenessPolicy::default()
+        };
+        let graph = chain_graph(None, true);
+
+        assert_eq!(completeness(policy.clone(), &graph, &untested), 0.0);
+        assert_eq!(completeness(policy, &graph, &tested), 100.0);
+    }
+
+    #[test]
+    fn test_completeness_policy_requires_descriptions() {
+        let models = [model("fct_orders", "select 1", Vec::new())];
+        let policy = CompletenessPolicy {
+            require_descriptions: true,
+            ..CompletenessPolicy::default()
+        };
+
+        assert_eq!(
+            completeness(policy.clone(), &chain_graph(None, true), &models),
+            0.0
+        );
+        assert_eq!(
+            completeness(policy, &chain_graph(Some("Orders fact"), true), &models),
+            100.0
+        );
+    }
+
+    #[test]
+    fn test_issue_and_severity_counts() {
+        let issue = |severity: IssueSeverity, issue_type: IssueType| AuditIssue {
+            severity,
+            issue_type,
+            message: String::new(),
+            node_id: None,
+            suggestion: None,
+            source_location: None,
+        };
+        let diagnostics = vec![
+            issue(IssueSeverity::Error, IssueType::CircularDependency),
+            issue(IssueSeverity::Warning, IssueType::MissingDescription),
+            issue(IssueSeverity::Warning, IssueType::MissingDescription),
+            issue(IssueSeverity::Info, IssueType::OrphanedModel),
+        ];
+
+        let audit = LineageAnalyzer::new()
+            .with_diagnostics(diagnostics)
+            .analyze(&empty_graph(), &[], &[], &[], &[]);
+
+        assert_eq!(audit.issues.len(), 4);
+        assert_eq!(audit.issue_counts.len(), 3);
+        assert_eq!(audit.issue_counts["MissingDescription"], 2);
+        assert_eq!(audit.issue_counts["CircularDependency"], 1);
+        assert_eq!(audit.issue_counts["OrphanedModel"], 1);
+        assert_eq!(audit.severity_counts["Error"], 1);
+        assert_eq!(audit.severity_counts["Warning"], 2);
+        assert_eq!(audit.severity_counts["Info"], 1);
+    }
+
+    #[test]
+    fn test_foreign_only_entity_is_many_to_many() {
+        let entity = |name: &str, entity_type: &str| SemanticEntity {
+            name: name.to_string(),
+            entity_type: entity_type.to_string(),
+            expr: None,
+            description: None,
+        };
+        let mut orders = balance_model("balance_date", "max");
+        orders.name = "orders".to_string();
+        orders.entities = vec![
+            entity("order_id", "primary"),
+            entity("customer_id", "foreign"),
+        ];
+        let mut returns = orders.clone();
+        returns.name = "returns".to_string();
+        returns.entities = vec![
+            entity("return_id", "primary"),
+            entity("customer_id", "foreign"),
+            entity("order_id", "foreign"),
+        ];
+
+        let issues =
+            LineageAnalyzer::new().check_entity_cardinality(&empty_graph(), &[orders, returns]);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::ManyToManyJoin);
+        assert_eq!(issues[0].severity, IssueSeverity::Warning);
+        assert!(issues[0]
+            .message
+            .contains("'customer_id' is foreign in orders, returns"));
+    }
+
+    #[test]
+    fn test_entity_with_conflicting_exprs() {
+        let entity = |name: &str, expr: Option<&str>| SemanticEntity {
+            name: name.to_string(),
+            entity_type: "foreign".to_string(),
+            expr: expr.map(|e| e.to_string()),
+            description: None,
+        };
+        let mut orders = balance_model("balance_date", "max");
+        orders.name = "orders".to_string();
+        orders.entities = vec![entity("customer_id", None), entity("order_id", None)];
+        let mut payments = orders.clone();
+        payments.name = "payments".to_string();
+        payments.entities = vec![
+            entity("customer_id", Some("cast(customer_id as varchar)")),
+            entity("order_id", Some("order_ref")),
+        ];
+
+        let issues = LineageAnalyzer::new()
+            .check_entity_expr_consistency(&empty_graph(), &[orders, payments]);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::EntityExprMismatch);
+        assert_eq!(
+            issues[0].message,
+            "Entity 'customer_id' is keyed differently across semantic models: \
+             orders uses `customer_id`, payments uses `cast(customer_id as varchar)`"
+        );
+    }
+
+    #[test]
+    fn test_dimension_available_through_entity_join() {
+        let entity = |name: &str, entity_type: &str| SemanticEntity {
+            name: name.to_string(),
+            entity_type: entity_type.to_string(),
+            expr: None,
+            description: None,
+        };
+        let dimension = |name: &str, dimension_type: &str| Dimension {
+            name: name.to_string(),
+            dimension_type: dimension_type.to_string(),
+            expr: None,
+            description: None,
+            label: None,
+            type_params: None,
+        };
+        let mut orders = balance_model("balance_date", "max");
+        orders.name = "orders".to_string();
+        orders.measures[0].name = "order_total".to_string();
+        orders.entities = vec![
+            entity("order_id", "primary"),
+            entity("customer_id", "foreign"),
+        ];
+        orders.dimensions = vec![dimension("ordered_at", "time")];
+        let mut customers = balance_model("balance_date", "max");
+        customers.name = "customers".to_string();
+        customers.measures.clear();
+        customers.entities = vec![entity("customer_id", "primary")];
+        customers.dimensions = vec![dimension("region", "categorical")];
+        let mut stores = customers.clone();
+        stores.name = "stores".to_string();
+        stores.entities = vec![entity("store_id", "primary")];
+        stores.dimensions = vec![dimension("store_name", "categorical")];
+        let mut revenue = metric("revenue", None);
+        revenue.type_params.measure = Some(MeasureRef {
+            name: "order_total".to_string(),
+            filter: None,
+            alias: None,
+            fill_nulls_with: None,
+            join_to_timespine: None,
+        });
+
+        let dimensions = LineageAnalyzer::new()
+            .dimension_availability(&[orders, customers, stores], &[revenue], "revenue")
+            .unwrap();
+
+        assert_eq!(
+            dimensions,
+            vec!["customer_id__region", "order_id__ordered_at"]
+        );
+        assert!(LineageAnalyzer::new()
+            .dimension_availability(&[], &[], "missing")
+            .is_none());
+    }
+
+    #[test]
+    fn test_unit_tests_count_as_tests() {
+        let mut stg_orders = model("stg_orders", "select 1 as id", Vec::new());
+        stg_orders.unit_test_count = 1;
+        let stg_customers = model("stg_customers", "select 1 as id", Vec::new());
+        let models = vec![stg_orders, stg_customers];
+
+        let audit = LineageAnalyzer::new().analyze(&empty_graph(), &models, &[], &[], &[]);
+
+        let untested: Vec<_> = audit
+            .issues
+            .iter()
+            .filter(|i| i.issue_type == IssueType::NoTests)
+            .collect();
+        assert_eq!(untested.len(), 1);
+        assert!(untested[0].message.contains("'stg_customers'"));
+        assert_eq!(audit.summary.tested_models, 1);
+    }
+
+    #[test]
+    fn test_duplicate_column() {
+        let mut stg_orders = model("stg_orders", "select 1 as id", Vec::new());
+        stg_orders.columns.push(column("id"));
+        stg_orders.duplicate_columns = vec!["id".to_string()];
+        let stg_customers = model("stg_customers", "select 1 as id", Vec::new());
+
+        let issues = LineageAnalyzer::new()
+            .check_duplicate_columns(&empty_graph(), &[stg_orders, stg_customers]);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::DuplicateName);
+        assert!(issues[0].message.contains("'id'"));
+        assert!(issues[0].message.contains("'stg_orders'"));
+    }
+
+    #[test]
+    fn test_incremental_model_without_unique_key() {
+        let incremental = |name: &str, unique_key: &[&str]| {
+            let mut m = model(name, "select 1 as id", Vec::new());
+            m.materialization = Some("incremental".to_string());
+            m.unique_key = unique_key.iter().map(|k| k.to_string()).collect();
+            m
+        };
+        let models = vec![
+            incremental("fct_events", &[]),
+            incremental("fct_orders", &["order_id"]),
+            model("stg_events", "select 1 as id", Vec::new()),
+        ];
+
+        let issues =
+            LineageAnalyzer::new().check_incremental_without_unique_key(&empty_graph(), &models);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::IncrementalWithoutUniqueKey);
+        assert!(issues[0].message.contains("'fct_events'"));
+    }
+
+    #[test]
+    fn test_missing_owner_is_gated() {
+        let metrics = vec![metric("revenue", Some("finance")), metric("churn", None)];
+
+        let lenient = LineageAnalyzer::new().analyze(&empty_graph(), &[], &[], &[], &metrics);
+        assert!(!lenient
+            .issues
+            .iter()
+            .any(|i| matches!(i.issue_type, IssueType::MissingOwner)));
+
+        let strict = LineageAnalyzer::new()
+            .with_required_owner(true)
+            .analyze(&empty_graph(), &[], &[], &[], &metrics);
+        let owner_issues: Vec<_> = strict
+            .issues
+            .iter()
+            .filter(|i| matches!(i.issue_type, IssueType::MissingOwner))
+            .collect();
+        assert_eq!(owner_issues.len(), 1);
+        assert_eq!(owner_issues[0].severity, IssueSeverity::Warning);
+        assert!(owner_issues[0].message.contains("'churn'"));
+    }
+
+    #[test]
+    fn test_derived_expr_missing_metric_ref() {
+        let mut profit_margin = metric("profit_margin", None);
+        profit_margin.metric_type = "derived".to_string();
+        profit_margin.type_params.expr = Some("profit / revenue".to_string());
+        profit_margin.type_params.metrics = Some(vec![MetricRef {
+            name: "revenue".to_string(),
+            alias: None,
+            offset_window: None,
+            offset_to_grain: None,
+        }]);
+
+        let issues = LineageAnalyzer::new().check_derived_metric_refs(&empty_graph(), &[profit_margin]);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::MissingMetricRef);
+        assert!(issues[0].message.contains("'profit'"));
+    }
+
+    #[test]
+    fn test_valid_non_additive_dimension() {
+        let issues = LineageAnalyzer::new()
+            .check_non_additive_dimensions(&empty_graph(), &[balance_model("balance_date", "max")]);
+
+        assert!(issues.is_empty());
+    }
+
+    fn fresh_source(
+        warn_after: Option<(i32, &str)>,
+        error_after: Option<(i32, &str)>,
+        loaded_at_field: Option<&str>,
+    ) -> DbtSource {
+        let rule = |(count, period): (i32, &str)| DbtFreshnessRule {
+            count,
+            period: period.to_string(),
+        };
+        let mut raw_orders = source("raw", "orders", Vec::new());
+        raw_orders.freshness = Some(DbtFreshness {
+            warn_after: warn_after.map(rule),
+            error_after: error_after.map(rule),
+        });
+        raw_orders.loaded_at_field = loaded_at_field.map(|s| s.to_string());
+        raw_orders
+    }
+
+    #[test]
+    fn test_freshness_without_loaded_at_field() {
+        let sources = vec![
+            fresh_source(Some((12, "hour")), Some((1, "day")), Some("_loaded_at")),
+            fresh_source(None, Some((1, "day")), None),
+        ];
+
+        let issues = LineageAnalyzer::new().check_stale_source_thresholds(&empty_graph(), &sources);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::InvalidFreshness);
+        assert!(issues[0].message.contains("'raw.orders'"));
+        assert!(issues[0].message.contains("loaded_at_field"));
+    }
+
+    #[test]
+    fn test_freshness_warn_after_exceeds_error_after() {
+        let sources = vec![fresh_source(
+            Some((2, "day")),
+            Some((24, "hour")),
+            Some("_loaded_at"),
+        )];
+
+        let issues = LineageAnalyzer::new().check_stale_source_thresholds(&empty_graph(), &sources);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::InvalidFreshness);
+        assert!(issues[0]
+            .message
+            .contains("warns after 2 day but errors after 24 hour"));
+    }
+
+    #[test]
+    fn test_disallowed_agg() {
+        let mut accounts = balance_model("balance_date", "max");
+        accounts.measures[0].agg = "count_distinct".to_string();
+
+        let lenient =
+            LineageAnalyzer::new().check_measure_aggs(&empty_graph(), &[accounts.clone()]);
+        assert!(lenient.is_empty());
+
+        let issues = LineageAnalyzer::new()
+            .with_allowed_aggs(Some(vec!["sum".to_string(), "count".to_string()]))
+            .check_measure_aggs(&empty_graph(), &[accounts]);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::DisallowedAggregation);
+        assert_eq!(issues[0].severity, IssueSeverity::Warning);
+        assert!(issues[0].message.contains("'count_distinct'"));
+    }
+
+    #[test]
+    fn test_measure_columns() {
+        let mut accounts = balance_model("balance_date", "max");
+        accounts.model = "fct_accounts".to_string();
+        accounts.measures[0].expr = Some("amount".to_string());
+        let mut opened = accounts.measures[0].clone();
+        opened.name = "accounts_opened".to_string();
+        opened.expr = Some("case when is_open then 1 else 0 end".to_string());
+        accounts.measures.push(opened.clone());
+        let mut fees = opened.clone();
+        fees.name = "fees".to_string();
+        fees.expr = Some("coalesce(cast(fee_cents as float), 0) / 100".to_string());
+        accounts.measures.push(fees);
+        let mut age = opened;
+        age.name = "account_age".to_string();
+        age.expr = Some("extract(epoch from closed_at - opened_at)::bigint".to_string());
+        accounts.measures.push(age);
+
+        let columns = LineageAnalyzer::new().measure_columns(&[accounts]);
+
+        assert_eq!(columns.len(), 4);
+        assert_eq!(columns[0].measure_name, "balance");
+        assert_eq!(columns[0].semantic_model, "accounts");
+        assert_eq!(columns[0].model_name, "fct_accounts");
+        assert_eq!(columns[0].columns, vec!["amount"]);
+        assert_eq!(columns[1].columns, vec!["is_open"]);
+        assert_eq!(columns[2].columns, vec!["fee_cents"]);
+        assert_eq!(columns[3].columns, vec!["closed_at", "opened_at"]);
+    }
+
+    #[test]
+    fn test_unused_measure() {
+        let mut accounts = balance_model("balance_date", "max");
+        let mut opened = accounts.measures[0].clone();
+        opened.name = "accounts_opened".to_string();
+        opened.non_additive_dimension = None;
+        accounts.measures.push(opened);
+
+        let mut total_balance = metric("total_balance", None);
+        total_balance.type_params.measure = Some(MeasureRef {
+            name: "balance".to_string(),
+            filter: None,
+            alias: None,
+            fill_nulls_with: None,
+            join_to_timespine: None,
+        });
+
+        let issues = LineageAnalyzer::new().check_unused_measures(
+            &empty_graph(),
+            &[accounts],
+            &[total_balance],
+        );
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::UnusedMeasure);
+        assert_eq!(issues[0].severity, IssueSeverity::Info);
+        assert!(issues[0].message.contains("'accounts.accounts_opened'"));
+    }
+
+    #[test]
+    fn test_invalid_agg() {
+        let mut accounts = balance_model("balance_date", "max");
+        accounts.measures[0].agg = "summ".to_string();
+
+        let issues = LineageAnalyzer::new().check_measure_aggs(&empty_graph(), &[accounts]);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::InvalidAggregation);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+        assert!(issues[0].message.contains("'summ'"));
+    }
+
+    #[test]
+    fn test_dangling_non_additive_dimension() {
+        let issues = LineageAnalyzer::new().check_non_additive_dimensions(
+            &empty_graph(),
+            &[balance_model("balanse_date", "last")],
+        );
+
+        assert_eq!(issues.len(), 2);
+        assert!(issues
+            .iter()
+            .all(|i| i.issue_type == IssueType::InvalidNonAdditiveDimension));
+        assert!(issues[0].message.contains("'balanse_date'"));
+        assert!(issues[1].message.contains("'last'"));
+    }
+
+    #[test]
+    fn test_cumulative_with_window_and_grain_to_date() {
+        let mut balance_mtd = metric("balance_mtd", None);
+        balance_mtd.metric_type = "cumulative".to_string();
+        balance_mtd.type_params.window = Some("7 days".to_string());
+        balance_mtd.type_params.grain_to_date = Some("month".to_string());
+
+        let issues = LineageAnalyzer::new().check_cumulative_params(&empty_graph(), &[balance_mtd]);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::InvalidCumulativeParams);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+    }
+
+    #[test]
+    fn test_cumulative_with_invalid_window() {
+        let cumulative = |name: &str, window: &str| {
+            let mut metric = metric(name, None);
+            metric.metric_type = "cumulative".to_string();
+            metric.type_params.window = Some(window.to_string());
+            metric
+        };
+        let metrics = [
+            cumulative("weekly_revenue", "7 days"),
+            cumulative("monthly_revenue", "1 month"),
+            cumulative("fortnightly_revenue", "2 fortnights"),
+            cumulative("rolling_revenue", "seven days"),
+        ];
+
+        let issues = LineageAnalyzer::new().check_cumulative_params(&empty_graph(), &metrics);
+
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|i| i.severity == IssueSeverity::Warning));
+        assert!(issues[0].message.contains("'2 fortnights'"));
+        assert!(issues[1].message.contains("'seven days'"));
+    }
+
+    #[test]
+    fn test_cumulative_measure_without_timespine_join() {
+        let mut accounts = balance_model("balance_date", "sum");
+        let mut joined = accounts.measures[0].clone();
+        joined.name = "joined_balance".to_string();
+        joined.join_to_timespine = Some(true);
+        accounts.measures.push(joined);
+        let cumulative = |name: &str, measure: &str, join_to_timespine: Option<bool>| {
+            let mut metric = metric(name, None);
+            metric.metric_type = "cumulative".to_string();
+            metric.type_params.measure = Some(MeasureRef {
+                name: measure.to_string(),
+                filter: None,
+                alias: None,
+                fill_nulls_with: None,
+                join_to_timespine,
+            });
+            metric
+        };
+        let metrics = [
+            cumulative("running_balance", "balance", None),
+            cumulative("running_joined_balance", "joined_balance", None),
+            // The metric input overrides the measure definition either way
+            cumulative("running_balance_on_spine", "balance", Some(true)),
+            cumulative("running_balance_off_spine", "joined_balance", Some(false)),
+        ];
+
+        let issues = LineageAnalyzer::new().check_cumulative_timespine(
+            &empty_graph(),
+            &[accounts],
+            &metrics,
+        );
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].issue_type, IssueType::MissingTimeSpineJoin);
+        assert!(issues[0].message.contains("'running_balance'"));
+        assert!(issues[1].message.contains("'running_balance_off_spine'"));
+    }
+
+    #[test]
+    fn test_derived_metric_on_deprecated_metric() {
+        let mut old_revenue = metric("old_revenue", None);
+        old_revenue
+            .meta
+            .insert("deprecated".to_string(), serde_json::json!(true));
+        let metrics = [
+            old_revenue,
+            metric("revenue", None),
+            metric("revenue_growth", None),
+        ];
+        let node = |id: &str| LineageNode {
+            id: id.to_string(),
+            node_type: LineageNodeType::Metric,
+            name: id.to_string(),
+            description: None,
+            metadata: HashMap::new(),
+        };
+        let edge = |source: &str, target: &str| LineageEdge {
+            id: format!("{}->{}", source, target),
+            source: source.to_string(),
+            target: target.to_string(),
+            edge_type: LineageEdgeType::MetricToMetric,
+            label: None,
+        };
+        let graph = LineageGraph {
+            nodes: vec![node("old_revenue"), node("revenue"), node("revenue_growth")],
+            edges: vec![
+                edge("revenue_growth", "old_revenue"),
+                edge("revenue_growth", "revenue"),
+                // old_revenue again, offset by a month
+                edge("revenue_growth", "old_revenue"),
+            ],
+        };
+
+        let issues = LineageAnalyzer::new().check_deprecated_usage(&graph, &metrics);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::DeprecatedMetricUsage);
+        assert_eq!(issues[0].node_id.as_deref(), Some("revenue_growth"));
+        assert_eq!(
+            issues[0].message,
+            "Metric 'revenue_growth' depends on deprecated metric 'old_revenue'"
+        );
+    }
+
+    #[test]
+    fn test_grain_finer_than_dimension() {
+        let mut accounts = balance_model("balance_date", "max");
+        accounts.dimensions[0].type_params = Some(DimensionTypeParams {
+            time_granularity: Some("month".to_string()),
+            validity_params: None,
+        });
+        let mut balance_mtd = metric("balance_mtd", None);
+        balance_mtd.metric_type = "cumulative".to_string();
+        balance_mtd.type_params.grain_to_date = Some("day".to_string());
+        balance_mtd.type_params.measure = Some(MeasureRef {
+            name: "balance".to_string(),
+            filter: None,
+            alias: None,
+            fill_nulls_with: None,
+            join_to_timespine: None,
+        });
+
+        let analyzer = LineageAnalyzer::new();
+        let issues = analyzer.check_grain_consistency(
+            &empty_graph(),
+            &[accounts.clone()],
+            &[balance_mtd.clone()],
+        );
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::GrainMismatch);
+
+        balance_mtd.type_params.grain_to_date = Some("quarter".to_string());
+        assert!(analyzer
+            .check_grain_consistency(&empty_graph(), &[accounts], &[balance_mtd])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_private_model_ref_from_other_group() {
+        let mut finance_internal = model("finance_internal", "select 1", Vec::new());
+        finance_internal.access = Some("private".to_string());
+        finance_internal.group = Some("finance".to_string());
+        let mut marketing_report = model("marketing_report", "select 1", Vec::new());
+        marketing_report.group = Some("marketing".to_string());
+        marketing_report.refs = vec!["finance_internal".to_string()];
+        let mut finance_report = model("finance_report", "select 1", Vec::new());
+        finance_report.group = Some("finance".to_string());
+        finance_report.refs = vec!["finance_internal".to_string()];
+
+        let issues = LineageAnalyzer::new().check_access_violations(
+            &empty_graph(),
+            &[finance_internal, marketing_report, finance_report],
+        );
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::AccessViolation);
+        assert!(issues[0].message.starts_with("Model 'marketing_report'"));
+    }
+
+    #[test]
+    fn test_severity_override() {
+        let mut stg_orders = model("stg_orders", "select id from orders", Vec::new());
+        stg_orders.columns.push(column("id"));
+        let overrides = HashMap::from([(IssueType::UndocumentedColumn, IssueSeverity::Error)]);
+
+        let audit = LineageAnalyzer::new()
+            .with_severity_overrides(overrides)
+            .analyze(&empty_graph(), &[stg_orders], &[], &[], &[]);
+
+        let issue = audit
+            .issues
+            .iter()
+            .find(|i| i.issue_type == IssueType::UndocumentedColumn)
+            .unwrap();
+        assert_eq!(issue.severity, IssueSeverity::Error);
+    }
+
+    #[test]
+    fn test_ownership_coverage() {
+        let metrics = vec![metric("revenue", Some("finance")), metric("churn", None)];
+
+        let audit = LineageAnalyzer::new().analyze(&empty_graph(), &[], &[], &[], &metrics);
+
+        assert_eq!(audit.ownership_coverage, 50.0);
+        assert_eq!(audit.summary.owned_metrics, 1);
+    }
+
+    #[test]
+    fn test_unused_source_columns() {
+        let sources = vec![source(
+            "raw",
+            "orders",
+            vec![column("order_id"), column("legacy_flag")],
+        )];
+        let models = vec![model(
+            "stg_orders",
+            "SELECT ORDER_ID, amount FROM {{ source('raw', 'orders') }}",
+            vec![DbtSourceRef {
+                source_name: "raw".to_string(),
+                table_name: "orders".to_string(),
+            }],
+        )];
+        let issues =
+            LineageAnalyzer::new().check_unused_source_columns(&empty_graph(), &models, &sources);
+
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0].issue_type, IssueType::UnusedColumn));
+        assert!(issues[0].message.contains("'legacy_flag'"));
+    }
+}
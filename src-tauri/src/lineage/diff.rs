@@ -0,0 +1,188 @@
+//! Comparison of two lineage graphs from separate parse runs
+
+use crate::types::{EdgeChange, LineageDiff, LineageGraph, LineageNodeType};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Diff two lineage graphs.
+///
+/// Node IDs are random per run, so nodes are matched by their logical
+/// `{type}.{name}` key and edges by their endpoint keys and edge type.
+pub fn diff_graphs(before: &LineageGraph, after: &LineageGraph) -> LineageDiff {
+    let before_nodes = node_keys(before);
+    let after_nodes = node_keys(after);
+    let before_edges = edge_set(before, &before_nodes);
+    let after_edges = edge_set(after, &after_nodes);
+
+    let before_keys: BTreeSet<&String> = before_nodes.values().collect();
+    let after_keys: BTreeSet<&String> = after_nodes.values().collect();
+
+    let before_upstream = metric_upstreams(before, &before_nodes);
+    let after_upstream = metric_upstreams(after, &after_nodes);
+    let mut changed_metrics: Vec<String> = before_upstream
+        .iter()
+        .filter(|(name, upstream)| {
+            after_upstream
+                .get(*name)
+                .is_some_and(|after| after != *upstream)
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    changed_metrics.sort();
+
+    LineageDiff {
+        added_nodes: after_keys
+            .difference(&before_keys)
+            .map(|k| k.to_string())
+            .collect(),
+        removed_nodes: before_keys
+            .difference(&after_keys)
+            .map(|k| k.to_string())
+            .collect(),
+        added_edges: after_edges
+            .difference(&before_edges)
+            .map(to_edge_change)
+            .collect(),
+        removed_edges: before_edges
+            .difference(&after_edges)
+            .map(to_edge_change)
+            .collect(),
+        changed_metrics,
+    }
+}
+
+/// Map node IDs to their logical `{type}.{name}` key
+fn node_keys(graph: &LineageGraph) -> HashMap<&str, String> {
+    graph
+        .nodes
+        .iter()
+        .map(|n| (n.id.as_str(), format!("{:?}.{}", n.node_type, n.name)))
+        .collect()
+}
+
+fn edge_set(
+    graph: &LineageGraph,
+    keys: &HashMap<&str, String>,
+) -> BTreeSet<(String, String, String)> {
+    graph
+        .edges
+        .iter()
+        .filter_map(|e| {
+            Some((
+                keys.get(e.source.as_str())?.clone(),
+                keys.get(e.target.as_str())?.clone(),
+                format!("{:?}", e.edge_type),
+            ))
+        })
+        .collect()
+}
+
+fn to_edge_change((source, target, edge_type): &(String, String, String)) -> EdgeChange {
+    EdgeChange {
+        source: source.clone(),
+        target: target.clone(),
+        edge_type: edge_type.clone(),
+    }
+}
+
+/// Collect the keys of every node upstream of each metric, keyed by metric name
+fn metric_upstreams(
+    graph: &LineageGraph,
+    keys: &HashMap<&str, String>,
+) -> HashMap<String, BTreeSet<String>> {
+    graph
+        .nodes
+        .iter()
+        .filter(|n| n.node_type == LineageNodeType::Metric)
+        .map(|metric| {
+            let mut visited = HashSet::new();
+            let mut queue = vec![metric.id.as_str()];
+            while let Some(current) = queue.pop() {
+                if !visited.insert(current) {
+                    continue;
+                }
+                for edge in &graph.edges {
+                    if edge.source == current && !visited.contains(edge.target.as_str()) {
+                        queue.push(edge.target.as_str());
+                    }
+                }
+            }
+            visited.remove(metric.id.as_str());
+
+            let upstream = visited
+                .into_iter()
+                .filter_map(|id| keys.get(id).cloned())
+                .collect();
+            (metric.name.clone(), upstream)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{LineageEdge, LineageEdgeType, LineageNode};
+
+    fn node(id: &str, node_type: LineageNodeType, name: &str) -> LineageNode {
+        LineageNode {
+            id: id.to_string(),
+            node_type,
+            name: name.to_string(),
+            description: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn edge(source: &str, target: &str, edge_type: LineageEdgeType) -> LineageEdge {
+        LineageEdge {
+            id: format!("{}->{}", source, target),
+            source: source.to_string(),
+            target: target.to_string(),
+            edge_type,
+            label: None,
+        }
+    }
+
+    fn base_graph(prefix: &str) -> LineageGraph {
+        let id = |s: &str| format!("{}-{}", prefix, s);
+        LineageGraph {
+            nodes: vec![
+                node(&id("m"), LineageNodeType::Metric, "revenue"),
+                node(&id("ms"), LineageNodeType::Measure, "order_total"),
+                node(&id("mod"), LineageNodeType::Model, "fct_orders"),
+            ],
+            edges: vec![edge(&id("m"), &id("ms"), LineageEdgeType::MetricToMeasure)],
+        }
+    }
+
+    #[test]
+    fn test_added_model() {
+        let before = base_graph("a");
+        let mut after = base_graph("b");
+        after
+            .nodes
+            .push(node("b-new", LineageNodeType::Model, "stg_payments"));
+
+        let diff = diff_graphs(&before, &after);
+
+        assert_eq!(diff.added_nodes, vec!["Model.stg_payments"]);
+        assert!(diff.removed_nodes.is_empty());
+        assert!(diff.added_edges.is_empty());
+        assert!(diff.changed_metrics.is_empty());
+    }
+
+    #[test]
+    fn test_removed_metric_edge() {
+        let before = base_graph("a");
+        let mut after = base_graph("b");
+        after.edges.clear();
+
+        let diff = diff_graphs(&before, &after);
+
+        assert!(diff.added_nodes.is_empty());
+        assert_eq!(diff.removed_edges.len(), 1);
+        assert_eq!(diff.removed_edges[0].source, "Metric.revenue");
+        assert_eq!(diff.removed_edges[0].target, "Measure.order_total");
+        assert_eq!(diff.removed_edges[0].edge_type, "MetricToMeasure");
+        assert_eq!(diff.changed_metrics, vec!["revenue"]);
+    }
+}
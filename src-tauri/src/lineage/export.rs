@@ -0,0 +1,913 @@
+//! Export of lineage graphs and audits to external formats
+//!
+//! Exporters only see the graph or result they are given, so a subgraph
+//! returned by a filtering command exports the same way as a full parse.
+
+use crate::types::{
+    AuditResult, DbtColumn, IssueSeverity, LineageGraph, LineageNodeType, ParseResult,
+};
+use anyhow::{Context, Result};
+use arrow::array::{ArrayRef, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+/// File name of the node table written by [`write_parquet`]
+pub const NODES_PARQUET_FILE: &str = "lineage_nodes.parquet";
+/// File name of the edge table written by [`write_parquet`]
+pub const EDGES_PARQUET_FILE: &str = "lineage_edges.parquet";
+
+/// Render a lineage graph as GraphML, using node UUIDs as GraphML node ids
+pub fn to_graphml(graph: &LineageGraph) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    out.push_str("  <key id=\"name\" for=\"node\" attr.name=\"name\" attr.type=\"string\"/>\n");
+    out.push_str("  <key id=\"type\" for=\"node\" attr.name=\"type\" attr.type=\"string\"/>\n");
+    out.push_str(
+        "  <key id=\"description\" for=\"node\" attr.name=\"description\" attr.type=\"string\"/>\n",
+    );
+    out.push_str(
+        "  <key id=\"edge_type\" for=\"edge\" attr.name=\"type\" attr.type=\"string\"/>\n",
+    );
+    out.push_str("  <key id=\"label\" for=\"edge\" attr.name=\"label\" attr.type=\"string\"/>\n");
+    out.push_str("  <graph id=\"lineage\" edgedefault=\"directed\">\n");
+
+    for node in &graph.nodes {
+        out.push_str(&format!("    <node id=\"{}\">\n", escape_xml(&node.id)));
+        out.push_str(&format!(
+            "      <data key=\"name\">{}</data>\n",
+            escape_xml(&node.name)
+        ));
+        out.push_str(&format!(
+            "      <data key=\"type\">{:?}</data>\n",
+            node.node_type
+        ));
+        if let Some(ref description) = node.description {
+            out.push_str(&format!(
+                "      <data key=\"description\">{}</data>\n",
+                escape_xml(description)
+            ));
+        }
+        out.push_str("    </node>\n");
+    }
+
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "    <edge id=\"{}\" source=\"{}\" target=\"{}\">\n",
+            escape_xml(&edge.id),
+            escape_xml(&edge.source),
+            escape_xml(&edge.target)
+        ));
+        out.push_str(&format!(
+            "      <data key=\"edge_type\">{:?}</data>\n",
+            edge.edge_type
+        ));
+        if let Some(ref label) = edge.label {
+            out.push_str(&format!(
+                "      <data key=\"label\">{}</data>\n",
+                escape_xml(label)
+            ));
+        }
+        out.push_str("    </edge>\n");
+    }
+
+    out.push_str("  </graph>\n");
+    out.push_str("</graphml>\n");
+    out
+}
+
+/// Render a lineage graph in Graphviz DOT, with edges pointing from each
+/// node to its upstream dependency and labelled with the edge type
+pub fn to_dot(graph: &LineageGraph) -> String {
+    let mut out = String::new();
+    out.push_str("digraph lineage {\n");
+    out.push_str("  rankdir=LR;\n");
+    out.push_str("  node [shape=box];\n");
+
+    for node in &graph.nodes {
+        out.push_str(&format!(
+            "  \"{}\" [label=\"{}\", tooltip=\"{:?}\"];\n",
+            escape_dot(&node.id),
+            escape_dot(&node.name),
+            node.node_type
+        ));
+    }
+
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "  \"{}\" -> \"{}\" [label=\"{:?}\"];\n",
+            escape_dot(&edge.source),
+            escape_dot(&edge.target),
+            edge.edge_type
+        ));
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Render a lineage graph as a Mermaid flowchart for pasting into Markdown,
+/// with the same edge direction as DOT and edges labelled when they carry one
+pub fn to_mermaid(graph: &LineageGraph) -> String {
+    let mut out = String::from("graph TD\n");
+
+    for node in &graph.nodes {
+        out.push_str(&format!(
+            "  {}[\"{}\"]\n",
+            mermaid_id(&node.id),
+            escape_mermaid(&node.name)
+        ));
+    }
+
+    out.push('\n');
+
+    for edge in &graph.edges {
+        let label = edge
+            .label
+            .as_deref()
+            .map(|l| format!("|\"{}\"|", escape_mermaid(l)))
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "  {} --{}--> {}\n",
+            mermaid_id(&edge.source),
+            label,
+            mermaid_id(&edge.target)
+        ));
+    }
+
+    out
+}
+
+/// Write the lineage graph as two Parquet tables in `dir`, one row per node
+/// (id, type, name, description) and one per edge (id, source, target,
+/// edge_type, label), for loading into a warehouse
+pub fn write_parquet(graph: &LineageGraph, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Cannot create directory '{}'", dir.display()))?;
+
+    let nodes = string_batch(vec![
+        (
+            "id",
+            false,
+            graph.nodes.iter().map(|n| Some(n.id.clone())).collect(),
+        ),
+        (
+            "type",
+            false,
+            graph
+                .nodes
+                .iter()
+                .map(|n| Some(format!("{:?}", n.node_type)))
+                .collect(),
+        ),
+        (
+            "name",
+            false,
+            graph.nodes.iter().map(|n| Some(n.name.clone())).collect(),
+        ),
+        (
+            "description",
+            true,
+            graph.nodes.iter().map(|n| n.description.clone()).collect(),
+        ),
+    ])?;
+    write_batch(&dir.join(NODES_PARQUET_FILE), &nodes)?;
+
+    let edges = string_batch(vec![
+        (
+            "id",
+            false,
+            graph.edges.iter().map(|e| Some(e.id.clone())).collect(),
+        ),
+        (
+            "source",
+            false,
+            graph.edges.iter().map(|e| Some(e.source.clone())).collect(),
+        ),
+        (
+            "target",
+            false,
+            graph.edges.iter().map(|e| Some(e.target.clone())).collect(),
+        ),
+        (
+            "edge_type",
+            false,
+            graph
+                .edges
+                .iter()
+                .map(|e| Some(format!("{:?}", e.edge_type)))
+                .collect(),
+        ),
+        (
+            "label",
+            true,
+            graph.edges.iter().map(|e| e.label.clone()).collect(),
+        ),
+    ])?;
+    write_batch(&dir.join(EDGES_PARQUET_FILE), &edges)?;
+
+    Ok(())
+}
+
+/// Build a record batch of string columns given as (name, nullable, values)
+fn string_batch(columns: Vec<(&str, bool, Vec<Option<String>>)>) -> Result<RecordBatch> {
+    let fields: Vec<Field> = columns
+        .iter()
+        .map(|(name, nullable, _)| Field::new(*name, DataType::Utf8, *nullable))
+        .collect();
+    let arrays: Vec<ArrayRef> = columns
+        .into_iter()
+        .map(|(_, _, values)| Arc::new(StringArray::from(values)) as ArrayRef)
+        .collect();
+
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+}
+
+fn write_batch(path: &Path, batch: &RecordBatch) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("Cannot create '{}'", path.display()))?;
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+const SVG_NODE_WIDTH: usize = 160;
+const SVG_NODE_HEIGHT: usize = 32;
+const SVG_COLUMN_GAP: usize = 80;
+const SVG_ROW_GAP: usize = 16;
+
+/// Render a lineage graph as a static SVG: sources and other upstream nodes in
+/// the left-most column, each node one column right of its deepest upstream
+/// dependency, with straight lines for edges. Meant for snapshots of graphs
+/// up to a few hundred nodes.
+pub fn to_svg(graph: &LineageGraph) -> String {
+    // Longest distance from an upstream-most node. Edges point from a node to
+    // its upstream dependency; relaxing at most once per node keeps cycles
+    // from looping forever
+    let mut rank: HashMap<&str, usize> = graph.nodes.iter().map(|n| (n.id.as_str(), 0)).collect();
+    for _ in 0..graph.nodes.len() {
+        let mut changed = false;
+        for edge in &graph.edges {
+            let (Some(&upstream), Some(&current)) = (
+                rank.get(edge.target.as_str()),
+                rank.get(edge.source.as_str()),
+            ) else {
+                continue;
+            };
+            if current < upstream + 1 {
+                rank.insert(&edge.source, upstream + 1);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut rows_per_rank: HashMap<usize, usize> = HashMap::new();
+    let mut position: HashMap<&str, (usize, usize)> = HashMap::new();
+    for node in &graph.nodes {
+        let column = rank[node.id.as_str()];
+        let row = rows_per_rank.entry(column).or_default();
+        position.insert(
+            &node.id,
+            (
+                SVG_COLUMN_GAP / 2 + column * (SVG_NODE_WIDTH + SVG_COLUMN_GAP),
+                SVG_ROW_GAP + *row * (SVG_NODE_HEIGHT + SVG_ROW_GAP),
+            ),
+        );
+        *row += 1;
+    }
+
+    let columns = rank.values().max().map_or(0, |max| max + 1);
+    let rows = rows_per_rank.values().max().copied().unwrap_or(0);
+    let width = columns * (SVG_NODE_WIDTH + SVG_COLUMN_GAP);
+    let height = SVG_ROW_GAP + rows * (SVG_NODE_HEIGHT + SVG_ROW_GAP);
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+        width, height
+    ));
+
+    for edge in &graph.edges {
+        let (Some(&(tx, ty)), Some(&(sx, sy))) = (
+            position.get(edge.target.as_str()),
+            position.get(edge.source.as_str()),
+        ) else {
+            continue;
+        };
+        out.push_str(&format!(
+            "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#9ca3af\"/>\n",
+            tx + SVG_NODE_WIDTH,
+            ty + SVG_NODE_HEIGHT / 2,
+            sx,
+            sy + SVG_NODE_HEIGHT / 2
+        ));
+    }
+
+    for node in &graph.nodes {
+        let (x, y) = position[node.id.as_str()];
+        out.push_str(&format!(
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\" fill=\"{}\"/>\n",
+            x,
+            y,
+            SVG_NODE_WIDTH,
+            SVG_NODE_HEIGHT,
+            svg_color(&node.node_type)
+        ));
+        out.push_str(&format!(
+            "  <text x=\"{}\" y=\"{}\" fill=\"#ffffff\">{}</text>\n",
+            x + 8,
+            y + SVG_NODE_HEIGHT / 2 + 4,
+            escape_xml(&node.name)
+        ));
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+fn svg_color(node_type: &LineageNodeType) -> &'static str {
+    match node_type {
+        LineageNodeType::Metric => "#7c3aed",
+        LineageNodeType::Measure => "#2563eb",
+        LineageNodeType::Dimension => "#0891b2",
+        LineageNodeType::Entity => "#0d9488",
+        LineageNodeType::Model => "#16a34a",
+        LineageNodeType::Source => "#ea580c",
+        LineageNodeType::Snapshot => "#ca8a04",
+        LineageNodeType::Seed => "#a16207",
+        LineageNodeType::Test => "#6b7280",
+    }
+}
+
+/// Render audit issues as a SARIF 2.1.0 log for code scanning tools, one
+/// result per issue with the issue type as its rule id. Locations under
+/// `project_root` are made relative to it, since code scanning resolves
+/// uris against the repository root.
+pub fn to_sarif(audit: &AuditResult, project_root: Option<&str>) -> String {
+    let relative_uri = |file: &str| -> String {
+        project_root
+            .and_then(|root| file.strip_prefix(root.trim_end_matches('/')))
+            .and_then(|rest| rest.strip_prefix('/'))
+            .unwrap_or(file)
+            .to_string()
+    };
+
+    let mut rule_ids: Vec<String> = audit
+        .issues
+        .iter()
+        .map(|issue| format!("{:?}", issue.issue_type))
+        .collect();
+    rule_ids.sort();
+    rule_ids.dedup();
+
+    let results: Vec<serde_json::Value> = audit
+        .issues
+        .iter()
+        .map(|issue| {
+            let level = match issue.severity {
+                IssueSeverity::Error => "error",
+                IssueSeverity::Warning => "warning",
+                IssueSeverity::Info => "note",
+            };
+            let mut result = serde_json::json!({
+                "ruleId": format!("{:?}", issue.issue_type),
+                "level": level,
+                "message": { "text": issue.message },
+            });
+            if let Some(ref location) = issue.source_location {
+                result["locations"] = serde_json::json!([{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": relative_uri(&location.file),
+                        },
+                        "region": {
+                            "startLine": location.line,
+                            "startColumn": location.column,
+                        },
+                    },
+                }]);
+            }
+            result
+        })
+        .collect();
+
+    let sarif = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rule_ids
+                        .iter()
+                        .map(|id| serde_json::json!({ "id": id }))
+                        .collect::<Vec<_>>(),
+                },
+            },
+            "results": results,
+        }],
+    });
+    format!("{:#}", sarif)
+}
+
+/// Render a parse result in the shape of dbt docs' `manifest.json`, with
+/// `nodes` (models, seeds and snapshots), `sources` and `metrics` keyed by
+/// unique id, each carrying its description, columns and `depends_on.nodes`.
+///
+/// Dependencies come from the lineage graph. Semantic nodes without a dbt
+/// resource (measures, dimensions, entities) are walked through, so a metric
+/// depends on the models behind its measures.
+pub fn to_dbt_docs(result: &ParseResult) -> String {
+    let graph = &result.lineage;
+    let node_names: HashMap<(&LineageNodeType, &str), &str> = graph
+        .nodes
+        .iter()
+        .map(|n| ((&n.node_type, n.name.as_str()), n.id.as_str()))
+        .collect();
+    let graph_id = |node_type: LineageNodeType, name: &str| {
+        node_names.get(&(&node_type, name)).map(|id| id.to_string())
+    };
+
+    // Graph node id -> dbt unique id
+    let mut unique_ids: HashMap<String, String> = HashMap::new();
+    for model in &result.models {
+        if let Some(id) = graph_id(LineageNodeType::Model, &model.name) {
+            unique_ids.insert(id, model.unique_id.clone());
+        }
+    }
+    for seed in &result.seeds {
+        if let Some(id) = graph_id(LineageNodeType::Seed, &seed.name) {
+            unique_ids.insert(id, seed.unique_id.clone());
+        }
+    }
+    for snapshot in &result.snapshots {
+        if let Some(id) = graph_id(LineageNodeType::Snapshot, &snapshot.name) {
+            unique_ids.insert(id, snapshot.unique_id.clone());
+        }
+    }
+    for node in graph
+        .nodes
+        .iter()
+        .filter(|n| n.node_type == LineageNodeType::Source)
+    {
+        let source_name = node.metadata.get("source_name").and_then(|v| v.as_str());
+        if let Some(source) = result
+            .sources
+            .iter()
+            .find(|s| s.name == node.name && Some(s.source_name.as_str()) == source_name)
+        {
+            unique_ids.insert(node.id.clone(), source.unique_id.clone());
+        }
+    }
+    for metric in &result.metrics {
+        if let Some(id) = graph_id(LineageNodeType::Metric, &metric.name) {
+            unique_ids.insert(id, format!("metric.{}", metric.name));
+        }
+    }
+
+    let mut upstream: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in &graph.edges {
+        upstream.entry(&edge.source).or_default().push(&edge.target);
+    }
+    let depends_on = |name: &str, node_type: LineageNodeType| -> Vec<String> {
+        let Some(start) = graph_id(node_type, name) else {
+            return Vec::new();
+        };
+        let mut found = Vec::new();
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut stack: Vec<&str> = upstream.get(start.as_str()).cloned().unwrap_or_default();
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            match unique_ids.get(id) {
+                Some(unique_id) => found.push(unique_id.clone()),
+                None => stack.extend(upstream.get(id).into_iter().flatten()),
+            }
+        }
+        found.sort();
+        found.dedup();
+        found
+    };
+    let columns = |columns: &[DbtColumn]| -> serde_json::Map<String, serde_json::Value> {
+        columns
+            .iter()
+            .map(|c| {
+                (
+                    c.name.clone(),
+                    serde_json::json!({
+                        "name": c.name,
+                        "description": c.description.clone().unwrap_or_default(),
+                        "data_type": c.data_type,
+                    }),
+                )
+            })
+            .collect()
+    };
+
+    let mut nodes = serde_json::Map::new();
+    for model in &result.models {
+        nodes.insert(
+            model.unique_id.clone(),
+            serde_json::json!({
+                "unique_id": model.unique_id,
+                "resource_type": "model",
+                "name": model.name,
+                "description": model.description.clone().unwrap_or_default(),
+                "original_file_path": model.file_path,
+                "tags": model.tags,
+                "columns": columns(&model.columns),
+                "depends_on": { "nodes": depends_on(&model.name, LineageNodeType::Model) },
+            }),
+        );
+    }
+    for seed in &result.seeds {
+        nodes.insert(
+            seed.unique_id.clone(),
+            serde_json::json!({
+                "unique_id": seed.unique_id,
+                "resource_type": "seed",
+                "name": seed.name,
+                "description": seed.description.clone().unwrap_or_default(),
+                "original_file_path": seed.file_path,
+                "columns": columns(&seed.columns),
+                "depends_on": { "nodes": [] },
+            }),
+        );
+    }
+    for snapshot in &result.snapshots {
+        nodes.insert(
+            snapshot.unique_id.clone(),
+            serde_json::json!({
+                "unique_id": snapshot.unique_id,
+                "resource_type": "snapshot",
+                "name": snapshot.name,
+                "description": "",
+                "original_file_path": snapshot.file_path,
+                "columns": {},
+                "depends_on": { "nodes": depends_on(&snapshot.name, LineageNodeType::Snapshot) },
+            }),
+        );
+    }
+
+    let sources: serde_json::Map<String, serde_json::Value> = result
+        .sources
+        .iter()
+        .map(|source| {
+            (
+                source.unique_id.clone(),
+                serde_json::json!({
+                    "unique_id": source.unique_id,
+                    "resource_type": "source",
+                    "source_name": source.source_name,
+                    "name": source.name,
+                    "description": source.description.clone().unwrap_or_default(),
+                    "loader": source.loader.clone().unwrap_or_default(),
+                    "tags": source.tags,
+                    "columns": columns(&source.columns),
+                }),
+            )
+        })
+        .collect();
+
+    let metrics: serde_json::Map<String, serde_json::Value> = result
+        .metrics
+        .iter()
+        .map(|metric| {
+            let unique_id = format!("metric.{}", metric.name);
+            (
+                unique_id.clone(),
+                serde_json::json!({
+                    "unique_id": unique_id,
+                    "resource_type": "metric",
+                    "name": metric.name,
+                    "label": metric.label.clone().unwrap_or_else(|| metric.name.clone()),
+                    "description": metric.description.clone().unwrap_or_default(),
+                    "type": metric.metric_type,
+                    "depends_on": { "nodes": depends_on(&metric.name, LineageNodeType::Metric) },
+                }),
+            )
+        })
+        .collect();
+
+    let docs = serde_json::json!({
+        "metadata": {
+            "project_name": result.dbt_project.as_ref().map(|p| p.name.clone()),
+            "generated_by": env!("CARGO_PKG_NAME"),
+        },
+        "nodes": nodes,
+        "sources": sources,
+        "metrics": metrics,
+    });
+    format!("{:#}", docs)
+}
+
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Mermaid node ids may only hold word characters
+fn mermaid_id(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn escape_mermaid(value: &str) -> String {
+    value.replace('"', "#quot;")
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lineage::LineageBuilder;
+    use crate::types::{
+        AuditIssue, DbtModel, DbtSource, DbtSourceRef, IssueType, LineageEdge, LineageEdgeType,
+        LineageNode, LineageNodeType, SourceLocation,
+    };
+    use std::collections::HashMap;
+
+    fn node(id: &str, node_type: LineageNodeType, description: Option<&str>) -> LineageNode {
+        LineageNode {
+            id: id.to_string(),
+            node_type,
+            name: id.to_string(),
+            description: description.map(|d| d.to_string()),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_graphml_nodes_and_edges() {
+        let graph = LineageGraph {
+            nodes: vec![
+                node(
+                    "fct_orders",
+                    LineageNodeType::Model,
+                    Some("Orders & <refunds>"),
+                ),
+                node("raw_orders", LineageNodeType::Source, None),
+            ],
+            edges: vec![LineageEdge {
+                id: "e1".to_string(),
+                source: "fct_orders".to_string(),
+                target: "raw_orders".to_string(),
+                edge_type: LineageEdgeType::ModelToSource,
+                label: Some("source".to_string()),
+            }],
+        };
+
+        let graphml = to_graphml(&graph);
+
+        assert_eq!(graphml.matches("<node ").count(), 2);
+        assert_eq!(graphml.matches("<edge ").count(), 1);
+        assert!(graphml.contains("Orders &amp; &lt;refunds&gt;"));
+        assert!(graphml.contains("<data key=\"edge_type\">ModelToSource</data>"));
+    }
+
+    #[test]
+    fn test_mermaid_ids_and_labels() {
+        let graph = LineageGraph {
+            nodes: vec![
+                node("model.fct-orders", LineageNodeType::Model, None),
+                LineageNode {
+                    name: "raw \"orders\"".to_string(),
+                    ..node("source.shop.orders", LineageNodeType::Source, None)
+                },
+            ],
+            edges: vec![LineageEdge {
+                id: "e1".to_string(),
+                source: "model.fct-orders".to_string(),
+                target: "source.shop.orders".to_string(),
+                edge_type: LineageEdgeType::ModelToSource,
+                label: Some("source".to_string()),
+            }],
+        };
+
+        let mermaid = to_mermaid(&graph);
+
+        assert!(mermaid.starts_with("graph TD\n"));
+        assert!(mermaid.contains("  model_fct_orders[\"model.fct-orders\"]\n"));
+        assert!(mermaid.contains("  source_shop_orders[\"raw #quot;orders#quot;\"]\n"));
+        assert!(mermaid.contains("  model_fct_orders --|\"source\"|--> source_shop_orders\n"));
+    }
+
+    #[test]
+    fn test_dbt_docs_depends_on_from_edges() {
+        let model = |name: &str, refs: &[&str], sources: Vec<DbtSourceRef>| DbtModel {
+            unique_id: format!("model.{}", name),
+            name: name.to_string(),
+            schema: None,
+            database: None,
+            qualified_name: None,
+            alias: None,
+            description: Some(format!("The {} model", name)),
+            columns: vec![DbtColumn {
+                name: "order_id".to_string(),
+                description: Some("Primary key".to_string()),
+                data_type: None,
+                meta: HashMap::new(),
+                tests: Vec::new(),
+            }],
+            depends_on: refs
+                .iter()
+                .map(|r| format!("model.{}", r))
+                .chain(
+                    sources
+                        .iter()
+                        .map(|s| format!("source.{}.{}", s.source_name, s.table_name)),
+                )
+                .collect(),
+            refs: refs.iter().map(|r| r.to_string()).collect(),
+            sources,
+            metric_refs: Vec::new(),
+            file_path: format!("models/{}.sql", name),
+            raw_sql: None,
+            materialization: None,
+            tags: Vec::new(),
+            access: None,
+            group: None,
+            unit_test_count: 0,
+            unique_key: Vec::new(),
+            duplicate_columns: Vec::new(),
+            source_location: None,
+            project: None,
+        };
+        let mut result = ParseResult::default();
+        result.models = vec![
+            model("fct_orders", &["stg_orders"], Vec::new()),
+            model(
+                "stg_orders",
+                &[],
+                vec![DbtSourceRef {
+                    source_name: "shop".to_string(),
+                    table_name: "orders".to_string(),
+                }],
+            ),
+        ];
+        result.sources = vec![DbtSource {
+            unique_id: "source.shop.orders".to_string(),
+            source_name: "shop".to_string(),
+            name: "orders".to_string(),
+            schema: None,
+            database: None,
+            qualified_name: None,
+            description: None,
+            columns: Vec::new(),
+            loader: None,
+            freshness: None,
+            loaded_at_field: None,
+            tags: Vec::new(),
+            project: None,
+        }];
+        result.lineage = LineageBuilder::new().build(&result.models, &result.sources, &[], &[]);
+
+        let docs: serde_json::Value = serde_json::from_str(&to_dbt_docs(&result)).unwrap();
+
+        let fct_orders = &docs["nodes"]["model.fct_orders"];
+        assert_eq!(fct_orders["resource_type"], "model");
+        assert_eq!(
+            fct_orders["depends_on"]["nodes"],
+            serde_json::json!(["model.stg_orders"])
+        );
+        assert_eq!(
+            docs["nodes"]["model.stg_orders"]["depends_on"]["nodes"],
+            serde_json::json!(["source.shop.orders"])
+        );
+        assert_eq!(
+            fct_orders["columns"]["order_id"]["description"],
+            "Primary key"
+        );
+        assert_eq!(
+            docs["sources"]["source.shop.orders"]["resource_type"],
+            "source"
+        );
+    }
+
+    #[test]
+    fn test_svg_has_one_rect_per_node() {
+        let edge = |source: &str, target: &str, edge_type| LineageEdge {
+            id: format!("{}->{}", source, target),
+            source: source.to_string(),
+            target: target.to_string(),
+            edge_type,
+            label: None,
+        };
+        let graph = LineageGraph {
+            nodes: vec![
+                node("revenue", LineageNodeType::Metric, None),
+                node("fct_orders", LineageNodeType::Model, None),
+                node("stg_orders", LineageNodeType::Model, None),
+                node("raw_<orders>", LineageNodeType::Source, None),
+            ],
+            edges: vec![
+                edge("revenue", "fct_orders", LineageEdgeType::MetricToMeasure),
+                edge("fct_orders", "stg_orders", LineageEdgeType::ModelToModel),
+                edge("stg_orders", "raw_<orders>", LineageEdgeType::ModelToSource),
+                edge("stg_orders", "fct_orders", LineageEdgeType::ModelToModel),
+            ],
+        };
+
+        let svg = to_svg(&graph);
+
+        assert!(svg.starts_with("<svg "));
+        assert_eq!(svg.matches("<rect ").count(), 4);
+        assert_eq!(svg.matches("<line ").count(), 4);
+        assert!(svg.contains("raw_&lt;orders&gt;"));
+    }
+
+    #[test]
+    fn test_sarif_has_one_result_per_issue() {
+        let issue = |severity, issue_type, source_location| AuditIssue {
+            severity,
+            issue_type,
+            message: "Metric 'revenue' has no description".to_string(),
+            node_id: None,
+            suggestion: None,
+            source_location,
+        };
+        let mut audit = ParseResult::default().audit;
+        audit.issues = vec![
+            issue(
+                IssueSeverity::Warning,
+                IssueType::MissingDescription,
+                Some(SourceLocation {
+                    file: "/work/jaffle_shop/models/metrics.yml".to_string(),
+                    line: 12,
+                    column: 3,
+                }),
+            ),
+            issue(IssueSeverity::Info, IssueType::MissingDescription, None),
+            issue(IssueSeverity::Error, IssueType::CircularDependency, None),
+        ];
+
+        let sarif: serde_json::Value =
+            serde_json::from_str(&to_sarif(&audit, Some("/work/jaffle_shop/"))).unwrap();
+        let run = &sarif["runs"][0];
+        let results = run["results"].as_array().unwrap();
+
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(results.len(), 3);
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 2);
+        assert_eq!(results[0]["ruleId"], "MissingDescription");
+        assert_eq!(results[0]["level"], "warning");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "models/metrics.yml");
+        assert_eq!(location["region"]["startLine"], 12);
+        assert_eq!(results[1]["level"], "note");
+        assert!(results[1].get("locations").is_none());
+        assert_eq!(results[2]["level"], "error");
+    }
+
+    #[test]
+    fn test_parquet_round_trip_row_counts() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let graph = LineageGraph {
+            nodes: vec![
+                node("fct_orders", LineageNodeType::Model, Some("Orders")),
+                node("raw_orders", LineageNodeType::Source, None),
+            ],
+            edges: vec![LineageEdge {
+                id: "e1".to_string(),
+                source: "fct_orders".to_string(),
+                target: "raw_orders".to_string(),
+                edge_type: LineageEdgeType::ModelToSource,
+                label: None,
+            }],
+        };
+        let dir = std::env::temp_dir().join(format!("semantic-tracer-{}", uuid::Uuid::new_v4()));
+
+        write_parquet(&graph, &dir).unwrap();
+
+        let row_count = |file: &str| -> usize {
+            ParquetRecordBatchReaderBuilder::try_new(File::open(dir.join(file)).unwrap())
+                .unwrap()
+                .build()
+                .unwrap()
+                .map(|batch| batch.unwrap().num_rows())
+                .sum()
+        };
+        assert_eq!(row_count(NODES_PARQUET_FILE), 2);
+        assert_eq!(row_count(EDGES_PARQUET_FILE), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
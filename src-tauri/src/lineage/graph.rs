@@ -1,9 +1,12 @@
 //! Lineage graph construction from parsed dbt and semantic layer data
 
+use crate::parsers::parse_expr_identifiers;
 use crate::types::{
-    DbtModel, DbtSource, LineageEdge, LineageEdgeType, LineageGraph, LineageNode, LineageNodeType,
-    Measure, Metric, SemanticModel,
+    AuditIssue, DbtModel, DbtSeed, DbtSnapshot, DbtSource, DbtTest, FilterReferenceKind,
+    IssueSeverity, IssueType, LineageEdge, LineageEdgeType, LineageGraph, LineageNode,
+    LineageNodeType, LookmlField, LookmlView, Measure, Metric, SemanticModel,
 };
+use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
@@ -11,6 +14,14 @@ pub struct LineageBuilder {
     nodes: Vec<LineageNode>,
     edges: Vec<LineageEdge>,
     node_ids: HashMap<String, String>, // name -> id mapping
+    issued_ids: HashSet<String>,       // deterministic node ids handed out so far
+    lookml_views: Vec<LookmlView>,
+    snapshots: Vec<DbtSnapshot>,
+    seeds: Vec<DbtSeed>,
+    tests: Vec<DbtTest>,
+    prune_unreferenced: bool,
+    deterministic_ids: bool,
+    diagnostics: Vec<AuditIssue>, // problems found while building, for the audit
 }
 
 impl LineageBuilder {
@@ -19,31 +30,113 @@ impl LineageBuilder {
             nodes: Vec::new(),
             edges: Vec::new(),
             node_ids: HashMap::new(),
+            issued_ids: HashSet::new(),
+            lookml_views: Vec::new(),
+            snapshots: Vec::new(),
+            seeds: Vec::new(),
+            tests: Vec::new(),
+            prune_unreferenced: false,
+            deterministic_ids: false,
+            diagnostics: Vec::new(),
         }
     }
 
+    /// Drop nodes with no path to or from any metric once the graph is built
+    pub fn with_pruning(mut self, prune: bool) -> Self {
+        self.prune_unreferenced = prune;
+        self
+    }
+
+    /// Derive node ids from each node's logical key and edge ids from their
+    /// endpoints and type, instead of random UUIDs, so ids stay the same
+    /// across parses of an unchanged project
+    pub fn with_deterministic_ids(mut self, deterministic: bool) -> Self {
+        self.deterministic_ids = deterministic;
+        self
+    }
+
+    /// Include dbt snapshots, linked to the models and sources they capture
+    pub fn with_snapshots(mut self, snapshots: Vec<DbtSnapshot>) -> Self {
+        self.snapshots = snapshots;
+        self
+    }
+
+    /// Include dbt seeds, which models `ref()` like any other upstream table
+    pub fn with_seeds(mut self, seeds: Vec<DbtSeed>) -> Self {
+        self.seeds = seeds;
+        self
+    }
+
+    /// Include dbt data tests, linked to the models they test
+    pub fn with_tests(mut self, tests: Vec<DbtTest>) -> Self {
+        self.tests = tests;
+        self
+    }
+
+    /// Include Looker views, linking their fields to the dbt models or sources
+    /// they read from
+    pub fn with_lookml_views(mut self, views: Vec<LookmlView>) -> Self {
+        self.lookml_views = views;
+        self
+    }
+
     /// Build a complete lineage graph from all parsed data
     pub fn build(
-        mut self,
+        self,
         models: &[DbtModel],
         sources: &[DbtSource],
         semantic_models: &[SemanticModel],
         metrics: &[Metric],
     ) -> LineageGraph {
+        self.build_with_diagnostics(models, sources, semantic_models, metrics)
+            .0
+    }
+
+    /// Build the lineage graph, also returning the issues found along the way
+    /// (such as edges that were skipped) so they can be passed to the audit
+    pub fn build_with_diagnostics(
+        mut self,
+        models: &[DbtModel],
+        sources: &[DbtSource],
+        semantic_models: &[SemanticModel],
+        metrics: &[Metric],
+    ) -> (LineageGraph, Vec<AuditIssue>) {
         // 1. Add source nodes first (bottom of the graph)
         for source in sources {
             self.add_source_node(source);
         }
+        for seed in std::mem::take(&mut self.seeds) {
+            self.add_seed_node(&seed);
+        }
 
         // 2. Add model nodes and their dependencies
+        let mut models_by_name: HashMap<&str, &DbtModel> = HashMap::new();
+        let mut unique_models: Vec<&DbtModel> = Vec::new();
         for model in models {
+            if let Some(original) = models_by_name.get(model.name.as_str()) {
+                self.report_duplicate_model(original, model);
+                continue;
+            }
             self.add_model_node(model);
+            models_by_name.insert(&model.name, model);
+            unique_models.push(model);
+        }
+        let models = unique_models;
+        let snapshots = std::mem::take(&mut self.snapshots);
+        for snapshot in &snapshots {
+            self.add_snapshot_node(snapshot);
         }
 
         // 3. Add model-to-model and model-to-source edges
-        for model in models {
+        for model in &models {
             self.add_model_edges(model);
         }
+        for snapshot in &snapshots {
+            self.add_snapshot_edges(snapshot);
+        }
+        for test in std::mem::take(&mut self.tests) {
+            self.add_test_node(&test);
+        }
 
         // 4. Add semantic model entities and measures
         for sm in semantic_models {
@@ -60,15 +153,116 @@ impl LineageBuilder {
             self.add_metric_edges(metric, semantic_models);
         }
 
-        LineageGraph {
+        // 7. Add edges from models that query metrics
+        for model in &models {
+            self.add_model_metric_edges(model);
+        }
+
+        // 8. Add Looker view fields
+        for view in std::mem::take(&mut self.lookml_views) {
+            self.add_lookml_view_nodes(&view);
+        }
+
+        if self.deterministic_ids {
+            self.assign_deterministic_edge_ids();
+        }
+
+        // 9. Drop everything unrelated to a metric
+        if self.prune_unreferenced {
+            let pruned = self.prune_unreferenced_nodes();
+            log::info!("Pruned {} nodes not connected to any metric", pruned);
+        }
+
+        let graph = LineageGraph {
             nodes: self.nodes,
             edges: self.edges,
+        };
+        (graph, self.diagnostics)
+    }
+
+    /// Id for a new node with the given logical key (such as `model.orders`).
+    /// In deterministic mode this is a name-based UUID of the key, suffixed
+    /// if two nodes share a key so ids never collide.
+    fn new_node_id(&mut self, key: &str) -> String {
+        if !self.deterministic_ids {
+            return Uuid::new_v4().to_string();
+        }
+
+        let mut name = key.to_string();
+        let mut duplicate = 0;
+        loop {
+            let id = Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()).to_string();
+            if self.issued_ids.insert(id.clone()) {
+                return id;
+            }
+            duplicate += 1;
+            name = format!("{}#{}", key, duplicate);
         }
     }
 
+    /// Replace edge ids with name-based UUIDs of `(source, target, edge_type)`,
+    /// numbering repeated edges between the same nodes in insertion order
+    fn assign_deterministic_edge_ids(&mut self) {
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for edge in &mut self.edges {
+            let key = format!("{}|{}|{:?}", edge.source, edge.target, edge.edge_type);
+            let count = seen.entry(key.clone()).or_default();
+            let name = match *count {
+                0 => key,
+                n => format!("{}#{}", key, n),
+            };
+            *count += 1;
+            edge.id = Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()).to_string();
+        }
+    }
+
+    /// Remove nodes that no metric depends on and that do not depend on a
+    /// metric, along with their edges. Returns the number of nodes removed.
+    fn prune_unreferenced_nodes(&mut self) -> usize {
+        let metric_ids: Vec<&str> = self
+            .nodes
+            .iter()
+            .filter(|n| n.node_type == LineageNodeType::Metric)
+            .map(|n| n.id.as_str())
+            .collect();
+
+        let mut upstream: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut downstream: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in &self.edges {
+            upstream.entry(&edge.source).or_default().push(&edge.target);
+            downstream
+                .entry(&edge.target)
+                .or_default()
+                .push(&edge.source);
+        }
+
+        // Walk dependencies and dependents separately so that siblings of a
+        // metric's inputs are not kept just for sharing an upstream model
+        let mut keep: HashSet<String> = HashSet::new();
+        for adjacency in [&upstream, &downstream] {
+            let mut visited: HashSet<&str> = HashSet::new();
+            let mut stack = metric_ids.clone();
+            while let Some(current) = stack.pop() {
+                if !visited.insert(current) {
+                    continue;
+                }
+                if let Some(next) = adjacency.get(current) {
+                    stack.extend(next.iter().filter(|n| !visited.contains(*n)));
+                }
+            }
+            keep.extend(visited.into_iter().map(|id| id.to_string()));
+        }
+
+        let before = self.nodes.len();
+        self.nodes.retain(|n| keep.contains(&n.id));
+        self.edges
+            .retain(|e| keep.contains(&e.source) && keep.contains(&e.target));
+        before - self.nodes.len()
+    }
+
     fn add_source_node(&mut self, source: &DbtSource) {
-        let id = Uuid::new_v4().to_string();
         let key = format!("source.{}.{}", source.source_name, source.name);
+        let id = self.new_node_id(&key);
 
         let mut metadata = HashMap::new();
         if let Some(ref schema) = source.schema {
@@ -78,7 +272,16 @@ impl LineageBuilder {
             metadata.insert("database".to_string(), serde_json::json!(database));
         }
         metadata.insert("source_name".to_string(), serde_json::json!(source.source_name));
+        if let Some(ref qualified_name) = source.qualified_name {
+            metadata.insert("qualified_name".to_string(), serde_json::json!(qualified_name));
+        }
         metadata.insert("columns".to_string(), serde_json::json!(source.columns.len()));
+        if let Some(ref freshness) = source.freshness {
+            metadata.insert("freshness".to_string(), serde_json::json!(freshness));
+        }
+        if let Some(ref loaded_at_field) = source.loaded_at_field {
+            metadata.insert("loaded_at_field".to_string(), serde_json::json!(loaded_at_field));
+        }
 
         self.nodes.push(LineageNode {
             id: id.clone(),
@@ -91,17 +294,50 @@ impl LineageBuilder {
         self.node_ids.insert(key, id);
     }
 
+    fn add_seed_node(&mut self, seed: &DbtSeed) {
+        let key = format!("seed.{}", seed.name);
+        let id = self.new_node_id(&key);
+
+        let mut metadata = HashMap::new();
+        metadata.insert("file_path".to_string(), serde_json::json!(seed.file_path));
+        metadata.insert("columns".to_string(), serde_json::json!(seed.columns.len()));
+
+        self.nodes.push(LineageNode {
+            id: id.clone(),
+            node_type: LineageNodeType::Seed,
+            name: seed.name.clone(),
+            description: seed.description.clone(),
+            metadata,
+        });
+
+        self.node_ids.insert(key, id);
+    }
+
     fn add_model_node(&mut self, model: &DbtModel) {
-        let id = Uuid::new_v4().to_string();
         let key = format!("model.{}", model.name);
+        let id = self.new_node_id(&key);
 
         let mut metadata = HashMap::new();
         if let Some(ref mat) = model.materialization {
             metadata.insert("materialization".to_string(), serde_json::json!(mat));
         }
         metadata.insert("file_path".to_string(), serde_json::json!(model.file_path));
+        if let Some(ref qualified_name) = model.qualified_name {
+            metadata.insert("qualified_name".to_string(), serde_json::json!(qualified_name));
+        }
         metadata.insert("columns".to_string(), serde_json::json!(model.columns.len()));
         metadata.insert("tags".to_string(), serde_json::json!(model.tags));
+        if let Some(ref access) = model.access {
+            metadata.insert("access".to_string(), serde_json::json!(access));
+        }
+        if let Some(ref group) = model.group {
+            metadata.insert("group".to_string(), serde_json::json!(group));
+        }
+        // Columns selected with `*` can't be traced to a specific upstream
+        // column, so flag the model's column lineage as approximate
+        if model.raw_sql.as_deref().is_some_and(selects_wildcard) {
+            metadata.insert("column_lineage".to_string(), serde_json::json!("wildcard"));
+        }
 
         self.nodes.push(LineageNode {
             id: id.clone(),
@@ -120,44 +356,210 @@ impl LineageBuilder {
             return;
         };
 
-        // Add edges to referenced models
-        for ref_name in &model.refs {
+        // depends_on is the single source of truth, so models parsed from SQL
+        // (`model.stg_orders`) and from a manifest (`model.jaffle_shop.stg_orders`)
+        // produce the same edges
+        for dependency in &model.depends_on {
+            let Some((kind, rest)) = dependency.split_once('.') else {
+                continue;
+            };
+            let parts: Vec<&str> = rest.split('.').collect();
+
+            let (candidates, label) = match kind {
+                "source" => {
+                    let [.., source_name, table_name] = parts[..] else {
+                        continue;
+                    };
+                    (
+                        vec![format!("source.{}.{}", source_name, table_name)],
+                        "source",
+                    )
+                }
+                // Metric nodes don't exist yet; see add_model_metric_edges
+                "metric" => continue,
+                "model" | "snapshot" | "seed" => {
+                    // A ref'd name may be a model, snapshot or seed; SQL parsing
+                    // records every ref as `model.`, so fall back through the others
+                    let name = parts[parts.len() - 1];
+                    let mut candidates = vec![format!("{}.{}", kind, name)];
+                    for fallback in ["model", "snapshot", "seed"] {
+                        candidates.push(format!("{}.{}", fallback, name));
+                    }
+                    (candidates, "ref")
+                }
+                // Macros, tests and other node kinds are not lineage
+                _ => continue,
+            };
+
+            let Some((target_key, target_id)) = candidates
+                .into_iter()
+                .find_map(|key| self.node_ids.get(&key).cloned().map(|id| (key, id)))
+            else {
+                continue;
+            };
+            if target_id == model_id {
+                self.report_model_self_reference(model, &model_id);
+                continue;
+            }
+            let edge_type = match target_key.split('.').next() {
+                Some("source") => LineageEdgeType::ModelToSource,
+                Some("snapshot") => LineageEdgeType::ModelToSnapshot,
+                Some("seed") => LineageEdgeType::ModelToSeed,
+                _ => LineageEdgeType::ModelToModel,
+            };
+            self.edges.push(LineageEdge {
+                id: Uuid::new_v4().to_string(),
+                source: model_id.clone(),
+                target: target_id,
+                edge_type,
+                label: Some(label.to_string()),
+            });
+        }
+    }
+
+    fn add_model_metric_edges(&mut self, model: &DbtModel) {
+        let model_key = format!("model.{}", model.name);
+        let Some(model_id) = self.node_ids.get(&model_key).cloned() else {
+            return;
+        };
+
+        // Metrics queried in SQL, plus `metric.` dependencies from a manifest
+        let mut metric_names: Vec<&str> = model.metric_refs.iter().map(|m| m.as_str()).collect();
+        for dependency in &model.depends_on {
+            if let Some(name) = dependency
+                .strip_prefix("metric.")
+                .and_then(|rest| rest.rsplit('.').next())
+            {
+                if !metric_names.contains(&name) {
+                    metric_names.push(name);
+                }
+            }
+        }
+
+        for metric_name in metric_names {
+            let metric_key = format!("metric.{}", metric_name);
+            if let Some(metric_id) = self.node_ids.get(&metric_key).cloned() {
+                self.edges.push(LineageEdge {
+                    id: Uuid::new_v4().to_string(),
+                    source: model_id.clone(),
+                    target: metric_id,
+                    edge_type: LineageEdgeType::ModelToMetric,
+                    label: Some("metric".to_string()),
+                });
+            }
+        }
+    }
+
+    fn add_snapshot_node(&mut self, snapshot: &DbtSnapshot) {
+        let key = format!("snapshot.{}", snapshot.name);
+        let id = self.new_node_id(&key);
+
+        let mut metadata = HashMap::new();
+        metadata.insert("file_path".to_string(), serde_json::json!(snapshot.file_path));
+        if let Some(ref strategy) = snapshot.strategy {
+            metadata.insert("strategy".to_string(), serde_json::json!(strategy));
+        }
+        if let Some(ref unique_key) = snapshot.unique_key {
+            metadata.insert("unique_key".to_string(), serde_json::json!(unique_key));
+        }
+        if let Some(ref target_schema) = snapshot.target_schema {
+            metadata.insert("target_schema".to_string(), serde_json::json!(target_schema));
+        }
+
+        self.nodes.push(LineageNode {
+            id: id.clone(),
+            node_type: LineageNodeType::Snapshot,
+            name: snapshot.name.clone(),
+            description: None,
+            metadata,
+        });
+
+        self.node_ids.insert(key, id);
+    }
+
+    fn add_snapshot_edges(&mut self, snapshot: &DbtSnapshot) {
+        let snapshot_key = format!("snapshot.{}", snapshot.name);
+        let Some(snapshot_id) = self.node_ids.get(&snapshot_key).cloned() else {
+            return;
+        };
+
+        for ref_name in &snapshot.refs {
             let ref_key = format!("model.{}", ref_name);
             if let Some(ref_id) = self.node_ids.get(&ref_key).cloned() {
                 self.edges.push(LineageEdge {
                     id: Uuid::new_v4().to_string(),
-                    source: model_id.clone(),
+                    source: snapshot_id.clone(),
                     target: ref_id,
-                    edge_type: LineageEdgeType::ModelToModel,
+                    edge_type: LineageEdgeType::SnapshotToModel,
                     label: Some("ref".to_string()),
                 });
             }
         }
 
-        // Add edges to sources
-        for source_ref in &model.sources {
+        for source_ref in &snapshot.sources {
             let source_key = format!("source.{}.{}", source_ref.source_name, source_ref.table_name);
             if let Some(source_id) = self.node_ids.get(&source_key).cloned() {
                 self.edges.push(LineageEdge {
                     id: Uuid::new_v4().to_string(),
-                    source: model_id.clone(),
+                    source: snapshot_id.clone(),
                     target: source_id,
-                    edge_type: LineageEdgeType::ModelToSource,
+                    edge_type: LineageEdgeType::SnapshotToSource,
                     label: Some("source".to_string()),
                 });
             }
         }
     }
 
+    fn add_test_node(&mut self, test: &DbtTest) {
+        let key = format!("test.{}", test.name);
+        let id = self.new_node_id(&key);
+
+        let mut metadata = HashMap::new();
+        metadata.insert("test_type".to_string(), serde_json::json!(test.test_type));
+        metadata.insert("file_path".to_string(), serde_json::json!(test.file_path));
+        if let Some(ref column) = test.column {
+            metadata.insert("column".to_string(), serde_json::json!(column));
+        }
+
+        self.nodes.push(LineageNode {
+            id: id.clone(),
+            node_type: LineageNodeType::Test,
+            name: test.name.clone(),
+            description: None,
+            metadata,
+        });
+
+        for ref_name in &test.refs {
+            let ref_key = format!("model.{}", ref_name);
+            if let Some(ref_id) = self.node_ids.get(&ref_key).cloned() {
+                self.edges.push(LineageEdge {
+                    id: Uuid::new_v4().to_string(),
+                    source: id.clone(),
+                    target: ref_id,
+                    edge_type: LineageEdgeType::TestToModel,
+                    label: test.column.clone(),
+                });
+            }
+        }
+
+        self.node_ids.insert(key, id);
+    }
+
     fn add_semantic_model_nodes(&mut self, sm: &SemanticModel) {
         // Add entity nodes
         for entity in &sm.entities {
-            let id = Uuid::new_v4().to_string();
             let key = format!("entity.{}.{}", sm.name, entity.name);
+            let id = self.new_node_id(&key);
 
             let mut metadata = HashMap::new();
             metadata.insert("entity_type".to_string(), serde_json::json!(entity.entity_type));
             metadata.insert("semantic_model".to_string(), serde_json::json!(sm.name));
+            if let Some(ref group) = sm.group {
+                metadata.insert("group".to_string(), serde_json::json!(group));
+            }
+            if !sm.meta.is_empty() {
+                metadata.insert("meta".to_string(), serde_json::json!(sm.meta));
+            }
             if let Some(ref expr) = entity.expr {
                 metadata.insert("expr".to_string(), serde_json::json!(expr));
             }
@@ -185,20 +587,43 @@ impl LineageBuilder {
             }
         }
 
+        let primary_entity_id = self.primary_entity_id(sm);
+
         // Add measure nodes
         for measure in &sm.measures {
-            let id = Uuid::new_v4().to_string();
             let key = format!("measure.{}.{}", sm.name, measure.name);
+            let id = self.new_node_id(&key);
 
             let mut metadata = HashMap::new();
             metadata.insert("agg".to_string(), serde_json::json!(measure.agg));
             metadata.insert("semantic_model".to_string(), serde_json::json!(sm.name));
+            if let Some(ref group) = sm.group {
+                metadata.insert("group".to_string(), serde_json::json!(group));
+            }
+            if !sm.meta.is_empty() {
+                metadata.insert("meta".to_string(), serde_json::json!(sm.meta));
+            }
             if let Some(ref expr) = measure.expr {
                 metadata.insert("expr".to_string(), serde_json::json!(expr));
             }
             if let Some(create_metric) = measure.create_metric {
                 metadata.insert("create_metric".to_string(), serde_json::json!(create_metric));
             }
+            if let Some(fill_nulls_with) = measure.fill_nulls_with {
+                metadata.insert(
+                    "fill_nulls_with".to_string(),
+                    serde_json::json!(fill_nulls_with),
+                );
+            }
+            if let Some(join_to_timespine) = measure.join_to_timespine {
+                metadata.insert(
+                    "join_to_timespine".to_string(),
+                    serde_json::json!(join_to_timespine),
+                );
+            }
+            if let Some(ref label) = measure.label {
+                metadata.insert("label".to_string(), serde_json::json!(label));
+            }
 
             self.nodes.push(LineageNode {
                 id: id.clone(),
@@ -211,32 +636,44 @@ impl LineageBuilder {
             self.node_ids.insert(key.clone(), id.clone());
 
             // Add edge from measure to primary entity
-            let primary_entity = sm.entities.iter().find(|e| e.entity_type == "primary");
-            if let Some(entity) = primary_entity {
-                let entity_key = format!("entity.{}.{}", sm.name, entity.name);
-                if let Some(entity_id) = self.node_ids.get(&entity_key).cloned() {
-                    self.edges.push(LineageEdge {
-                        id: Uuid::new_v4().to_string(),
-                        source: id,
-                        target: entity_id,
-                        edge_type: LineageEdgeType::MeasureToEntity,
-                        label: None,
-                    });
-                }
+            if let Some(entity_id) = primary_entity_id.clone() {
+                self.edges.push(LineageEdge {
+                    id: Uuid::new_v4().to_string(),
+                    source: id,
+                    target: entity_id,
+                    edge_type: LineageEdgeType::MeasureToEntity,
+                    label: None,
+                });
             }
         }
 
         // Add dimension nodes
         for dim in &sm.dimensions {
-            let id = Uuid::new_v4().to_string();
             let key = format!("dimension.{}.{}", sm.name, dim.name);
+            let id = self.new_node_id(&key);
 
             let mut metadata = HashMap::new();
             metadata.insert("dimension_type".to_string(), serde_json::json!(dim.dimension_type));
             metadata.insert("semantic_model".to_string(), serde_json::json!(sm.name));
+            if let Some(ref group) = sm.group {
+                metadata.insert("group".to_string(), serde_json::json!(group));
+            }
+            if !sm.meta.is_empty() {
+                metadata.insert("meta".to_string(), serde_json::json!(sm.meta));
+            }
             if let Some(ref expr) = dim.expr {
                 metadata.insert("expr".to_string(), serde_json::json!(expr));
             }
+            if let Some(grain) = dim
+                .type_params
+                .as_ref()
+                .and_then(|p| p.time_granularity.as_ref())
+            {
+                metadata.insert("time_granularity".to_string(), serde_json::json!(grain));
+            }
+            if let Some(ref label) = dim.label {
+                metadata.insert("label".to_string(), serde_json::json!(label));
+            }
 
             self.nodes.push(LineageNode {
                 id: id.clone(),
@@ -249,34 +686,126 @@ impl LineageBuilder {
             self.node_ids.insert(key, id.clone());
 
             // Add edge from dimension to primary entity
-            let primary_entity = sm.entities.iter().find(|e| e.entity_type == "primary");
-            if let Some(entity) = primary_entity {
-                let entity_key = format!("entity.{}.{}", sm.name, entity.name);
-                if let Some(entity_id) = self.node_ids.get(&entity_key).cloned() {
-                    self.edges.push(LineageEdge {
-                        id: Uuid::new_v4().to_string(),
-                        source: id,
-                        target: entity_id,
-                        edge_type: LineageEdgeType::DimensionToEntity,
-                        label: None,
-                    });
-                }
+            if let Some(entity_id) = primary_entity_id.clone() {
+                self.edges.push(LineageEdge {
+                    id: Uuid::new_v4().to_string(),
+                    source: id,
+                    target: entity_id,
+                    edge_type: LineageEdgeType::DimensionToEntity,
+                    label: None,
+                });
+            }
+        }
+
+        // Add edges from measures to the time dimension they aggregate over,
+        // which a measure may override from the semantic model default
+        let default_time_dimension = sm
+            .defaults
+            .as_ref()
+            .and_then(|d| d.agg_time_dimension.as_ref());
+        for measure in &sm.measures {
+            let Some(time_dimension) = measure
+                .agg_time_dimension
+                .as_ref()
+                .or(default_time_dimension)
+            else {
+                continue;
+            };
+            let measure_key = format!("measure.{}.{}", sm.name, measure.name);
+            let dimension_key = format!("dimension.{}.{}", sm.name, time_dimension);
+            if let (Some(measure_id), Some(dimension_id)) = (
+                self.node_ids.get(&measure_key).cloned(),
+                self.node_ids.get(&dimension_key).cloned(),
+            ) {
+                self.edges.push(LineageEdge {
+                    id: Uuid::new_v4().to_string(),
+                    source: measure_id,
+                    target: dimension_id,
+                    edge_type: LineageEdgeType::MeasureToDimension,
+                    label: Some("agg_time_dimension".to_string()),
+                });
             }
         }
     }
 
+    /// Resolve the node ID of a semantic model's primary entity.
+    ///
+    /// Uses the entity declared with `type: primary`, falling back to the
+    /// top-level `primary_entity` key. When that name is not among the declared
+    /// entities, a synthetic entity node linked to the model is created.
+    fn primary_entity_id(&mut self, sm: &SemanticModel) -> Option<String> {
+        if let Some(entity) = sm.entities.iter().find(|e| e.entity_type == "primary") {
+            let entity_key = format!("entity.{}.{}", sm.name, entity.name);
+            return self.node_ids.get(&entity_key).cloned();
+        }
+
+        let name = sm.primary_entity.as_ref()?;
+        let entity_key = format!("entity.{}.{}", sm.name, name);
+        if let Some(entity_id) = self.node_ids.get(&entity_key) {
+            return Some(entity_id.clone());
+        }
+
+        let id = self.new_node_id(&entity_key);
+        let mut metadata = HashMap::new();
+        metadata.insert("entity_type".to_string(), serde_json::json!("primary"));
+        metadata.insert("semantic_model".to_string(), serde_json::json!(sm.name));
+        metadata.insert("synthetic".to_string(), serde_json::json!(true));
+        if let Some(ref group) = sm.group {
+            metadata.insert("group".to_string(), serde_json::json!(group));
+        }
+        if !sm.meta.is_empty() {
+            metadata.insert("meta".to_string(), serde_json::json!(sm.meta));
+        }
+
+        self.nodes.push(LineageNode {
+            id: id.clone(),
+            node_type: LineageNodeType::Entity,
+            name: name.clone(),
+            description: None,
+            metadata,
+        });
+        self.node_ids.insert(entity_key, id.clone());
+
+        let model_key = format!("model.{}", sm.model);
+        if let Some(model_id) = self.node_ids.get(&model_key).cloned() {
+            self.edges.push(LineageEdge {
+                id: Uuid::new_v4().to_string(),
+                source: id.clone(),
+                target: model_id,
+                edge_type: LineageEdgeType::EntityToModel,
+                label: None,
+            });
+        }
+
+        Some(id)
+    }
+
     fn add_metric_node(&mut self, metric: &Metric) {
-        let id = Uuid::new_v4().to_string();
         let key = format!("metric.{}", metric.name);
+        let id = self.new_node_id(&key);
 
         let mut metadata = HashMap::new();
         metadata.insert("metric_type".to_string(), serde_json::json!(metric.metric_type));
+        if let Some(ref group) = metric.group {
+            metadata.insert("group".to_string(), serde_json::json!(group));
+        }
         if let Some(ref filter) = metric.filter {
             metadata.insert("filter".to_string(), serde_json::json!(filter));
         }
         if let Some(ref label) = metric.label {
             metadata.insert("label".to_string(), serde_json::json!(label));
         }
+        if !metric.meta.is_empty() {
+            metadata.insert("meta".to_string(), serde_json::json!(metric.meta));
+        }
+        if let Some(ref conversion) = metric.type_params.conversion_type_params {
+            if let Some(ref entity) = conversion.entity {
+                metadata.insert("conversion_entity".to_string(), serde_json::json!(entity));
+            }
+            if let Some(ref window) = conversion.window {
+                metadata.insert("conversion_window".to_string(), serde_json::json!(window));
+            }
+        }
 
         self.nodes.push(LineageNode {
             id: id.clone(),
@@ -308,7 +837,7 @@ impl LineageBuilder {
                                 source: metric_id.clone(),
                                 target: measure_id,
                                 edge_type: LineageEdgeType::MetricToMeasure,
-                                label: None,
+                                label: measure_ref.filter.clone(),
                             });
                             break;
                         }
@@ -319,6 +848,10 @@ impl LineageBuilder {
                 // Link to other metrics
                 if let Some(ref metric_refs) = metric.type_params.metrics {
                     for metric_ref in metric_refs {
+                        if metric_ref.name == metric.name {
+                            self.report_self_reference(metric, &metric_id);
+                            continue;
+                        }
                         let ref_key = format!("metric.{}", metric_ref.name);
                         if let Some(ref_id) = self.node_ids.get(&ref_key).cloned() {
                             self.edges.push(LineageEdge {
@@ -331,9 +864,294 @@ impl LineageBuilder {
                         }
                     }
                 }
+
+                // Also link metrics named in `expr` that were left out of `metrics`;
+                // the audit reports them as MissingMetricRef
+                if let Some(ref expr) = metric.type_params.expr {
+                    let declared: Vec<&str> = metric
+                        .type_params
+                        .metrics
+                        .iter()
+                        .flatten()
+                        .flat_map(|r| std::iter::once(r.name.as_str()).chain(r.alias.as_deref()))
+                        .collect();
+                    for name in parse_expr_identifiers(expr) {
+                        if declared.contains(&name.as_str()) {
+                            continue;
+                        }
+                        if name == metric.name {
+                            self.report_self_reference(metric, &metric_id);
+                            continue;
+                        }
+                        if let Some(ref_id) = self.node_ids.get(&format!("metric.{}", name)).cloned() {
+                            self.edges.push(LineageEdge {
+                                id: Uuid::new_v4().to_string(),
+                                source: metric_id.clone(),
+                                target: ref_id,
+                                edge_type: LineageEdgeType::MetricToMetric,
+                                label: Some("expr".to_string()),
+                            });
+                        }
+                    }
+                }
             }
             _ => {}
         }
+
+        // Link to the dimensions, entities and metrics the filter references
+        let mut linked = HashSet::new();
+        for reference in &metric.filter_references {
+            let (target_key, edge_type) = match reference.kind {
+                FilterReferenceKind::Dimension | FilterReferenceKind::TimeDimension => (
+                    self.find_dimension_key(
+                        semantic_models,
+                        &reference.name,
+                        reference.entity.as_deref(),
+                    ),
+                    LineageEdgeType::MetricToDimension,
+                ),
+                FilterReferenceKind::Entity => (
+                    self.find_entity_key(semantic_models, &reference.name),
+                    LineageEdgeType::MetricToEntity,
+                ),
+                FilterReferenceKind::Metric => (
+                    Some(format!("metric.{}", reference.name)),
+                    LineageEdgeType::MetricToMetric,
+                ),
+            };
+
+            let Some(target_id) = target_key.and_then(|k| self.node_ids.get(&k).cloned()) else {
+                continue;
+            };
+            if target_id == metric_id || !linked.insert(target_id.clone()) {
+                continue;
+            }
+
+            self.edges.push(LineageEdge {
+                id: Uuid::new_v4().to_string(),
+                source: metric_id.clone(),
+                target: target_id,
+                edge_type,
+                label: Some("filter".to_string()),
+            });
+        }
+    }
+
+    /// dbt forbids two models with the same name, e.g. `stg_orders.sql` in two
+    /// model paths; keep the first as the node and report the later one
+    fn report_duplicate_model(&mut self, original: &DbtModel, duplicate: &DbtModel) {
+        self.diagnostics.push(AuditIssue {
+            severity: IssueSeverity::Error,
+            issue_type: IssueType::DuplicateDefinition,
+            message: format!(
+                "Model '{}' is defined in both {} and {}",
+                duplicate.name, original.file_path, duplicate.file_path
+            ),
+            node_id: self
+                .node_ids
+                .get(&format!("model.{}", original.name))
+                .cloned(),
+            suggestion: Some(
+                "Rename or remove one of the files; dbt requires unique model names".to_string(),
+            ),
+            source_location: None,
+        });
+    }
+
+    /// A model that refs itself, usually a copy-paste typo, would become a
+    /// self-loop; skip the edge and report the cycle instead
+    fn report_model_self_reference(&mut self, model: &DbtModel, model_id: &str) {
+        self.diagnostics.push(AuditIssue {
+            severity: IssueSeverity::Error,
+            issue_type: IssueType::CircularDependency,
+            message: format!("Model '{}' references itself", model.name),
+            node_id: Some(model_id.to_string()),
+            suggestion: Some(format!(
+                "Replace ref('{}') with the intended upstream model, or use {{{{ this }}}} \
+                 for the model's own relation",
+                model.name
+            )),
+            source_location: None,
+        });
+    }
+
+    /// A derived metric that lists itself as an input would become a self-loop;
+    /// skip the edge and report the cycle instead
+    fn report_self_reference(&mut self, metric: &Metric, metric_id: &str) {
+        self.diagnostics.push(AuditIssue {
+            severity: IssueSeverity::Error,
+            issue_type: IssueType::CircularDependency,
+            message: format!("Derived metric '{}' references itself", metric.name),
+            node_id: Some(metric_id.to_string()),
+            suggestion: Some(format!(
+                "Remove '{}' from its own inputs or fix the misspelled metric name",
+                metric.name
+            )),
+            source_location: None,
+        });
+    }
+
+    fn add_lookml_view_nodes(&mut self, view: &LookmlView) {
+        let targets = self.lookml_view_targets(view);
+
+        for (field, node_type) in view
+            .dimensions
+            .iter()
+            .map(|d| (d, LineageNodeType::Dimension))
+            .chain(view.measures.iter().map(|m| (m, LineageNodeType::Measure)))
+        {
+            let id = self.add_lookml_field_node(view, field, node_type.clone());
+
+            for (target_id, target_type) in &targets {
+                let edge_type = match (&node_type, target_type) {
+                    (LineageNodeType::Measure, LineageNodeType::Source) => {
+                        LineageEdgeType::MeasureToSource
+                    }
+                    (LineageNodeType::Measure, _) => LineageEdgeType::MeasureToModel,
+                    (_, LineageNodeType::Source) => LineageEdgeType::DimensionToSource,
+                    _ => LineageEdgeType::DimensionToModel,
+                };
+
+                self.edges.push(LineageEdge {
+                    id: Uuid::new_v4().to_string(),
+                    source: id.clone(),
+                    target: target_id.clone(),
+                    edge_type,
+                    label: Some(view.name.clone()),
+                });
+            }
+        }
+    }
+
+    fn add_lookml_field_node(
+        &mut self,
+        view: &LookmlView,
+        field: &LookmlField,
+        node_type: LineageNodeType,
+    ) -> String {
+        let kind = match node_type {
+            LineageNodeType::Measure => "measure",
+            _ => "dimension",
+        };
+        let key = format!("looker_{}.{}.{}", kind, view.name, field.name);
+        let id = self.new_node_id(&key);
+
+        let mut metadata = HashMap::new();
+        metadata.insert("semantic_layer".to_string(), serde_json::json!("looker"));
+        metadata.insert("view".to_string(), serde_json::json!(view.name));
+        metadata.insert("file_path".to_string(), serde_json::json!(view.file_path));
+        if let Some(ref field_type) = field.field_type {
+            metadata.insert("type".to_string(), serde_json::json!(field_type));
+        }
+        if let Some(ref sql) = field.sql {
+            metadata.insert("sql".to_string(), serde_json::json!(sql));
+        }
+        if let Some(ref label) = field.label {
+            metadata.insert("label".to_string(), serde_json::json!(label));
+        }
+
+        self.nodes.push(LineageNode {
+            id: id.clone(),
+            node_type,
+            name: field.name.clone(),
+            description: field.description.clone(),
+            metadata,
+        });
+
+        self.node_ids.insert(key, id.clone());
+        id
+    }
+
+    /// Find the dbt models or sources a Looker view reads from, matching
+    /// `sql_table_name` and the tables named in a derived table by table name
+    fn lookml_view_targets(&self, view: &LookmlView) -> Vec<(String, LineageNodeType)> {
+        let mut tables = Vec::new();
+        if let Some(ref table) = view.sql_table_name {
+            tables.push(table.clone());
+        }
+        if let Some(ref sql) = view.derived_table_sql {
+            let table_regex = Regex::new(r#"(?i)\b(?:from|join)\s+([\w."`\[\]]+)"#).unwrap();
+            tables.extend(
+                table_regex
+                    .captures_iter(sql)
+                    .filter_map(|cap| cap.get(1).map(|m| m.as_str().to_string())),
+            );
+        }
+
+        let mut targets = Vec::new();
+        for table in tables {
+            let name = table
+                .rsplit('.')
+                .next()
+                .unwrap_or(&table)
+                .trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'))
+                .to_lowercase();
+
+            let target = self
+                .node_ids
+                .get(&format!("model.{}", name))
+                .map(|id| (id.clone(), LineageNodeType::Model))
+                .or_else(|| {
+                    self.nodes
+                        .iter()
+                        .find(|n| {
+                            n.node_type == LineageNodeType::Source && n.name.to_lowercase() == name
+                        })
+                        .map(|n| (n.id.clone(), LineageNodeType::Source))
+                });
+
+            if let Some(target) = target {
+                if !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+        }
+
+        targets
+    }
+
+    /// Resolve a dimension to its node key, preferring the semantic model whose
+    /// primary entity matches the qualifying entity
+    fn find_dimension_key(
+        &self,
+        semantic_models: &[SemanticModel],
+        name: &str,
+        entity: Option<&str>,
+    ) -> Option<String> {
+        let has_dimension = |sm: &&SemanticModel| sm.dimensions.iter().any(|d| d.name == name);
+
+        let owner = entity
+            .and_then(|entity| {
+                semantic_models.iter().filter(has_dimension).find(|sm| {
+                    sm.primary_entity.as_deref() == Some(entity)
+                        || sm
+                            .entities
+                            .iter()
+                            .any(|e| e.name == entity && e.entity_type == "primary")
+                })
+            })
+            .or_else(|| semantic_models.iter().find(has_dimension))?;
+
+        Some(format!("dimension.{}.{}", owner.name, name))
+    }
+
+    /// Resolve an entity to its node key, preferring the semantic model where
+    /// it is the primary entity
+    fn find_entity_key(&self, semantic_models: &[SemanticModel], name: &str) -> Option<String> {
+        let owner = semantic_models
+            .iter()
+            .find(|sm| {
+                sm.entities
+                    .iter()
+                    .any(|e| e.name == name && e.entity_type == "primary")
+            })
+            .or_else(|| {
+                semantic_models
+                    .iter()
+                    .find(|sm| sm.entities.iter().any(|e| e.name == name))
+            })?;
+
+        Some(format!("entity.{}.{}", owner.name, name))
     }
 }
 
@@ -342,3 +1160,563 @@ impl Default for LineageBuilder {
         Self::new()
     }
 }
+
+/// Whether a query selects `*` or `table.*`, ignoring `count(*)` and the like
+fn selects_wildcard(sql: &str) -> bool {
+    let wildcard_regex =
+        Regex::new(r"(?is)(?:\bselect\s+(?:distinct\s+)?|,\s*)(?:\w+\.)?\*(?:\s|,|$)").unwrap();
+    wildcard_regex.is_match(sql)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::parse_filter_references;
+    use crate::types::{
+        Dimension, Measure, MeasureRef, MetricRef, MetricTypeParams, SemanticModelDefaults,
+    };
+
+    fn model(name: &str) -> DbtModel {
+        DbtModel {
+            unique_id: format!("model.{}", name),
+            name: name.to_string(),
+            schema: None,
+            database: None,
+            qualified_name: None,
+            alias: None,
+            description: None,
+            columns: Vec::new(),
+            depends_on: Vec::new(),
+            refs: Vec::new(),
+            sources: Vec::new(),
+            metric_refs: Vec::new(),
+            file_path: format!("models/{}.sql", name),
+            raw_sql: None,
+            materialization: None,
+            tags: Vec::new(),
+            access: None,
+            group: None,
+            unit_test_count: 0,
+            unique_key: Vec::new(),
+            duplicate_columns: Vec::new(),
+            source_location: None,
+            project: None,
+        }
+    }
+
+    fn semantic_model(name: &str, model: &str) -> SemanticModel {
+        SemanticModel {
+            name: name.to_string(),
+            description: None,
+            model: model.to_string(),
+            node_relation: None,
+            group: None,
+            primary_entity: None,
+            defaults: None,
+            entities: Vec::new(),
+            measures: Vec::new(),
+            dimensions: Vec::new(),
+            source_location: None,
+            meta: HashMap::new(),
+            project: None,
+        }
+    }
+
+    fn measure(name: &str) -> Measure {
+        Measure {
+            name: name.to_string(),
+            agg: "sum".to_string(),
+            expr: None,
+            description: None,
+            label: None,
+            create_metric: None,
+            non_additive_dimension: None,
+            agg_time_dimension: None,
+            fill_nulls_with: None,
+            join_to_timespine: None,
+        }
+    }
+
+    #[test]
+    fn test_model_querying_metric() {
+        let mut revenue_report = model("revenue_report");
+        revenue_report.raw_sql = Some("select * from {{ metric('revenue') }}".to_string());
+        revenue_report.metric_refs = vec!["revenue".to_string()];
+        revenue_report.depends_on = vec!["metric.jaffle_shop.revenue".to_string()];
+        // As read from a manifest, without the SQL-derived metric_refs
+        let mut manifest_report = model("manifest_report");
+        manifest_report.depends_on = vec![
+            "metric.jaffle_shop.revenue".to_string(),
+            "macro.dbt.date_trunc".to_string(),
+        ];
+        let revenue = Metric {
+            name: "revenue".to_string(),
+            description: None,
+            metric_type: "simple".to_string(),
+            group: None,
+            type_params: MetricTypeParams {
+                measure: None,
+                expr: None,
+                metrics: None,
+                window: None,
+                grain_to_date: None,
+                conversion_type_params: None,
+            },
+            filter: None,
+            filter_references: Vec::new(),
+            label: None,
+            meta: HashMap::new(),
+            config: None,
+            source_location: None,
+            project: None,
+        };
+
+        let graph =
+            LineageBuilder::new().build(&[revenue_report, manifest_report], &[], &[], &[revenue]);
+
+        let find = |name: &str| graph.nodes.iter().find(|n| n.name == name).unwrap();
+        let metric_node = find("revenue");
+        for name in ["revenue_report", "manifest_report"] {
+            let edges: Vec<&LineageEdge> = graph
+                .edges
+                .iter()
+                .filter(|e| e.source == find(name).id)
+                .collect();
+            assert_eq!(edges.len(), 1, "{}", name);
+            assert_eq!(edges[0].edge_type, LineageEdgeType::ModelToMetric);
+            assert_eq!(edges[0].target, metric_node.id);
+        }
+    }
+
+    #[test]
+    fn test_metric_filtered_by_another_metric() {
+        let metric = |name: &str, filter: Option<&str>| Metric {
+            name: name.to_string(),
+            description: None,
+            metric_type: "simple".to_string(),
+            group: None,
+            type_params: MetricTypeParams {
+                measure: None,
+                expr: None,
+                metrics: None,
+                window: None,
+                grain_to_date: None,
+                conversion_type_params: None,
+            },
+            filter: filter.map(|f| f.to_string()),
+            filter_references: filter.map(parse_filter_references).unwrap_or_default(),
+            label: None,
+            meta: HashMap::new(),
+            config: None,
+            source_location: None,
+            project: None,
+        };
+        let repeat_revenue = metric(
+            "repeat_customer_revenue",
+            Some("{{ Metric('order_count', group_by=['customer']) }} > 1"),
+        );
+        let order_count = metric("order_count", None);
+
+        let graph = LineageBuilder::new().build(&[], &[], &[], &[repeat_revenue, order_count]);
+
+        let find = |name: &str| graph.nodes.iter().find(|n| n.name == name).unwrap();
+        let (filtered, input) = (find("repeat_customer_revenue"), find("order_count"));
+        assert!(graph.edges.iter().any(|e| {
+            e.edge_type == LineageEdgeType::MetricToMetric
+                && e.source == filtered.id
+                && e.target == input.id
+                && e.label.as_deref() == Some("filter")
+        }));
+    }
+
+    #[test]
+    fn test_ref_resolves_to_seed() {
+        let mut stg_countries = model("stg_countries");
+        stg_countries.refs = vec!["country_codes".to_string()];
+        stg_countries.depends_on = vec!["model.country_codes".to_string()];
+        let seed = DbtSeed {
+            unique_id: "seed.country_codes".to_string(),
+            name: "country_codes".to_string(),
+            file_path: "seeds/country_codes.csv".to_string(),
+            description: None,
+            columns: Vec::new(),
+        };
+
+        let graph = LineageBuilder::new()
+            .with_seeds(vec![seed])
+            .build(&[stg_countries], &[], &[], &[]);
+
+        let find = |name: &str| graph.nodes.iter().find(|n| n.name == name).unwrap();
+        let (model_node, seed_node) = (find("stg_countries"), find("country_codes"));
+        assert_eq!(seed_node.node_type, LineageNodeType::Seed);
+        assert!(graph.edges.iter().any(|e| {
+            e.edge_type == LineageEdgeType::ModelToSeed
+                && e.source == model_node.id
+                && e.target == seed_node.id
+        }));
+    }
+
+    #[test]
+    fn test_self_referencing_derived_metric() {
+        let revenue_growth = Metric {
+            name: "revenue_growth".to_string(),
+            description: None,
+            metric_type: "derived".to_string(),
+            group: None,
+            type_params: MetricTypeParams {
+                measure: None,
+                expr: Some("revenue_growth - revenue_growth_prev".to_string()),
+                metrics: Some(vec![MetricRef {
+                    name: "revenue_growth".to_string(),
+                    alias: None,
+                    offset_window: None,
+                    offset_to_grain: None,
+                }]),
+                window: None,
+                grain_to_date: None,
+                conversion_type_params: None,
+            },
+            filter: None,
+            filter_references: Vec::new(),
+            label: None,
+            meta: HashMap::new(),
+            config: None,
+            source_location: None,
+            project: None,
+        };
+
+        let (graph, diagnostics) =
+            LineageBuilder::new().build_with_diagnostics(&[], &[], &[], &[revenue_growth]);
+
+        assert!(graph.edges.is_empty());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].issue_type, IssueType::CircularDependency);
+        assert_eq!(
+            diagnostics[0].node_id.as_deref(),
+            Some(graph.nodes[0].id.as_str())
+        );
+    }
+
+    #[test]
+    fn test_self_referencing_model() {
+        let mut stg_orders = model("stg_orders");
+        stg_orders.depends_on = vec!["model.stg_orders".to_string()];
+        let mut fct_orders = model("fct_orders");
+        fct_orders.depends_on = vec!["model.stg_orders".to_string()];
+
+        let (graph, diagnostics) =
+            LineageBuilder::new().build_with_diagnostics(&[stg_orders, fct_orders], &[], &[], &[]);
+
+        assert_eq!(graph.edges.len(), 1);
+        assert!(graph.edges.iter().all(|e| e.source != e.target));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].issue_type, IssueType::CircularDependency);
+        assert_eq!(
+            diagnostics[0].message,
+            "Model 'stg_orders' references itself"
+        );
+    }
+
+    #[test]
+    fn test_select_star_marks_column_lineage_as_wildcard() {
+        let mut stg_orders = model("stg_orders");
+        stg_orders.raw_sql = Some("select o.* from {{ source('shop', 'orders') }} o".to_string());
+        let mut order_counts = model("order_counts");
+        order_counts.raw_sql =
+            Some("select customer_id, count(*) as orders from {{ ref('stg_orders') }}".to_string());
+
+        let graph = LineageBuilder::new().build(&[stg_orders, order_counts], &[], &[], &[]);
+
+        let column_lineage = |name: &str| {
+            let node = graph.nodes.iter().find(|n| n.name == name).unwrap();
+            node.metadata.get("column_lineage").cloned()
+        };
+        assert_eq!(
+            column_lineage("stg_orders"),
+            Some(serde_json::json!("wildcard"))
+        );
+        assert_eq!(column_lineage("order_counts"), None);
+    }
+
+    #[test]
+    fn test_edges_follow_depends_on() {
+        let mut fct_orders = model("fct_orders");
+        fct_orders.refs = vec!["stg_orders".to_string()];
+        fct_orders.depends_on = vec![
+            "model.stg_orders".to_string(),
+            "model.jaffle_shop.stg_payments".to_string(),
+            "source.jaffle_shop.raw.orders".to_string(),
+        ];
+        let source = DbtSource {
+            unique_id: "source.raw.orders".to_string(),
+            name: "orders".to_string(),
+            source_name: "raw".to_string(),
+            schema: None,
+            database: None,
+            qualified_name: None,
+            description: None,
+            columns: Vec::new(),
+            loader: None,
+            freshness: None,
+            loaded_at_field: None,
+            tags: Vec::new(),
+            project: None,
+        };
+        let models = [model("stg_orders"), model("stg_payments"), fct_orders];
+
+        let graph = LineageBuilder::new().build(&models, &[source], &[], &[]);
+
+        let id = |name: &str| &graph.nodes.iter().find(|n| n.name == name).unwrap().id;
+        let has_edge = |target: &str, edge_type: LineageEdgeType| {
+            graph.edges.iter().any(|e| {
+                e.source == *id("fct_orders") && e.target == *id(target) && e.edge_type == edge_type
+            })
+        };
+        assert!(has_edge("stg_orders", LineageEdgeType::ModelToModel));
+        assert!(has_edge("stg_payments", LineageEdgeType::ModelToModel));
+        assert!(has_edge("orders", LineageEdgeType::ModelToSource));
+    }
+
+    #[test]
+    fn test_deterministic_ids_are_stable_across_builds() {
+        let mut fct_orders = model("fct_orders");
+        fct_orders.refs = vec!["stg_orders".to_string()];
+        fct_orders.depends_on = vec!["model.stg_orders".to_string()];
+        let mut sm = semantic_model("orders", "fct_orders");
+        sm.primary_entity = Some("order".to_string());
+        sm.measures.push(measure("order_total"));
+        let models = [model("stg_orders"), fct_orders];
+        let build = || {
+            LineageBuilder::new().with_deterministic_ids(true).build(
+                &models,
+                &[],
+                &[sm.clone()],
+                &[],
+            )
+        };
+
+        let (first, second) = (build(), build());
+
+        let ids = |graph: &LineageGraph| -> Vec<String> {
+            graph.nodes.iter().map(|n| n.id.clone()).collect()
+        };
+        let edge_ids = |graph: &LineageGraph| -> Vec<String> {
+            graph.edges.iter().map(|e| e.id.clone()).collect()
+        };
+        assert_eq!(ids(&first), ids(&second));
+        assert_eq!(edge_ids(&first), edge_ids(&second));
+        let unique: HashSet<String> = ids(&first).into_iter().collect();
+        assert_eq!(unique.len(), first.nodes.len());
+    }
+
+    #[test]
+    fn test_pruning_drops_unreferenced_models() {
+        let mut sm = semantic_model("orders", "fct_orders");
+        sm.primary_entity = Some("order".to_string());
+        sm.measures.push(measure("order_total"));
+        let revenue = Metric {
+            name: "revenue".to_string(),
+            description: None,
+            metric_type: "simple".to_string(),
+            group: None,
+            type_params: MetricTypeParams {
+                measure: Some(MeasureRef {
+                    name: "order_total".to_string(),
+                    filter: None,
+                    alias: None,
+                    fill_nulls_with: None,
+                    join_to_timespine: None,
+                }),
+                expr: None,
+                metrics: None,
+                window: None,
+                grain_to_date: None,
+                conversion_type_params: None,
+            },
+            filter: None,
+            filter_references: Vec::new(),
+            label: None,
+            meta: HashMap::new(),
+            config: None,
+            source_location: None,
+            project: None,
+        };
+        let models = [model("fct_orders"), model("scratch_analysis")];
+
+        let full = LineageBuilder::new().build(
+            &models,
+            &[],
+            std::slice::from_ref(&sm),
+            std::slice::from_ref(&revenue),
+        );
+        let pruned =
+            LineageBuilder::new()
+                .with_pruning(true)
+                .build(&models, &[], &[sm], &[revenue]);
+
+        assert!(full.nodes.iter().any(|n| n.name == "scratch_analysis"));
+        assert!(!pruned.nodes.iter().any(|n| n.name == "scratch_analysis"));
+        assert!(pruned.nodes.iter().any(|n| n.name == "fct_orders"));
+        assert_eq!(pruned.nodes.len(), full.nodes.len() - 1);
+    }
+
+    #[test]
+    fn test_measure_agg_time_dimension_override() {
+        let time_dimension = |name: &str| Dimension {
+            name: name.to_string(),
+            dimension_type: "time".to_string(),
+            expr: None,
+            description: None,
+            label: None,
+            type_params: None,
+        };
+        let mut sm = semantic_model("orders", "fct_orders");
+        sm.defaults = Some(SemanticModelDefaults {
+            agg_time_dimension: Some("ordered_at".to_string()),
+        });
+        sm.dimensions = vec![time_dimension("ordered_at"), time_dimension("shipped_at")];
+        let mut shipped_total = measure("shipped_total");
+        shipped_total.agg_time_dimension = Some("shipped_at".to_string());
+        sm.measures = vec![measure("order_total"), shipped_total];
+
+        let graph = LineageBuilder::new().build(&[model("fct_orders")], &[], &[sm], &[]);
+
+        let find = |name: &str| graph.nodes.iter().find(|n| n.name == name).unwrap();
+        let time_dimensions_of = |measure: &str| {
+            let measure_id = &find(measure).id;
+            graph
+                .edges
+                .iter()
+                .filter(|e| {
+                    e.edge_type == LineageEdgeType::MeasureToDimension && &e.source == measure_id
+                })
+                .filter_map(|e| graph.nodes.iter().find(|n| n.id == e.target))
+                .map(|n| n.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(time_dimensions_of("order_total"), vec!["ordered_at"]);
+        assert_eq!(time_dimensions_of("shipped_total"), vec!["shipped_at"]);
+    }
+
+    #[test]
+    fn test_measures_inherit_default_agg_time_dimension() {
+        let mut sm = semantic_model("orders", "fct_orders");
+        sm.defaults = Some(SemanticModelDefaults {
+            agg_time_dimension: Some("ordered_at".to_string()),
+        });
+        sm.dimensions = vec![Dimension {
+            name: "ordered_at".to_string(),
+            dimension_type: "time".to_string(),
+            expr: None,
+            description: None,
+            label: None,
+            type_params: None,
+        }];
+        sm.measures = vec![measure("order_total"), measure("order_count")];
+
+        let graph = LineageBuilder::new().build(&[model("fct_orders")], &[], &[sm], &[]);
+
+        let ordered_at = graph.nodes.iter().find(|n| n.name == "ordered_at").unwrap();
+        for name in ["order_total", "order_count"] {
+            let measure = graph.nodes.iter().find(|n| n.name == name).unwrap();
+            assert!(
+                graph.edges.iter().any(|e| {
+                    e.edge_type == LineageEdgeType::MeasureToDimension
+                        && e.source == measure.id
+                        && e.target == ordered_at.id
+                }),
+                "{} has no edge to its default agg_time_dimension",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_top_level_primary_entity() {
+        let mut sm = semantic_model("orders", "fct_orders");
+        sm.primary_entity = Some("order".to_string());
+        sm.measures.push(measure("order_total"));
+
+        let graph = LineageBuilder::new().build(&[model("fct_orders")], &[], &[sm], &[]);
+
+        let entity = graph
+            .nodes
+            .iter()
+            .find(|n| n.node_type == LineageNodeType::Entity && n.name == "order")
+            .expect("synthetic primary entity");
+        let measure = graph.nodes.iter().find(|n| n.name == "order_total").unwrap();
+
+        assert!(graph.edges.iter().any(|e| {
+            e.edge_type == LineageEdgeType::MeasureToEntity
+                && e.source == measure.id
+                && e.target == entity.id
+        }));
+        assert!(graph.edges.iter().any(|e| {
+            e.edge_type == LineageEdgeType::EntityToModel && e.source == entity.id
+        }));
+    }
+
+    #[test]
+    fn test_semantic_model_meta_inherited_by_measures() {
+        let mut orders = semantic_model("orders", "fct_orders");
+        orders.measures = vec![measure("order_total")];
+        orders
+            .meta
+            .insert("owner".to_string(), serde_json::json!("finance"));
+
+        let graph = LineageBuilder::new().build(&[model("fct_orders")], &[], &[orders], &[]);
+
+        let order_total = graph
+            .nodes
+            .iter()
+            .find(|n| n.name == "order_total")
+            .unwrap();
+        assert_eq!(order_total.metadata["meta"]["owner"], "finance");
+    }
+
+    #[test]
+    fn test_measure_and_dimension_labels_in_metadata() {
+        let project_dir =
+            std::env::temp_dir().join(format!("semantic-tracer-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(project_dir.join("models")).unwrap();
+        std::fs::write(
+            project_dir.join("models/orders.yml"),
+            r#"
+semantic_models:
+  - name: orders
+    model: ref('fct_orders')
+    measures:
+      - name: order_total
+        label: Order Total (USD)
+        agg: sum
+    dimensions:
+      - name: ordered_at
+        label: Order Date
+        type: time
+"#,
+        )
+        .unwrap();
+
+        let parsed = crate::parsers::DbtSemanticLayerParser::new(&project_dir).parse();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+        let (semantic_models, _, _) = parsed.unwrap();
+        assert_eq!(
+            semantic_models[0].measures[0].label.as_deref(),
+            Some("Order Total (USD)")
+        );
+
+        let graph = LineageBuilder::new().build(&[model("fct_orders")], &[], &semantic_models, &[]);
+
+        let label_of = |name: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|n| n.name == name)
+                .unwrap()
+                .metadata["label"]
+                .clone()
+        };
+        assert_eq!(label_of("order_total"), "Order Total (USD)");
+        assert_eq!(label_of("ordered_at"), "Order Date");
+    }
+}
@@ -2,6 +2,8 @@
 
 pub mod graph;
 pub mod analysis;
+pub mod diff;
+pub mod export;
 
 pub use graph::LineageBuilder;
 pub use analysis::LineageAnalyzer;
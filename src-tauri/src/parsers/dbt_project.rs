@@ -1,32 +1,125 @@
 //! Parser for dbt project files and models
 
-use crate::types::{DbtColumn, DbtModel, DbtProject, DbtSource, DbtSourceRef};
+use crate::parsers::yaml::{interpolate_env_vars, locate_definition, parse_yaml_documents};
+use crate::paths::normalize_path;
+use crate::types::{
+    DbtColumn, DbtFreshness, DbtFreshnessRule, DbtModel, DbtProject, DbtSeed, DbtSnapshot,
+    DbtSource, DbtSourceRef, DbtTest, ProfileTarget, SourceLocation,
+};
 use anyhow::{Context, Result};
 use regex::Regex;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// Catalog written to the target directory by `dbt docs generate`
+pub const CATALOG_FILE_NAME: &str = "catalog.json";
+
 pub struct DbtProjectParser {
     project_path: PathBuf,
+    ref_macros: Vec<String>,
+    source_macros: Vec<String>,
+    sql_extensions: Vec<String>,
+    read_failures: RefCell<Vec<String>>, // files skipped because they could not be read
+    unset_env_vars: RefCell<Vec<String>>, // env_var() names in dbt_project.yml with no value
 }
 
 impl DbtProjectParser {
     pub fn new(project_path: impl AsRef<Path>) -> Self {
         Self {
             project_path: project_path.as_ref().to_path_buf(),
+            ref_macros: vec!["ref".to_string()],
+            source_macros: vec!["source".to_string()],
+            sql_extensions: vec![".sql".to_string(), ".sql.jinja".to_string()],
+            read_failures: RefCell::new(Vec::new()),
+            unset_env_vars: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Take the messages for files and directories skipped so far because
+    /// they could not be read, e.g. for lack of permission or because they are
+    /// not UTF-8, or because a model file could not be parsed
+    pub fn take_read_failures(&self) -> Vec<String> {
+        self.read_failures.take()
+    }
+
+    /// Take the names of the variables `dbt_project.yml` reads through
+    /// `env_var()` that are unset and have no default, which are left as-is
+    pub fn take_unset_env_vars(&self) -> Vec<String> {
+        self.unset_env_vars.take()
+    }
+
+    /// Read a yml file, recording a read failure instead of failing the parse
+    fn read_yaml_file(&self, path: &Path) -> Option<String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Some(content),
+            Err(e) => {
+                self.record_read_failure(path, e);
+                None
+            }
         }
     }
 
+    fn record_read_failure(&self, path: &Path, error: impl std::fmt::Display) {
+        self.read_failures
+            .borrow_mut()
+            .push(skipped_file_warning(path, error));
+    }
+
+    /// Walk `root`, recording entries that cannot be read (such as
+    /// directories without permission) instead of dropping them silently
+    fn walk<'a>(&'a self, root: &Path) -> impl Iterator<Item = walkdir::DirEntry> + 'a {
+        walk_recording_failures(root, &self.read_failures)
+    }
+
+    /// Also treat calls to these macros (e.g. a project's `dbt_ref` wrapper)
+    /// as `ref()`
+    pub fn with_ref_macros(mut self, macros: Vec<String>) -> Self {
+        self.ref_macros.extend(macros);
+        self
+    }
+
+    /// Also treat calls to these macros as `source()`
+    pub fn with_source_macros(mut self, macros: Vec<String>) -> Self {
+        self.source_macros.extend(macros);
+        self
+    }
+
+    /// Also read models from files with these extensions, e.g. `.sql.j2`
+    pub fn with_sql_extensions(mut self, extensions: Vec<String>) -> Self {
+        self.sql_extensions
+            .extend(extensions.into_iter().map(|ext| {
+                if ext.starts_with('.') {
+                    ext
+                } else {
+                    format!(".{}", ext)
+                }
+            }));
+        self
+    }
+
+    /// Model name of a SQL file: its file name without the longest matching
+    /// SQL extension, or `None` if it has none of them
+    fn sql_model_name<'a>(&self, path: &'a Path) -> Option<&'a str> {
+        let file_name = path.file_name()?.to_str()?;
+        self.sql_extensions
+            .iter()
+            .filter_map(|ext| file_name.strip_suffix(ext.as_str()))
+            .min_by_key(|name| name.len())
+            .filter(|name| !name.is_empty())
+    }
+
     /// Parse the dbt_project.yml file
     pub fn parse_project(&self) -> Result<DbtProject> {
         let project_file = self.project_path.join("dbt_project.yml");
         let content = fs::read_to_string(&project_file)
             .with_context(|| format!("Failed to read dbt_project.yml at {:?}", project_file))?;
 
-        let yaml: serde_yaml::Value = serde_yaml::from_str(&content)
+        let mut yaml: serde_yaml::Value = serde_yaml::from_str(&content)
             .with_context(|| "Failed to parse dbt_project.yml as YAML")?;
+        *self.unset_env_vars.borrow_mut() = interpolate_env_vars(&mut yaml);
 
         Ok(DbtProject {
             name: yaml["name"]
@@ -47,6 +140,8 @@ impl DbtProjectParser {
                 .unwrap_or_else(|| vec!["analyses".to_string()]),
             macro_paths: self.extract_string_array(&yaml, "macro-paths")
                 .unwrap_or_else(|| vec!["macros".to_string()]),
+            snapshot_paths: self.extract_string_array(&yaml, "snapshot-paths")
+                .unwrap_or_else(|| vec!["snapshots".to_string()]),
             target_path: yaml["target-path"].as_str().map(|s| s.to_string()),
         })
     }
@@ -61,6 +156,16 @@ impl DbtProjectParser {
 
     /// Parse all models in the project
     pub fn parse_models(&self, project: &DbtProject) -> Result<Vec<DbtModel>> {
+        self.parse_models_with_progress(project, |_| {})
+    }
+
+    /// Parse all models, calling `on_batch` with the running model count
+    /// after each model path is scanned
+    pub fn parse_models_with_progress(
+        &self,
+        project: &DbtProject,
+        mut on_batch: impl FnMut(usize),
+    ) -> Result<Vec<DbtModel>> {
         let mut models = Vec::new();
 
         for model_path in &project.model_paths {
@@ -71,45 +176,73 @@ impl DbtProjectParser {
             }
 
             // Find all .sql files
-            for entry in WalkDir::new(&full_path)
-                .into_iter()
-                .filter_map(|e| e.ok())
-                .filter(|e| e.path().extension().map_or(false, |ext| ext == "sql"))
+            for entry in self
+                .walk(&full_path)
+                .filter(|e| self.sql_model_name(e.path()).is_some())
             {
-                if let Ok(model) = self.parse_model_file(entry.path()) {
-                    models.push(model);
+                match self.parse_model_file(entry.path()) {
+                    Ok(model) => models.push(model),
+                    Err(e) => self.record_read_failure(entry.path(), e),
                 }
             }
 
             // Find all schema.yml files for metadata
-            let schema_metadata = self.parse_schema_files(&full_path)?;
+            let (schema_metadata, unit_test_counts) = self.parse_schema_files(&full_path)?;
 
             // Merge metadata into models
             for model in &mut models {
                 if let Some(meta) = schema_metadata.get(&model.name) {
+                    model.schema = meta.schema.clone();
+                    model.database = meta.database.clone();
                     model.description = meta.description.clone();
-                    model.columns = meta.columns.clone();
+                    // Keep the first definition of a repeated column so it is
+                    // only counted once, remembering the repeat for the audit
+                    model.columns.clear();
+                    for column in &meta.columns {
+                        if model.columns.iter().any(|c| c.name == column.name) {
+                            if !model.duplicate_columns.contains(&column.name) {
+                                model.duplicate_columns.push(column.name.clone());
+                            }
+                        } else {
+                            model.columns.push(column.clone());
+                        }
+                    }
                     model.tags = meta.tags.clone();
+                    model.access = meta.access.clone();
+                    model.group = meta.group.clone();
+                    // Inline config() takes precedence over schema.yml config
+                    if model.materialization.is_none() {
+                        model.materialization = meta.materialization.clone();
+                    }
+                    if model.alias.is_none() {
+                        model.alias = meta.alias.clone();
+                    }
+                    if model.unique_key.is_empty() {
+                        model.unique_key = meta.unique_key.clone();
+                    }
+                    model.source_location = meta.source_location.clone();
+                }
+                if let Some(count) = unit_test_counts.get(&model.name) {
+                    model.unit_test_count = *count;
                 }
             }
+
+            on_batch(models.len());
         }
 
         Ok(models)
     }
 
     fn parse_model_file(&self, path: &Path) -> Result<DbtModel> {
-        let content = fs::read_to_string(path)?;
-        let name = path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown")
-            .to_string();
+        let content = read_sql(path)?;
+        let name = self.sql_model_name(path).unwrap_or("unknown").to_string();
 
         let unique_id = format!("model.{}", name);
 
         // Extract refs from SQL
         let refs = self.extract_refs(&content);
         let sources = self.extract_sources(&content);
+        let metric_refs = self.extract_metric_refs(&content);
 
         // Build depends_on from refs and sources
         let mut depends_on: Vec<String> = refs.iter()
@@ -119,51 +252,326 @@ impl DbtProjectParser {
             depends_on.push(format!("source.{}.{}", source.source_name, source.table_name));
         }
 
-        // Extract materialization from config
+        // Extract materialization, alias and incremental unique_key from config
         let materialization = self.extract_materialization(&content);
+        let alias = self.extract_config_value(&content, "alias");
+        let unique_key = self.extract_unique_key(&content);
 
         Ok(DbtModel {
             unique_id,
             name,
             schema: None,
             database: None,
+            qualified_name: None,
+            alias,
             description: None,
             columns: Vec::new(),
             depends_on,
             refs,
             sources,
-            file_path: path.to_string_lossy().to_string(),
+            metric_refs,
+            file_path: normalize_path(path),
             raw_sql: Some(content),
             materialization,
             tags: Vec::new(),
+            access: None,
+            group: None,
+            unit_test_count: 0,
+            unique_key,
+            duplicate_columns: Vec::new(),
+            source_location: None,
+            project: None,
         })
     }
 
+    /// Parse all snapshots in the project's snapshot paths
+    pub fn parse_snapshots(&self, project: &DbtProject) -> Result<Vec<DbtSnapshot>> {
+        let mut snapshots = Vec::new();
+
+        for snapshot_path in &project.snapshot_paths {
+            let full_path = self.project_path.join(snapshot_path);
+            if !full_path.exists() {
+                continue;
+            }
+
+            for entry in self
+                .walk(&full_path)
+                .filter(|e| e.path().extension().is_some_and(|ext| ext == "sql"))
+            {
+                let content = match read_sql(entry.path()) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        self.record_read_failure(entry.path(), e);
+                        continue;
+                    }
+                };
+                snapshots.extend(self.parse_snapshot_blocks(&content, entry.path()));
+            }
+        }
+
+        Ok(snapshots)
+    }
+
+    /// Parse all seeds in the project's seed paths: one per CSV file, with
+    /// descriptions and columns taken from any `seeds:` entries in yml files
+    /// alongside them
+    pub fn parse_seeds(&self, project: &DbtProject) -> Result<Vec<DbtSeed>> {
+        let mut seeds = Vec::new();
+        let mut metadata: HashMap<String, (Option<String>, Vec<DbtColumn>)> = HashMap::new();
+
+        for seed_path in &project.seed_paths {
+            let full_path = self.project_path.join(seed_path);
+            if !full_path.exists() {
+                continue;
+            }
+
+            for entry in self.walk(&full_path) {
+                let path = entry.path();
+                let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+                    continue;
+                };
+
+                match ext {
+                    "csv" => {
+                        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                            continue;
+                        };
+                        seeds.push(DbtSeed {
+                            unique_id: format!("seed.{}", name),
+                            name: name.to_string(),
+                            file_path: normalize_path(path),
+                            description: None,
+                            columns: Vec::new(),
+                        });
+                    }
+                    "yml" | "yaml" => {
+                        let Some(content) = self.read_yaml_file(path) else {
+                            continue;
+                        };
+                        let documents = match parse_yaml_documents(&content) {
+                            Ok(documents) => documents,
+                            Err(e) => {
+                                self.record_read_failure(path, e);
+                                continue;
+                            }
+                        };
+                        for yaml in documents {
+                            for seed in yaml["seeds"].as_sequence().into_iter().flatten() {
+                                if let Some(name) = seed["name"].as_str() {
+                                    metadata.insert(
+                                        name.to_string(),
+                                        (
+                                            seed["description"].as_str().map(|s| s.to_string()),
+                                            self.parse_columns(&seed["columns"]),
+                                        ),
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for seed in &mut seeds {
+            if let Some((description, columns)) = metadata.remove(&seed.name) {
+                seed.description = description;
+                seed.columns = columns;
+            }
+        }
+
+        Ok(seeds)
+    }
+
+    /// Parse dbt data tests: generic tests declared on models and their
+    /// columns in yml files under the model paths, and singular test SQL
+    /// files under the test paths
+    pub fn parse_tests(&self, project: &DbtProject) -> Result<Vec<DbtTest>> {
+        let mut tests = Vec::new();
+
+        for model_path in &project.model_paths {
+            let full_path = self.project_path.join(model_path);
+            if !full_path.exists() {
+                continue;
+            }
+
+            for entry in self.walk(&full_path).filter(|e| {
+                e.path()
+                    .extension()
+                    .is_some_and(|ext| ext == "yml" || ext == "yaml")
+            }) {
+                let Some(content) = self.read_yaml_file(entry.path()) else {
+                    continue;
+                };
+                let file_path = normalize_path(entry.path());
+
+                for yaml in parse_yaml_documents(&content).unwrap_or_default() {
+                    for model in yaml["models"].as_sequence().into_iter().flatten() {
+                        let Some(model_name) = model["name"].as_str() else {
+                            continue;
+                        };
+
+                        let mut declared: Vec<(String, Option<String>)> = generic_test_names(model)
+                            .into_iter()
+                            .map(|(test, args)| (format!("{}_{}{}", test, model_name, args), None))
+                            .collect();
+                        for column in model["columns"].as_sequence().into_iter().flatten() {
+                            let Some(column_name) = column["name"].as_str() else {
+                                continue;
+                            };
+                            for (test, args) in generic_test_names(column) {
+                                declared.push((
+                                    format!("{}_{}_{}{}", test, model_name, column_name, args),
+                                    Some(column_name.to_string()),
+                                ));
+                            }
+                        }
+
+                        tests.extend(declared.into_iter().map(|(name, column)| DbtTest {
+                            unique_id: format!("test.{}", name),
+                            name,
+                            test_type: "generic".to_string(),
+                            refs: vec![model_name.to_string()],
+                            column,
+                            file_path: file_path.clone(),
+                        }));
+                    }
+                }
+            }
+        }
+
+        for test_path in &project.test_paths {
+            let full_path = self.project_path.join(test_path);
+            if !full_path.exists() {
+                continue;
+            }
+
+            for entry in self
+                .walk(&full_path)
+                .filter(|e| e.path().extension().is_some_and(|ext| ext == "sql"))
+            {
+                let Some(name) = entry.path().file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                let content = match read_sql(entry.path()) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        self.record_read_failure(entry.path(), e);
+                        continue;
+                    }
+                };
+                tests.push(DbtTest {
+                    unique_id: format!("test.{}", name),
+                    name: name.to_string(),
+                    test_type: "singular".to_string(),
+                    refs: self.extract_refs(&content),
+                    column: None,
+                    file_path: normalize_path(entry.path()),
+                });
+            }
+        }
+
+        Ok(tests)
+    }
+
+    /// Extract every `{% snapshot name %}...{% endsnapshot %}` block from a file
+    fn parse_snapshot_blocks(&self, content: &str, path: &Path) -> Vec<DbtSnapshot> {
+        let block_regex = Regex::new(
+            r"(?s)\{%-?\s*snapshot\s+(\w+)\s*-?%\}(.*?)\{%-?\s*endsnapshot\s*-?%\}",
+        )
+        .unwrap();
+
+        block_regex
+            .captures_iter(content)
+            .filter_map(|cap| {
+                let name = cap.get(1)?.as_str().to_string();
+                let body = cap.get(2)?.as_str();
+
+                Some(DbtSnapshot {
+                    unique_id: format!("snapshot.{}", name),
+                    name,
+                    refs: self.extract_refs(body),
+                    sources: self.extract_sources(body),
+                    strategy: self.extract_config_value(body, "strategy"),
+                    unique_key: self.extract_config_value(body, "unique_key"),
+                    updated_at: self.extract_config_value(body, "updated_at"),
+                    target_schema: self.extract_config_value(body, "target_schema"),
+                    file_path: normalize_path(path),
+                    raw_sql: Some(body.trim().to_string()),
+                })
+            })
+            .collect()
+    }
+
+    fn extract_config_value(&self, sql: &str, key: &str) -> Option<String> {
+        let pattern = format!(
+            r#"\{{\{{\s*config\s*\([^)]*\b{}\s*=\s*['"]([^'"]+)['"][^)]*\)\s*\}}\}}"#,
+            regex::escape(key)
+        );
+
+        Regex::new(&pattern)
+            .ok()?
+            .captures(sql)
+            .and_then(|cap| cap.get(1).map(|m| m.as_str().to_string()))
+    }
+
     fn extract_refs(&self, sql: &str) -> Vec<String> {
-        let ref_regex = Regex::new(r#"\{\{\s*ref\s*\(\s*['"]([^'"]+)['"]\s*\)\s*\}\}"#).unwrap();
-        ref_regex
+        ref_regex(&self.ref_macros)
             .captures_iter(sql)
-            .filter_map(|cap| cap.get(1).map(|m| m.as_str().to_string()))
+            .filter_map(|cap| quoted_arg(&cap, 3))
             .collect()
     }
 
     fn extract_sources(&self, sql: &str) -> Vec<DbtSourceRef> {
-        let source_regex = Regex::new(
-            r#"\{\{\s*source\s*\(\s*['"]([^'"]+)['"]\s*,\s*['"]([^'"]+)['"]\s*\)\s*\}\}"#,
-        )
-        .unwrap();
-
-        source_regex
+        source_regex(&self.source_macros)
             .captures_iter(sql)
             .filter_map(|cap| {
                 Some(DbtSourceRef {
-                    source_name: cap.get(1)?.as_str().to_string(),
-                    table_name: cap.get(2)?.as_str().to_string(),
+                    source_name: quoted_arg(&cap, 1)?,
+                    table_name: quoted_arg(&cap, 3)?,
                 })
             })
             .collect()
     }
 
+    /// Extract metric names from `metric('name')` calls, e.g.
+    /// `{{ metric('revenue') }}` or `{{ metrics.calculate(metric('revenue'), ...) }}`
+    fn extract_metric_refs(&self, sql: &str) -> Vec<String> {
+        let metric_regex = Regex::new(r#"\bmetric\s*\(\s*['"]([^'"]+)['"]\s*\)"#).unwrap();
+        let mut metric_refs: Vec<String> = Vec::new();
+        for cap in metric_regex.captures_iter(sql) {
+            let name = cap[1].to_string();
+            if !metric_refs.contains(&name) {
+                metric_refs.push(name);
+            }
+        }
+        metric_refs
+    }
+
+    /// Extract `unique_key` from an inline `config()`, as a single column
+    /// (`unique_key='id'`) or a list (`unique_key=['id', 'day']`)
+    fn extract_unique_key(&self, sql: &str) -> Vec<String> {
+        if let Some(key) = self.extract_config_value(sql, "unique_key") {
+            return vec![key];
+        }
+
+        let list_regex =
+            Regex::new(r#"\{\{\s*config\s*\([^)]*\bunique_key\s*=\s*\[([^\]]*)\][^)]*\)\s*\}\}"#)
+                .unwrap();
+        let item_regex = Regex::new(r#"['"]([^'"]+)['"]"#).unwrap();
+
+        list_regex
+            .captures(sql)
+            .map(|cap| {
+                item_regex
+                    .captures_iter(&cap[1])
+                    .map(|item| item[1].to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     fn extract_materialization(&self, sql: &str) -> Option<String> {
         let config_regex = Regex::new(
             r#"\{\{\s*config\s*\([^)]*materialized\s*=\s*['"]([^'"]+)['"][^)]*\)\s*\}\}"#,
@@ -175,42 +583,82 @@ impl DbtProjectParser {
             .and_then(|cap| cap.get(1).map(|m| m.as_str().to_string()))
     }
 
-    fn parse_schema_files(&self, model_path: &Path) -> Result<HashMap<String, ModelMetadata>> {
+    /// Read model metadata from the `models:` sections of yml files under
+    /// `model_path`, along with the number of `unit_tests:` entries per model
+    fn parse_schema_files(
+        &self,
+        model_path: &Path,
+    ) -> Result<(HashMap<String, ModelMetadata>, HashMap<String, usize>)> {
         let mut metadata = HashMap::new();
+        let mut unit_test_counts: HashMap<String, usize> = HashMap::new();
 
-        for entry in WalkDir::new(model_path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| {
-                e.path()
-                    .file_name()
-                    .map_or(false, |n| {
-                        let name = n.to_string_lossy();
-                        (name.ends_with(".yml") || name.ends_with(".yaml"))
-                            && !name.starts_with("dbt_project")
-                    })
+        for entry in self.walk(model_path).filter(|e| {
+            e.path().file_name().map_or(false, |n| {
+                let name = n.to_string_lossy();
+                (name.ends_with(".yml") || name.ends_with(".yaml"))
+                    && !name.starts_with("dbt_project")
             })
-        {
-            if let Ok(content) = fs::read_to_string(entry.path()) {
-                if let Ok(yaml) = serde_yaml::from_str::<serde_yaml::Value>(&content) {
+        }) {
+            if let Some(content) = self.read_yaml_file(entry.path()) {
+                for yaml in parse_yaml_documents(&content).unwrap_or_default() {
                     // Parse models section
                     if let Some(models) = yaml["models"].as_sequence() {
                         for model in models {
                             if let Some(name) = model["name"].as_str() {
+                                let config = &model["config"];
                                 let meta = ModelMetadata {
+                                    schema: config["schema"]
+                                        .as_str()
+                                        .or_else(|| model["schema"].as_str())
+                                        .map(|s| s.to_string()),
+                                    database: config["database"]
+                                        .as_str()
+                                        .or_else(|| model["database"].as_str())
+                                        .map(|s| s.to_string()),
                                     description: model["description"].as_str().map(|s| s.to_string()),
                                     columns: self.parse_columns(&model["columns"]),
                                     tags: self.extract_string_array(&model, "tags").unwrap_or_default(),
+                                    access: config["access"]
+                                        .as_str()
+                                        .or_else(|| model["access"].as_str())
+                                        .map(|s| s.to_string()),
+                                    group: config["group"]
+                                        .as_str()
+                                        .or_else(|| model["group"].as_str())
+                                        .map(|s| s.to_string()),
+                                    materialization: config["materialized"]
+                                        .as_str()
+                                        .map(|s| s.to_string()),
+                                    alias: config["alias"].as_str().map(|s| s.to_string()),
+                                    unique_key: match config["unique_key"].as_str() {
+                                        Some(key) => vec![key.to_string()],
+                                        None => self
+                                            .extract_string_array(config, "unique_key")
+                                            .unwrap_or_default(),
+                                    },
+                                    source_location: locate_definition(&content, "models", name)
+                                        .map(|(line, column)| SourceLocation {
+                                            file: normalize_path(entry.path()),
+                                            line,
+                                            column,
+                                        }),
                                 };
                                 metadata.insert(name.to_string(), meta);
                             }
                         }
                     }
+
+                    // Parse unit_tests section
+                    for unit_test in yaml["unit_tests"].as_sequence().into_iter().flatten() {
+                        if let Some(model) = unit_test["model"].as_str() {
+                            *unit_test_counts.entry(model.to_string()).or_default() += 1;
+                        }
+                    }
                 }
             }
         }
 
-        Ok(metadata)
+        Ok((metadata, unit_test_counts))
     }
 
     fn parse_columns(&self, columns_yaml: &serde_yaml::Value) -> Vec<DbtColumn> {
@@ -258,20 +706,14 @@ impl DbtProjectParser {
             }
 
             // Find all schema.yml files
-            for entry in WalkDir::new(&full_path)
-                .into_iter()
-                .filter_map(|e| e.ok())
-                .filter(|e| {
-                    e.path()
-                        .file_name()
-                        .map_or(false, |n| {
-                            let name = n.to_string_lossy();
-                            name.ends_with(".yml") || name.ends_with(".yaml")
-                        })
+            for entry in self.walk(&full_path).filter(|e| {
+                e.path().file_name().map_or(false, |n| {
+                    let name = n.to_string_lossy();
+                    name.ends_with(".yml") || name.ends_with(".yaml")
                 })
-            {
-                if let Ok(content) = fs::read_to_string(entry.path()) {
-                    if let Ok(yaml) = serde_yaml::from_str::<serde_yaml::Value>(&content) {
+            }) {
+                if let Some(content) = self.read_yaml_file(entry.path()) {
+                    for yaml in parse_yaml_documents(&content).unwrap_or_default() {
                         if let Some(source_list) = yaml["sources"].as_sequence() {
                             for source in source_list {
                                 sources.extend(self.parse_source_definition(source));
@@ -292,6 +734,10 @@ impl DbtProjectParser {
             .to_string();
         let database = source_yaml["database"].as_str().map(|s| s.to_string());
         let schema = source_yaml["schema"].as_str().map(|s| s.to_string());
+        let freshness = self.parse_freshness(&source_yaml["freshness"]);
+        let loaded_at_field = source_yaml["loaded_at_field"]
+            .as_str()
+            .map(|s| s.to_string());
 
         source_yaml["tables"]
             .as_sequence()
@@ -314,23 +760,426 @@ impl DbtProjectParser {
                                 .as_str()
                                 .map(|s| s.to_string())
                                 .or_else(|| database.clone()),
+                            qualified_name: None,
                             description: table["description"].as_str().map(|s| s.to_string()),
                             columns: self.parse_columns(&table["columns"]),
                             loader: table["loader"].as_str().map(|s| s.to_string()),
-                            freshness: None, // TODO: Parse freshness config
+                            // A table-level `freshness: null` disables the source default
+                            freshness: match table.get("freshness") {
+                                Some(value) => self.parse_freshness(value),
+                                None => freshness.clone(),
+                            },
+                            loaded_at_field: table["loaded_at_field"]
+                                .as_str()
+                                .map(|s| s.to_string())
+                                .or_else(|| loaded_at_field.clone()),
                             tags: self.extract_string_array(table, "tags").unwrap_or_default(),
+                            project: None,
                         })
                     })
                     .collect()
             })
             .unwrap_or_default()
     }
+
+    fn parse_freshness(&self, freshness_yaml: &serde_yaml::Value) -> Option<DbtFreshness> {
+        let rule = |rule_yaml: &serde_yaml::Value| {
+            Some(DbtFreshnessRule {
+                count: rule_yaml["count"].as_i64()? as i32,
+                period: rule_yaml["period"].as_str()?.to_string(),
+            })
+        };
+
+        freshness_yaml.as_mapping()?;
+        Some(DbtFreshness {
+            warn_after: rule(&freshness_yaml["warn_after"]),
+            error_after: rule(&freshness_yaml["error_after"]),
+        })
+    }
+
+    /// Resolve the active target of the project's profile from a profiles.yml file
+    pub fn resolve_target(
+        &self,
+        project: &DbtProject,
+        profiles_path: impl AsRef<Path>,
+    ) -> Result<Option<ProfileTarget>> {
+        let Some(ref profile) = project.profile else {
+            return Ok(None);
+        };
+
+        let content = fs::read_to_string(profiles_path.as_ref())
+            .with_context(|| format!("Failed to read profiles.yml at {:?}", profiles_path.as_ref()))?;
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&content)
+            .with_context(|| "Failed to parse profiles.yml as YAML")?;
+
+        Ok(self.target_from_profiles(&yaml, profile))
+    }
+
+    fn target_from_profiles(&self, yaml: &serde_yaml::Value, profile: &str) -> Option<ProfileTarget> {
+        let profile_yaml = &yaml[profile];
+        let target_name = profile_yaml["target"].as_str().unwrap_or("dev");
+        let output = &profile_yaml["outputs"][target_name];
+        if output.is_null() {
+            return None;
+        }
+
+        // Adapters name these differently (Postgres dbname, BigQuery project/dataset)
+        let first_of = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|k| output[*k].as_str())
+                .map(|s| s.to_string())
+        };
+
+        Some(ProfileTarget {
+            name: target_name.to_string(),
+            database: first_of(&["database", "dbname", "project", "catalog"]),
+            schema: first_of(&["schema", "dataset"]),
+        })
+    }
+
+    /// Stamp fully-qualified relation names onto models and sources.
+    ///
+    /// Models follow dbt's default `generate_schema_name`, so a custom schema
+    /// is appended to the target schema. Sources default to a schema named
+    /// after the source. Explicit `database`/`schema` overrides win.
+    pub fn qualify_relations(
+        &self,
+        target: &ProfileTarget,
+        models: &mut [DbtModel],
+        sources: &mut [DbtSource],
+    ) {
+        for model in models.iter_mut() {
+            let database = model.database.clone().or_else(|| target.database.clone());
+            let schema = match (&target.schema, &model.schema) {
+                (Some(base), Some(custom)) => Some(format!("{}_{}", base, custom)),
+                (None, Some(custom)) => Some(custom.clone()),
+                (base, None) => base.clone(),
+            };
+            let relation = model.alias.as_deref().unwrap_or(&model.name);
+            model.qualified_name = qualify(database, schema, relation);
+        }
+
+        for source in sources.iter_mut() {
+            let database = source.database.clone().or_else(|| target.database.clone());
+            let schema = source.schema.clone().or_else(|| Some(source.source_name.clone()));
+            source.qualified_name = qualify(database, schema, &source.name);
+        }
+    }
+
+    /// Fill missing column data types from the warehouse types in the
+    /// project's `catalog.json`. Returns `false` when there is no catalog.
+    pub fn merge_catalog(
+        &self,
+        project: &DbtProject,
+        models: &mut [DbtModel],
+        sources: &mut [DbtSource],
+    ) -> Result<bool> {
+        let catalog_file =
+            target_dir(&self.project_path, project.target_path.as_deref()).join(CATALOG_FILE_NAME);
+        if !catalog_file.exists() {
+            return Ok(false);
+        }
+
+        let content = fs::read_to_string(&catalog_file)
+            .with_context(|| format!("Failed to read {:?}", catalog_file))?;
+        let catalog: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {:?} as JSON", catalog_file))?;
+        self.apply_catalog(&catalog, models, sources);
+
+        Ok(true)
+    }
+
+    /// Catalog unique ids carry the package name (`model.jaffle_shop.orders`)
+    /// while ours do not, so entries are matched on the trailing name parts
+    fn apply_catalog(
+        &self,
+        catalog: &serde_json::Value,
+        models: &mut [DbtModel],
+        sources: &mut [DbtSource],
+    ) {
+        let entries = |section: &str, name_parts: usize| {
+            let mut by_name: HashMap<String, &serde_json::Value> = HashMap::new();
+            for (unique_id, entry) in catalog[section].as_object().into_iter().flatten() {
+                let parts: Vec<&str> = unique_id.split('.').collect();
+                if parts.len() > name_parts {
+                    by_name.insert(parts[parts.len() - name_parts..].join("."), entry);
+                }
+            }
+            by_name
+        };
+
+        let catalog_models = entries("nodes", 1);
+        for model in models.iter_mut() {
+            if let Some(entry) = catalog_models.get(&model.name) {
+                fill_column_types(&mut model.columns, entry);
+            }
+        }
+
+        let catalog_sources = entries("sources", 2);
+        for source in sources.iter_mut() {
+            let key = format!("{}.{}", source.source_name, source.name);
+            if let Some(entry) = catalog_sources.get(&key) {
+                fill_column_types(&mut source.columns, entry);
+            }
+        }
+    }
+}
+
+/// Set `data_type` on columns that lack one; warehouses often upper-case
+/// column names, so names are compared case-insensitively
+fn fill_column_types(columns: &mut [DbtColumn], catalog_entry: &serde_json::Value) {
+    let Some(catalog_columns) = catalog_entry["columns"].as_object() else {
+        return;
+    };
+
+    for column in columns.iter_mut().filter(|c| c.data_type.is_none()) {
+        column.data_type = catalog_columns
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&column.name))
+            .and_then(|(_, c)| c["type"].as_str())
+            .map(|s| s.to_string());
+    }
+}
+
+/// The directory dbt writes artifacts such as `catalog.json` to, as dbt
+/// resolves it: `DBT_TARGET_PATH`, else the project's `target-path`, else
+/// `target`, relative to the project root. A `target-path` whose `env_var()`
+/// could not be interpolated counts as unset.
+pub fn target_dir(project_path: &Path, target_path: Option<&str>) -> PathBuf {
+    let target_path = std::env::var("DBT_TARGET_PATH")
+        .ok()
+        .filter(|p| !p.is_empty())
+        .or_else(|| {
+            target_path
+                .filter(|p| !p.contains("env_var"))
+                .map(|p| p.to_string())
+        })
+        .unwrap_or_else(|| "target".to_string());
+    project_path.join(target_path)
+}
+
+/// Warning for a file or directory left out of the parse because it could
+/// not be read, or, for model files, parsed
+pub(crate) fn skipped_file_warning(path: &Path, error: impl std::fmt::Display) -> String {
+    format!(
+        "Skipped {}, which could not be read or parsed: {}",
+        normalize_path(path),
+        error
+    )
+}
+
+/// Walk `root`, pushing a [`skipped_file_warning`] to `failures` for every
+/// entry that cannot be read instead of dropping it silently
+pub(crate) fn walk_recording_failures<'a>(
+    root: &Path,
+    failures: &'a RefCell<Vec<String>>,
+) -> impl Iterator<Item = walkdir::DirEntry> + 'a {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_map(move |entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                let path = e.path().map(Path::to_path_buf).unwrap_or_default();
+                failures.borrow_mut().push(skipped_file_warning(&path, e));
+                None
+            }
+        })
+}
+
+/// Read a SQL file, dropping a leading UTF-8 byte order mark so it does not
+/// end up in front of the first token
+pub(crate) fn read_sql(path: &Path) -> std::io::Result<String> {
+    let content = fs::read_to_string(path)?;
+    Ok(match content.strip_prefix('\u{feff}') {
+        Some(stripped) => stripped.to_string(),
+        None => content,
+    })
+}
+
+/// Names of the generic tests under a model's or column's `tests:` (or
+/// `data_tests:`) key, each with a suffix built from its arguments. Tests
+/// with arguments are single-key mappings such as
+/// `accepted_values: {values: [...]}`; like dbt, their argument values go
+/// into the test name (`__placed__shipped`) so that two tests of the same
+/// kind on one column get distinct ids.
+fn generic_test_names(yaml: &serde_yaml::Value) -> Vec<(String, String)> {
+    ["tests", "data_tests"]
+        .iter()
+        .filter_map(|key| yaml[*key].as_sequence())
+        .flatten()
+        .filter_map(|test| match test {
+            serde_yaml::Value::String(name) => Some((name.clone(), String::new())),
+            serde_yaml::Value::Mapping(map) => {
+                let (name, args) = map.iter().next()?;
+                let mut values = Vec::new();
+                collect_test_arg_values(args, &mut values);
+                let suffix: String = values.iter().map(|v| format!("__{}", v)).collect();
+                Some((name.as_str()?.to_string(), suffix))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Flatten a generic test's arguments into identifier-safe scalar values, in
+/// declaration order. `config` only changes how the test runs, so it is
+/// left out.
+fn collect_test_arg_values(value: &serde_yaml::Value, values: &mut Vec<String>) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            for (key, value) in map {
+                if key.as_str() != Some("config") {
+                    collect_test_arg_values(value, values);
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for item in items {
+                collect_test_arg_values(item, values);
+            }
+        }
+        serde_yaml::Value::String(s) => values.push(
+            s.chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect(),
+        ),
+        serde_yaml::Value::Number(n) => values.push(n.to_string().replace(['.', '-'], "_")),
+        serde_yaml::Value::Bool(b) => values.push(b.to_string()),
+        _ => {}
+    }
+}
+
+/// Regex alternation matching any of the given macro names literally
+fn macro_alternatives(macros: &[String]) -> String {
+    macros
+        .iter()
+        .map(|m| regex::escape(m))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// A single- or double-quoted Jinja string argument, captured in one of two
+/// groups depending on the quote used
+const QUOTED_ARG: &str = r#"(?:'([^']+)'|"([^"]+)")"#;
+
+/// Read the [`QUOTED_ARG`] starting at capture group `group` as a relation
+/// name, dropping identifier quoting such as `"My Model"` or `[My Model]`
+fn quoted_arg(cap: &regex::Captures, group: usize) -> Option<String> {
+    let raw = cap
+        .get(group)
+        .or_else(|| cap.get(group + 1))?
+        .as_str()
+        .trim();
+    let unquoted = [('"', '"'), ('`', '`'), ('[', ']')]
+        .iter()
+        .find_map(|&(open, close)| raw.strip_prefix(open)?.strip_suffix(close))
+        .unwrap_or(raw);
+    Some(unquoted.trim().to_string())
+}
+
+/// Matches `{{ ref('name') }}` or `{{ ref('package', 'name') }}` through
+/// any of `ref_macros`; the name is the [`QUOTED_ARG`] at group 3
+fn ref_regex(ref_macros: &[String]) -> Regex {
+    Regex::new(&format!(
+        r#"\{{\{{\s*(?:{})\s*\(\s*(?:{}\s*,\s*)?{}\s*\)\s*\}}\}}"#,
+        macro_alternatives(ref_macros),
+        QUOTED_ARG,
+        QUOTED_ARG
+    ))
+    .unwrap()
+}
+
+/// Matches `{{ source('source_name', 'table_name') }}` through any
+/// of `source_macros`, with the names at groups 1 and 3
+fn source_regex(source_macros: &[String]) -> Regex {
+    Regex::new(&format!(
+        r#"\{{\{{\s*(?:{})\s*\(\s*{}\s*,\s*{}\s*\)\s*\}}\}}"#,
+        macro_alternatives(source_macros),
+        QUOTED_ARG,
+        QUOTED_ARG
+    ))
+    .unwrap()
+}
+
+/// Replace `{{ ref(...) }}` and `{{ source(...) }}` calls (or calls to any
+/// of `ref_macros` and `source_macros`) in model SQL with the relation they
+/// point at: the profile-qualified name when known, else `schema.name` or
+/// the bare name. Calls to unknown nodes are kept inside a
+/// `/* unresolved ... */` comment.
+pub fn resolve_relations(
+    sql: &str,
+    ref_macros: &[String],
+    source_macros: &[String],
+    models: &[DbtModel],
+    sources: &[DbtSource],
+    seeds: &[DbtSeed],
+) -> String {
+    let sql = ref_regex(ref_macros).replace_all(sql, |cap: &regex::Captures| {
+        let Some(name) = quoted_arg(cap, 3) else {
+            return cap[0].to_string();
+        };
+        let name = name.as_str();
+        if let Some(model) = models.iter().find(|m| m.name == name) {
+            let relation = model.alias.as_deref().unwrap_or(name);
+            model
+                .qualified_name
+                .clone()
+                .or_else(|| qualify(model.database.clone(), model.schema.clone(), relation))
+                .unwrap_or_else(|| relation.to_string())
+        } else if seeds.iter().any(|s| s.name == name) {
+            name.to_string()
+        } else {
+            format!("/* unresolved ref('{}') */", name)
+        }
+    });
+
+    source_regex(source_macros)
+        .replace_all(&sql, |cap: &regex::Captures| {
+            let (Some(source_name), Some(table_name)) = (quoted_arg(cap, 1), quoted_arg(cap, 3))
+            else {
+                return cap[0].to_string();
+            };
+            let (source_name, table_name) = (source_name.as_str(), table_name.as_str());
+            match sources
+                .iter()
+                .find(|s| s.source_name == source_name && s.name == table_name)
+            {
+                Some(source) => source.qualified_name.clone().unwrap_or_else(|| {
+                    let schema = source
+                        .schema
+                        .clone()
+                        .unwrap_or_else(|| source_name.to_string());
+                    format!("{}.{}", schema, table_name)
+                }),
+                None => format!(
+                    "/* unresolved source('{}', '{}') */",
+                    source_name, table_name
+                ),
+            }
+        })
+        .into_owned()
+}
+
+fn qualify(database: Option<String>, schema: Option<String>, name: &str) -> Option<String> {
+    let parts: Vec<String> = [database, schema, Some(name.to_string())]
+        .into_iter()
+        .flatten()
+        .collect();
+    (parts.len() > 1).then(|| parts.join("."))
 }
 
 struct ModelMetadata {
+    schema: Option<String>,
+    database: Option<String>,
     description: Option<String>,
     columns: Vec<DbtColumn>,
     tags: Vec<String>,
+    access: Option<String>,
+    group: Option<String>,
+    materialization: Option<String>,
+    alias: Option<String>,
+    unique_key: Vec<String>,
+    source_location: Option<SourceLocation>,
 }
 
 #[cfg(test)]
@@ -348,6 +1197,146 @@ mod tests {
         assert_eq!(refs, vec!["stg_orders", "stg_customers"]);
     }
 
+    #[test]
+    fn test_extract_quoted_refs_and_sources() {
+        let parser = DbtProjectParser::new("/tmp");
+        let sql = r#"
+            SELECT * FROM {{ ref('"My Model"') }}
+            JOIN {{ ref(" [Customer Dim] ") }} ON ...
+            JOIN {{ source('"raw"', 'orders') }} ON ...
+        "#;
+
+        assert_eq!(parser.extract_refs(sql), vec!["My Model", "Customer Dim"]);
+        let sources = parser.extract_sources(sql);
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].source_name, "raw");
+        assert_eq!(sources[0].table_name, "orders");
+    }
+
+    #[test]
+    fn test_extract_unique_key() {
+        let parser = DbtProjectParser::new("/tmp");
+        let single = "{{ config(materialized='incremental', unique_key='order_id') }}";
+        let list = r#"{{ config(materialized="incremental", unique_key=["order_id", "day"]) }}"#;
+
+        assert_eq!(parser.extract_unique_key(single), vec!["order_id"]);
+        assert_eq!(parser.extract_unique_key(list), vec!["order_id", "day"]);
+        assert!(parser
+            .extract_unique_key("{{ config(materialized='incremental') }}")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_schema_columns_are_deduped() {
+        let project_dir =
+            std::env::temp_dir().join(format!("semantic-tracer-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(project_dir.join("models")).unwrap();
+        std::fs::write(project_dir.join("models/stg_orders.sql"), "select 1 as id").unwrap();
+        std::fs::write(
+            project_dir.join("models/schema.yml"),
+            r#"
+models:
+  - name: stg_orders
+    columns:
+      - name: id
+        description: Order id
+      - name: status
+      - name: id
+"#,
+        )
+        .unwrap();
+        let parser = DbtProjectParser::new(&project_dir);
+        let project = DbtProject {
+            name: "jaffle_shop".to_string(),
+            version: None,
+            config_version: None,
+            profile: None,
+            model_paths: vec!["models".to_string()],
+            seed_paths: Vec::new(),
+            test_paths: Vec::new(),
+            analysis_paths: Vec::new(),
+            macro_paths: Vec::new(),
+            snapshot_paths: Vec::new(),
+            target_path: None,
+        };
+
+        let models = parser.parse_models(&project).unwrap();
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        let columns: Vec<&str> = models[0].columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(columns, vec!["id", "status"]);
+        assert_eq!(
+            models[0].columns[0].description.as_deref(),
+            Some("Order id")
+        );
+        assert_eq!(models[0].duplicate_columns, vec!["id"]);
+    }
+
+    #[test]
+    fn test_models_with_bom_and_jinja_extension() {
+        let project_dir =
+            std::env::temp_dir().join(format!("semantic-tracer-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(project_dir.join("models")).unwrap();
+        fs::write(
+            project_dir.join("models/orders.sql"),
+            "\u{feff}{{ config(materialized='table', alias='fct_orders') }}\nselect * from {{ ref('stg_orders') }}",
+        )
+        .unwrap();
+        fs::write(
+            project_dir.join("models/customers.sql.jinja"),
+            "select * from {{ ref('stg_customers') }}",
+        )
+        .unwrap();
+        fs::write(project_dir.join("models/notes.md"), "not a model").unwrap();
+
+        let parser = DbtProjectParser::new(&project_dir);
+        let project = DbtProject {
+            name: "jaffle_shop".to_string(),
+            version: None,
+            config_version: None,
+            profile: None,
+            model_paths: vec!["models".to_string()],
+            seed_paths: Vec::new(),
+            test_paths: Vec::new(),
+            analysis_paths: Vec::new(),
+            macro_paths: Vec::new(),
+            snapshot_paths: Vec::new(),
+            target_path: None,
+        };
+        let models = parser.parse_models(&project);
+        fs::remove_dir_all(&project_dir).unwrap();
+        let mut models = models.unwrap();
+        models.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].name, "customers");
+        assert_eq!(models[0].refs, vec!["stg_customers"]);
+        assert_eq!(models[1].name, "orders");
+        assert_eq!(models[1].refs, vec!["stg_orders"]);
+        assert_eq!(models[1].materialization.as_deref(), Some("table"));
+        assert_eq!(models[1].alias.as_deref(), Some("fct_orders"));
+    }
+
+    #[test]
+    fn test_extract_refs_with_custom_macros() {
+        let parser = DbtProjectParser::new("/tmp")
+            .with_ref_macros(vec!["dbt_ref".to_string()])
+            .with_source_macros(vec!["raw_source".to_string()]);
+        let sql = r#"
+            SELECT * FROM {{ dbt_ref('stg_orders') }}
+            JOIN {{ ref('stg_customers') }} ON ...
+            JOIN {{ raw_source('raw', 'payments') }} ON ...
+        "#;
+
+        assert_eq!(
+            parser.extract_refs(sql),
+            vec!["stg_orders", "stg_customers"]
+        );
+        let sources = parser.extract_sources(sql);
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].table_name, "payments");
+    }
+
     #[test]
     fn test_extract_sources() {
         let parser = DbtProjectParser::new("/tmp");
@@ -360,4 +1349,260 @@ mod tests {
         assert_eq!(sources[0].source_name, "raw");
         assert_eq!(sources[0].table_name, "orders");
     }
+
+    #[test]
+    fn test_extract_metric_refs() {
+        let parser = DbtProjectParser::new("/tmp");
+        let sql = r#"
+            SELECT * FROM {{ metric('revenue') }}
+            UNION ALL
+            SELECT * FROM {{ metrics.calculate(metric("order_count"), grain='day') }}
+        "#;
+        let metric_refs = parser.extract_metric_refs(sql);
+        assert_eq!(metric_refs, vec!["revenue", "order_count"]);
+    }
+
+    #[test]
+    fn test_parse_snapshot_block() {
+        let parser = DbtProjectParser::new("/tmp");
+        let sql = r#"
+            {% snapshot orders_snapshot %}
+            {{
+                config(
+                  target_schema='snapshots',
+                  unique_key='id',
+                  strategy='timestamp',
+                  updated_at='updated_at'
+                )
+            }}
+            select * from {{ source('raw', 'orders') }}
+            {% endsnapshot %}
+        "#;
+
+        let snapshots = parser.parse_snapshot_blocks(sql, Path::new("snapshots/orders.sql"));
+
+        assert_eq!(snapshots.len(), 1);
+        let snapshot = &snapshots[0];
+        assert_eq!(snapshot.name, "orders_snapshot");
+        assert_eq!(snapshot.strategy.as_deref(), Some("timestamp"));
+        assert_eq!(snapshot.unique_key.as_deref(), Some("id"));
+        assert_eq!(snapshot.sources.len(), 1);
+        assert_eq!(snapshot.sources[0].source_name, "raw");
+        assert_eq!(snapshot.sources[0].table_name, "orders");
+    }
+
+    #[test]
+    fn test_project_paths_interpolate_env_vars() {
+        let project_dir =
+            std::env::temp_dir().join(format!("semantic-tracer-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&project_dir).unwrap();
+        std::fs::write(
+            project_dir.join("dbt_project.yml"),
+            r#"
+name: jaffle_shop
+model-paths: ["{{ env_var('SEMANTIC_TRACER_TEST_MODEL_PATH') }}"]
+seed-paths: ["{{ env_var('SEMANTIC_TRACER_TEST_UNSET', 'data') }}"]
+target-path: "{{ env_var('SEMANTIC_TRACER_TEST_UNSET') }}"
+"#,
+        )
+        .unwrap();
+        std::env::set_var("SEMANTIC_TRACER_TEST_MODEL_PATH", "src/models");
+
+        let parser = DbtProjectParser::new(&project_dir);
+        let project = parser.parse_project().unwrap();
+
+        assert_eq!(parser.take_unset_env_vars(), vec!["SEMANTIC_TRACER_TEST_UNSET"]);
+        assert_eq!(project.model_paths, vec!["src/models"]);
+        assert_eq!(project.seed_paths, vec!["data"]);
+        assert_eq!(
+            project.target_path.as_deref(),
+            Some("{{ env_var('SEMANTIC_TRACER_TEST_UNSET') }}")
+        );
+    }
+
+    #[test]
+    fn test_resolve_relations_qualifies_refs() {
+        let stg_orders = DbtModel {
+            unique_id: "model.stg_orders".to_string(),
+            name: "stg_orders".to_string(),
+            schema: Some("staging".to_string()),
+            database: None,
+            qualified_name: None,
+            alias: None,
+            description: None,
+            columns: Vec::new(),
+            depends_on: Vec::new(),
+            refs: Vec::new(),
+            sources: Vec::new(),
+            metric_refs: Vec::new(),
+            file_path: "models/stg_orders.sql".to_string(),
+            raw_sql: None,
+            materialization: None,
+            tags: Vec::new(),
+            access: None,
+            group: None,
+            unit_test_count: 0,
+            unique_key: Vec::new(),
+            duplicate_columns: Vec::new(),
+            source_location: None,
+            project: None,
+        };
+        let mut customers = stg_orders.clone();
+        customers.name = "customers".to_string();
+        customers.qualified_name = Some("analytics.prod.customers".to_string());
+        let sql = "select * from {{ ref('stg_orders') }}\n\
+                   join {{ dbt_ref('jaffle_shop', 'customers') }} using (customer_id)\n\
+                   join {{ ref(\"payments\") }} using (order_id)";
+
+        let resolved = resolve_relations(
+            sql,
+            &["ref".to_string(), "dbt_ref".to_string()],
+            &["source".to_string()],
+            &[stg_orders, customers],
+            &[],
+            &[],
+        );
+
+        assert_eq!(
+            resolved,
+            "select * from staging.stg_orders\n\
+             join analytics.prod.customers using (customer_id)\n\
+             join /* unresolved ref('payments') */ using (order_id)"
+        );
+    }
+
+    #[test]
+    fn test_catalog_fills_missing_data_types() {
+        let parser = DbtProjectParser::new("/tmp");
+        let column = |name: &str, data_type: Option<&str>| DbtColumn {
+            name: name.to_string(),
+            description: None,
+            data_type: data_type.map(|s| s.to_string()),
+            meta: HashMap::new(),
+            tests: Vec::new(),
+        };
+        let orders = DbtModel {
+            unique_id: "model.orders".to_string(),
+            name: "orders".to_string(),
+            schema: None,
+            database: None,
+            qualified_name: None,
+            alias: None,
+            description: None,
+            columns: vec![column("id", None), column("amount", Some("numeric(10, 2)"))],
+            depends_on: Vec::new(),
+            refs: Vec::new(),
+            sources: Vec::new(),
+            metric_refs: Vec::new(),
+            file_path: "models/orders.sql".to_string(),
+            raw_sql: None,
+            materialization: None,
+            tags: Vec::new(),
+            access: None,
+            group: None,
+            unit_test_count: 0,
+            unique_key: Vec::new(),
+            duplicate_columns: Vec::new(),
+            source_location: None,
+            project: None,
+        };
+        let catalog = serde_json::json!({
+            "nodes": {
+                "model.jaffle_shop.orders": {
+                    "columns": {
+                        "ID": { "type": "INTEGER", "index": 1, "name": "ID" },
+                        "AMOUNT": { "type": "NUMBER", "index": 2, "name": "AMOUNT" }
+                    }
+                }
+            },
+            "sources": {}
+        });
+
+        let mut models = vec![orders];
+        parser.apply_catalog(&catalog, &mut models, &mut []);
+
+        assert_eq!(models[0].columns[0].data_type.as_deref(), Some("INTEGER"));
+        assert_eq!(
+            models[0].columns[1].data_type.as_deref(),
+            Some("numeric(10, 2)")
+        );
+    }
+
+    #[test]
+    fn test_qualify_source_with_explicit_schema() {
+        let parser = DbtProjectParser::new("/tmp");
+        let profiles: serde_yaml::Value = serde_yaml::from_str(
+            r#"
+            jaffle_shop:
+              target: prod
+              outputs:
+                prod:
+                  type: snowflake
+                  database: ANALYTICS
+                  schema: dbt_prod
+            "#,
+        )
+        .unwrap();
+        let target = parser.target_from_profiles(&profiles, "jaffle_shop").unwrap();
+
+        let source_yaml: serde_yaml::Value = serde_yaml::from_str(
+            r#"
+            name: stripe
+            schema: stripe_raw
+            tables:
+              - name: payments
+            "#,
+        )
+        .unwrap();
+        let mut sources = parser.parse_source_definition(&source_yaml);
+        parser.qualify_relations(&target, &mut [], &mut sources);
+
+        assert_eq!(target.name, "prod");
+        assert_eq!(
+            sources[0].qualified_name.as_deref(),
+            Some("ANALYTICS.stripe_raw.payments")
+        );
+    }
+
+    #[test]
+    fn test_generic_test_names_include_arguments() {
+        let column: serde_yaml::Value = serde_yaml::from_str(
+            r#"
+            name: status
+            tests:
+              - not_null
+              - accepted_values:
+                  values: ['placed', 'shipped']
+              - accepted_values:
+                  values: ['returned']
+                  config:
+                    severity: warn
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            generic_test_names(&column),
+            vec![
+                ("not_null".to_string(), String::new()),
+                (
+                    "accepted_values".to_string(),
+                    "__placed__shipped".to_string()
+                ),
+                ("accepted_values".to_string(), "__returned".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_target_dir_follows_target_path() {
+        let project = Path::new("/work/jaffle_shop");
+
+        assert_eq!(target_dir(project, None), project.join("target"));
+        assert_eq!(target_dir(project, Some("build")), project.join("build"));
+        assert_eq!(
+            target_dir(project, Some("{{ env_var('DBT_UNSET_TARGET') }}")),
+            project.join("target")
+        );
+    }
 }
@@ -1,49 +1,79 @@
 //! Parser for dbt Semantic Layer (MetricFlow) configurations
 
+use crate::parsers::dbt_project::{skipped_file_warning, walk_recording_failures};
+use crate::parsers::metric_filter::parse_filter_references;
+use crate::parsers::yaml::{locate_definition, parse_yaml_documents};
+use crate::paths::normalize_path;
 use crate::types::{
-    Dimension, DimensionTypeParams, Measure, MeasureRef, Metric, MetricRef, MetricTypeParams,
-    NonAdditiveDimension, SemanticEntity, SemanticModel, SemanticModelDefaults,
+    ConstantProperty, ConversionTypeParams, Dimension, DimensionTypeParams, Measure, MeasureRef,
+    Metric, MetricConfig, MetricRef, MetricTypeParams, NonAdditiveDimension, SemanticEntity,
+    SemanticModel, SemanticModelDefaults, SourceLocation,
 };
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+
+/// Metric types understood by MetricFlow
+const KNOWN_METRIC_TYPES: &[&str] = &["simple", "derived", "cumulative", "conversion", "ratio"];
 
 pub struct DbtSemanticLayerParser {
     project_path: PathBuf,
+    strict: bool,
+    read_failures: RefCell<Vec<String>>, // files skipped because they could not be read
 }
 
 impl DbtSemanticLayerParser {
     pub fn new(project_path: impl AsRef<Path>) -> Self {
         Self {
             project_path: project_path.as_ref().to_path_buf(),
+            strict: false,
+            read_failures: RefCell::new(Vec::new()),
         }
     }
 
+    /// Take the messages for semantic layer files and directories skipped so
+    /// far because they could not be read
+    pub fn take_read_failures(&self) -> Vec<String> {
+        self.read_failures.take()
+    }
+
+    /// In strict mode every rejected definition is reported instead of being
+    /// silently skipped, and unknown metric types are rejected
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
     /// Parse all semantic models and metrics from the project
-    pub fn parse(&self) -> Result<(Vec<SemanticModel>, Vec<Metric>)> {
+    ///
+    /// The third element holds one message per rejected definition; it is
+    /// only populated in strict mode.
+    pub fn parse(&self) -> Result<(Vec<SemanticModel>, Vec<Metric>, Vec<String>)> {
         let mut semantic_models = Vec::new();
         let mut metrics = Vec::new();
+        let mut errors = Vec::new();
 
         // Look for semantic layer files in models directory
         let models_path = self.project_path.join("models");
         if models_path.exists() {
-            self.scan_directory(&models_path, &mut semantic_models, &mut metrics)?;
+            self.scan_directory(&models_path, &mut semantic_models, &mut metrics, &mut errors)?;
         }
 
         // Also check for dedicated semantic_models directory
         let semantic_path = self.project_path.join("semantic_models");
         if semantic_path.exists() {
-            self.scan_directory(&semantic_path, &mut semantic_models, &mut metrics)?;
+            self.scan_directory(&semantic_path, &mut semantic_models, &mut metrics, &mut errors)?;
         }
 
         // Check for metrics directory
         let metrics_path = self.project_path.join("metrics");
         if metrics_path.exists() {
-            self.scan_directory(&metrics_path, &mut semantic_models, &mut metrics)?;
+            self.scan_directory(&metrics_path, &mut semantic_models, &mut metrics, &mut errors)?;
         }
 
-        Ok((semantic_models, metrics))
+        Ok((semantic_models, metrics, errors))
     }
 
     fn scan_directory(
@@ -51,51 +81,102 @@ impl DbtSemanticLayerParser {
         path: &Path,
         semantic_models: &mut Vec<SemanticModel>,
         metrics: &mut Vec<Metric>,
+        errors: &mut Vec<String>,
     ) -> Result<()> {
-        for entry in WalkDir::new(path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| {
+        for entry in walk_recording_failures(path, &self.read_failures).filter(|e| {
                 e.path()
                     .extension()
                     .map_or(false, |ext| ext == "yml" || ext == "yaml")
             })
         {
-            let content = fs::read_to_string(entry.path())
-                .with_context(|| format!("Failed to read {:?}", entry.path()))?;
-
-            if let Ok(yaml) = serde_yaml::from_str::<serde_yaml::Value>(&content) {
-                // Parse semantic_models section
-                if let Some(models) = yaml["semantic_models"].as_sequence() {
-                    for model in models {
-                        if let Ok(sm) = self.parse_semantic_model(model) {
-                            semantic_models.push(sm);
-                        }
+            let content = match fs::read_to_string(entry.path()) {
+                Ok(content) => content,
+                Err(e) => {
+                    self.read_failures
+                        .borrow_mut()
+                        .push(skipped_file_warning(entry.path(), e));
+                    continue;
+                }
+            };
+
+            match parse_yaml_documents(&content) {
+                Ok(documents) => {
+                    for yaml in &documents {
+                        self.collect_definitions(
+                            yaml,
+                            &content,
+                            entry.path(),
+                            semantic_models,
+                            metrics,
+                            errors,
+                        );
                     }
                 }
+                Err(e) if self.strict => {
+                    errors.push(format!("{}: invalid YAML: {}", entry.path().display(), e));
+                }
+                Err(_) => {}
+            }
+        }
 
-                // Parse metrics section
-                if let Some(metric_list) = yaml["metrics"].as_sequence() {
-                    for metric in metric_list {
-                        if let Ok(m) = self.parse_metric(metric) {
-                            metrics.push(m);
-                        }
+        Ok(())
+    }
+
+    fn collect_definitions(
+        &self,
+        yaml: &serde_yaml::Value,
+        content: &str,
+        path: &Path,
+        semantic_models: &mut Vec<SemanticModel>,
+        metrics: &mut Vec<Metric>,
+        errors: &mut Vec<String>,
+    ) {
+        // Parse semantic_models section
+        if let Some(models) = yaml["semantic_models"].as_sequence() {
+            for model in models {
+                let location = locate(content, path, "semantic_models", model);
+                match self.parse_semantic_model(model) {
+                    Ok(mut sm) => {
+                        sm.source_location = location;
+                        semantic_models.push(sm);
                     }
+                    Err(e) if self.strict => errors.push(describe_rejection(path, location, e)),
+                    Err(_) => {}
                 }
             }
         }
 
-        Ok(())
+        // Parse metrics section
+        if let Some(metric_list) = yaml["metrics"].as_sequence() {
+            for metric in metric_list {
+                let location = locate(content, path, "metrics", metric);
+                match self.parse_metric(metric) {
+                    Ok(mut m) => {
+                        m.source_location = location;
+                        metrics.push(m);
+                    }
+                    Err(e) if self.strict => errors.push(describe_rejection(path, location, e)),
+                    Err(_) => {}
+                }
+            }
+        }
     }
 
-    fn parse_semantic_model(&self, yaml: &serde_yaml::Value) -> Result<SemanticModel> {
+    pub(crate) fn parse_semantic_model(&self, yaml: &serde_yaml::Value) -> Result<SemanticModel> {
         let name = yaml["name"]
             .as_str()
             .context("Semantic model missing name")?
             .to_string();
 
+        // Compiled manifests point at the table through `node_relation`
+        // instead of a `ref()`. Its alias is the table name, which differs
+        // from the model name when the model configures an alias, so the
+        // manifest parser swaps in the model the relation resolves to and the
+        // alias only stands in until then
+        let node_relation = &yaml["node_relation"];
         let model = yaml["model"]
             .as_str()
+            .or_else(|| node_relation["alias"].as_str())
             .map(|s| {
                 // Strip ref() if present
                 if s.starts_with("ref(") && s.ends_with(")") {
@@ -108,19 +189,54 @@ impl DbtSemanticLayerParser {
                     s.to_string()
                 }
             })
-            .context("Semantic model missing model reference")?;
+            .with_context(|| format!("Semantic model '{}' missing model reference", name))?;
 
         Ok(SemanticModel {
             name,
             description: yaml["description"].as_str().map(|s| s.to_string()),
             model,
+            node_relation: self.parse_node_relation(node_relation),
+            group: self.parse_group(yaml),
+            primary_entity: yaml["primary_entity"].as_str().map(|s| s.to_string()),
             defaults: self.parse_defaults(&yaml["defaults"]),
             entities: self.parse_entities(&yaml["entities"]),
             measures: self.parse_measures(&yaml["measures"]),
             dimensions: self.parse_dimensions(&yaml["dimensions"]),
+            source_location: None,
+            meta: self.parse_meta(yaml),
+            project: None,
         })
     }
 
+    /// Format a `node_relation` block as `database.schema.alias`, preferring
+    /// its `relation_name` when present
+    fn parse_node_relation(&self, yaml: &serde_yaml::Value) -> Option<String> {
+        if let Some(relation_name) = yaml["relation_name"].as_str() {
+            return Some(relation_name.to_string());
+        }
+
+        let alias = yaml["alias"].as_str()?;
+        let parts: Vec<&str> = [
+            yaml["database"].as_str(),
+            yaml["schema_name"]
+                .as_str()
+                .or_else(|| yaml["schema"].as_str()),
+            Some(alias),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        Some(parts.join("."))
+    }
+
+    /// Read the owning group from `config.group`, falling back to a top-level `group`
+    fn parse_group(&self, yaml: &serde_yaml::Value) -> Option<String> {
+        yaml["config"]["group"]
+            .as_str()
+            .or_else(|| yaml["group"].as_str())
+            .map(|s| s.to_string())
+    }
+
     fn parse_defaults(&self, yaml: &serde_yaml::Value) -> Option<SemanticModelDefaults> {
         if yaml.is_null() {
             return None;
@@ -160,8 +276,14 @@ impl DbtSemanticLayerParser {
                             agg: m["agg"].as_str().unwrap_or("sum").to_string(),
                             expr: m["expr"].as_str().map(|s| s.to_string()),
                             description: m["description"].as_str().map(|s| s.to_string()),
+                            label: m["label"].as_str().map(|s| s.to_string()),
                             create_metric: m["create_metric"].as_bool(),
                             non_additive_dimension: self.parse_non_additive(&m["non_additive_dimension"]),
+                            agg_time_dimension: m["agg_time_dimension"]
+                                .as_str()
+                                .map(|s| s.to_string()),
+                            fill_nulls_with: m["fill_nulls_with"].as_f64(),
+                            join_to_timespine: m["join_to_timespine"].as_bool(),
                         })
                     })
                     .collect()
@@ -190,6 +312,7 @@ impl DbtSemanticLayerParser {
                             dimension_type: d["type"].as_str().unwrap_or("categorical").to_string(),
                             expr: d["expr"].as_str().map(|s| s.to_string()),
                             description: d["description"].as_str().map(|s| s.to_string()),
+                            label: d["label"].as_str().map(|s| s.to_string()),
                             type_params: self.parse_dimension_type_params(&d["type_params"]),
                         })
                     })
@@ -213,21 +336,63 @@ impl DbtSemanticLayerParser {
         })
     }
 
-    fn parse_metric(&self, yaml: &serde_yaml::Value) -> Result<Metric> {
+    pub(crate) fn parse_metric(&self, yaml: &serde_yaml::Value) -> Result<Metric> {
         let name = yaml["name"]
             .as_str()
             .context("Metric missing name")?
             .to_string();
 
         let metric_type = yaml["type"].as_str().unwrap_or("simple").to_string();
+        if self.strict && !KNOWN_METRIC_TYPES.contains(&metric_type.as_str()) {
+            bail!("Metric '{}' has unknown type '{}'", name, metric_type);
+        }
+        let filter = yaml["filter"].as_str().map(|s| s.to_string());
+        let filter_references = filter
+            .as_deref()
+            .map(parse_filter_references)
+            .unwrap_or_default();
 
         Ok(Metric {
             name,
             description: yaml["description"].as_str().map(|s| s.to_string()),
             metric_type: metric_type.clone(),
+            group: self.parse_group(yaml),
             type_params: self.parse_metric_type_params(&yaml["type_params"], &metric_type),
-            filter: yaml["filter"].as_str().map(|s| s.to_string()),
+            filter,
+            filter_references,
             label: yaml["label"].as_str().map(|s| s.to_string()),
+            meta: self.parse_meta(yaml),
+            config: self.parse_metric_config(&yaml["config"]),
+            source_location: None,
+            project: None,
+        })
+    }
+
+    /// Merge top-level `meta` with `config.meta`, the latter taking precedence
+    fn parse_meta(&self, yaml: &serde_yaml::Value) -> HashMap<String, serde_json::Value> {
+        let mut meta = HashMap::new();
+        for meta_yaml in [&yaml["meta"], &yaml["config"]["meta"]] {
+            if let Some(obj) = meta_yaml.as_mapping() {
+                for (key, value) in obj {
+                    if let Some(key_str) = key.as_str() {
+                        if let Ok(json_value) = serde_json::to_value(value) {
+                            meta.insert(key_str.to_string(), json_value);
+                        }
+                    }
+                }
+            }
+        }
+        meta
+    }
+
+    fn parse_metric_config(&self, yaml: &serde_yaml::Value) -> Option<MetricConfig> {
+        if yaml.is_null() {
+            return None;
+        }
+
+        Some(MetricConfig {
+            enabled: yaml["enabled"].as_bool(),
+            group: yaml["group"].as_str().map(|s| s.to_string()),
         })
     }
 
@@ -239,6 +404,7 @@ impl DbtSemanticLayerParser {
                 metrics: None,
                 window: yaml["window"].as_str().map(|s| s.to_string()),
                 grain_to_date: yaml["grain_to_date"].as_str().map(|s| s.to_string()),
+                conversion_type_params: None,
             },
             "derived" => MetricTypeParams {
                 measure: None,
@@ -246,6 +412,16 @@ impl DbtSemanticLayerParser {
                 metrics: self.parse_metric_refs(&yaml["metrics"]),
                 window: None,
                 grain_to_date: None,
+                conversion_type_params: None,
+            },
+            "conversion" => MetricTypeParams {
+                measure: None,
+                expr: None,
+                metrics: None,
+                window: None,
+                grain_to_date: None,
+                conversion_type_params: self
+                    .parse_conversion_type_params(&yaml["conversion_type_params"]),
             },
             _ => MetricTypeParams {
                 measure: self.parse_measure_ref(&yaml["measure"]),
@@ -253,10 +429,42 @@ impl DbtSemanticLayerParser {
                 metrics: self.parse_metric_refs(&yaml["metrics"]),
                 window: yaml["window"].as_str().map(|s| s.to_string()),
                 grain_to_date: yaml["grain_to_date"].as_str().map(|s| s.to_string()),
+                conversion_type_params: None,
             },
         }
     }
 
+    fn parse_conversion_type_params(
+        &self,
+        yaml: &serde_yaml::Value,
+    ) -> Option<ConversionTypeParams> {
+        if yaml.is_null() {
+            return None;
+        }
+
+        Some(ConversionTypeParams {
+            base_measure: self.parse_measure_ref(&yaml["base_measure"]),
+            conversion_measure: self.parse_measure_ref(&yaml["conversion_measure"]),
+            entity: yaml["entity"].as_str().map(|s| s.to_string()),
+            window: yaml["window"].as_str().map(|s| s.to_string()),
+            calculation: yaml["calculation"].as_str().map(|s| s.to_string()),
+            constant_properties: yaml["constant_properties"]
+                .as_sequence()
+                .map(|props| {
+                    props
+                        .iter()
+                        .filter_map(|p| {
+                            Some(ConstantProperty {
+                                base_property: p["base_property"].as_str()?.to_string(),
+                                conversion_property: p["conversion_property"].as_str()?.to_string(),
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+
     fn parse_measure_ref(&self, yaml: &serde_yaml::Value) -> Option<MeasureRef> {
         if yaml.is_null() {
             return None;
@@ -268,6 +476,8 @@ impl DbtSemanticLayerParser {
                 name: name.to_string(),
                 filter: None,
                 alias: None,
+                fill_nulls_with: None,
+                join_to_timespine: None,
             });
         }
 
@@ -275,6 +485,8 @@ impl DbtSemanticLayerParser {
             name: yaml["name"].as_str()?.to_string(),
             filter: yaml["filter"].as_str().map(|s| s.to_string()),
             alias: yaml["alias"].as_str().map(|s| s.to_string()),
+            fill_nulls_with: yaml["fill_nulls_with"].as_f64(),
+            join_to_timespine: yaml["join_to_timespine"].as_bool(),
         })
     }
 
@@ -286,6 +498,7 @@ impl DbtSemanticLayerParser {
                     if let Some(name) = r.as_str() {
                         return Some(MetricRef {
                             name: name.to_string(),
+                            alias: None,
                             offset_window: None,
                             offset_to_grain: None,
                         });
@@ -293,6 +506,7 @@ impl DbtSemanticLayerParser {
 
                     Some(MetricRef {
                         name: r["name"].as_str()?.to_string(),
+                        alias: r["alias"].as_str().map(|s| s.to_string()),
                         offset_window: r["offset_window"].as_str().map(|s| s.to_string()),
                         offset_to_grain: r["offset_to_grain"].as_str().map(|s| s.to_string()),
                     })
@@ -302,6 +516,35 @@ impl DbtSemanticLayerParser {
     }
 }
 
+/// Locate a named definition from `section` within its file's content
+fn locate(
+    content: &str,
+    path: &Path,
+    section: &str,
+    yaml: &serde_yaml::Value,
+) -> Option<SourceLocation> {
+    let name = yaml["name"].as_str()?;
+    let (line, column) = locate_definition(content, section, name)?;
+    Some(SourceLocation {
+        file: normalize_path(path),
+        line,
+        column,
+    })
+}
+
+/// Prefix a strict-mode error with `file:line:column`, or just the file when
+/// the definition could not be located (e.g. it has no name)
+fn describe_rejection(
+    path: &Path,
+    location: Option<SourceLocation>,
+    error: anyhow::Error,
+) -> String {
+    match location {
+        Some(loc) => format!("{}:{}:{}: {}", loc.file, loc.line, loc.column, error),
+        None => format!("{}: {}", path.display(), error),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -327,5 +570,287 @@ mod tests {
 
         assert_eq!(model.name, "orders");
         assert_eq!(model.model, "stg_orders");
+        assert_eq!(model.group, None);
+    }
+
+    #[test]
+    fn test_semantic_model_with_node_relation() {
+        let yaml_str = r#"
+        name: orders
+        node_relation:
+          alias: fct_orders
+          schema_name: analytics
+          database: warehouse
+        entities:
+          - name: order_id
+            type: primary
+        "#;
+
+        let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_str).unwrap();
+        let parser = DbtSemanticLayerParser::new("/tmp");
+        let sm = parser.parse_semantic_model(&yaml).unwrap();
+
+        assert_eq!(sm.model, "fct_orders");
+        assert_eq!(
+            sm.node_relation.as_deref(),
+            Some("warehouse.analytics.fct_orders")
+        );
+
+        let ref_yaml: serde_yaml::Value = serde_yaml::from_str(
+            "name: orders
+model: ref('fct_orders')",
+        )
+        .unwrap();
+        let sm = parser.parse_semantic_model(&ref_yaml).unwrap();
+        assert_eq!(sm.model, "fct_orders");
+        assert!(sm.node_relation.is_none());
+    }
+
+    #[test]
+    fn test_semantic_model_meta_from_config() {
+        let yaml_str = r#"
+        name: orders
+        model: ref('fct_orders')
+        meta:
+          tier: 2
+        config:
+          meta:
+            owner: finance
+        "#;
+
+        let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_str).unwrap();
+        let parser = DbtSemanticLayerParser::new("/tmp");
+        let sm = parser.parse_semantic_model(&yaml).unwrap();
+
+        assert_eq!(sm.meta.len(), 2);
+        assert_eq!(sm.meta["owner"], serde_json::json!("finance"));
+        assert_eq!(sm.meta["tier"], serde_json::json!(2));
+    }
+
+    #[test]
+    fn test_metric_group() {
+        let yaml_str = r#"
+        metrics:
+          - name: revenue
+            type: simple
+            config:
+              group: finance
+            type_params:
+              measure: order_total
+        "#;
+
+        let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_str).unwrap();
+        let parser = DbtSemanticLayerParser::new("/tmp");
+        let metric = parser.parse_metric(&yaml["metrics"][0]).unwrap();
+
+        assert_eq!(metric.group.as_deref(), Some("finance"));
+    }
+
+    #[test]
+    fn test_metric_meta_and_config() {
+        let yaml_str = r#"
+        metrics:
+          - name: revenue
+            type: simple
+            meta:
+              owner: finance-team
+              tier: 1
+            config:
+              enabled: true
+              meta:
+                tier: 2
+            type_params:
+              measure: order_total
+        "#;
+
+        let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_str).unwrap();
+        let parser = DbtSemanticLayerParser::new("/tmp");
+        let metric = parser.parse_metric(&yaml["metrics"][0]).unwrap();
+
+        assert_eq!(metric.meta["owner"], "finance-team");
+        assert_eq!(metric.meta["tier"], 2);
+        assert_eq!(metric.config.unwrap().enabled, Some(true));
+    }
+
+    #[test]
+    fn test_measure_fill_nulls_and_timespine() {
+        let yaml_str = r#"
+        name: orders
+        model: ref('fct_orders')
+        measures:
+          - name: order_total
+            agg: sum
+            fill_nulls_with: 0
+            join_to_timespine: true
+          - name: order_count
+            agg: count
+        "#;
+
+        let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_str).unwrap();
+        let parser = DbtSemanticLayerParser::new("/tmp");
+        let sm = parser.parse_semantic_model(&yaml).unwrap();
+
+        assert_eq!(sm.measures[0].fill_nulls_with, Some(0.0));
+        assert_eq!(sm.measures[0].join_to_timespine, Some(true));
+        assert_eq!(sm.measures[1].fill_nulls_with, None);
+        assert_eq!(sm.measures[1].join_to_timespine, None);
+    }
+
+    #[test]
+    fn test_measure_input_fill_nulls_and_timespine() {
+        let yaml_str = r#"
+        name: running_revenue
+        type: cumulative
+        type_params:
+          measure:
+            name: order_total
+            fill_nulls_with: 0.5
+            join_to_timespine: false
+        "#;
+
+        let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_str).unwrap();
+        let parser = DbtSemanticLayerParser::new("/tmp");
+        let metric = parser.parse_metric(&yaml).unwrap();
+
+        let measure = metric.type_params.measure.unwrap();
+        assert_eq!(measure.fill_nulls_with, Some(0.5));
+        assert_eq!(measure.join_to_timespine, Some(false));
+    }
+
+    #[test]
+    fn test_conversion_type_params() {
+        let yaml_str = r#"
+        metrics:
+          - name: visit_to_buy_rate
+            type: conversion
+            type_params:
+              conversion_type_params:
+                base_measure: visits
+                conversion_measure:
+                  name: buys
+                entity: user
+                window: 7 days
+                calculation: conversion_rate
+                constant_properties:
+                  - base_property: product_id
+                    conversion_property: product_id
+        "#;
+
+        let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_str).unwrap();
+        let parser = DbtSemanticLayerParser::new("/tmp");
+        let metric = parser.parse_metric(&yaml["metrics"][0]).unwrap();
+
+        let conversion = metric.type_params.conversion_type_params.unwrap();
+        assert_eq!(conversion.entity.as_deref(), Some("user"));
+        assert_eq!(conversion.window.as_deref(), Some("7 days"));
+        assert_eq!(conversion.base_measure.unwrap().name, "visits");
+        assert_eq!(conversion.conversion_measure.unwrap().name, "buys");
+        assert_eq!(conversion.constant_properties.len(), 1);
+    }
+
+    #[test]
+    fn test_multi_document_yaml() {
+        let project_dir =
+            std::env::temp_dir().join(format!("semantic-tracer-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(project_dir.join("models")).unwrap();
+        fs::write(
+            project_dir.join("models/semantic.yml"),
+            r#"
+metrics:
+  - name: revenue
+    type: simple
+    type_params:
+      measure: order_total
+---
+semantic_models:
+  - name: orders
+    model: ref('fct_orders')
+    measures:
+      - name: order_total
+        agg: sum
+"#,
+        )
+        .unwrap();
+
+        let result = DbtSemanticLayerParser::new(&project_dir).parse();
+        fs::remove_dir_all(&project_dir).unwrap();
+        let (semantic_models, metrics, _) = result.unwrap();
+
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(semantic_models.len(), 1);
+        assert_eq!(semantic_models[0].name, "orders");
+    }
+
+    #[test]
+    fn test_metric_source_location() {
+        let project_dir =
+            std::env::temp_dir().join(format!("semantic-tracer-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(project_dir.join("metrics")).unwrap();
+        let file = project_dir.join("metrics/revenue.yml");
+        fs::write(
+            &file,
+            r#"metrics:
+  - name: order_count
+    type: simple
+    type_params:
+      measure: orders
+  - name: revenue
+    type: simple
+    type_params:
+      measure:
+        name: revenue
+"#,
+        )
+        .unwrap();
+
+        let result = DbtSemanticLayerParser::new(&project_dir).parse();
+        fs::remove_dir_all(&project_dir).unwrap();
+        let (_, metrics, _) = result.unwrap();
+
+        let revenue = metrics.iter().find(|m| m.name == "revenue").unwrap();
+        let location = revenue.source_location.as_ref().unwrap();
+        assert_eq!(location.file, normalize_path(&file));
+        assert_eq!((location.line, location.column), (6, 3));
+    }
+
+    #[test]
+    fn test_strict_reports_missing_model() {
+        let yaml_str = r#"
+        semantic_models:
+          - name: orders
+            entities:
+              - name: order_id
+                type: primary
+        "#;
+
+        let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_str).unwrap();
+        let path = Path::new("models/orders.yml");
+        let (mut models, mut metrics, mut errors) = (Vec::new(), Vec::new(), Vec::new());
+
+        let lenient = DbtSemanticLayerParser::new("/tmp");
+        lenient.collect_definitions(
+            &yaml,
+            yaml_str,
+            path,
+            &mut models,
+            &mut metrics,
+            &mut errors,
+        );
+        assert!(models.is_empty());
+        assert!(errors.is_empty());
+
+        let strict = DbtSemanticLayerParser::new("/tmp").with_strict(true);
+        strict.collect_definitions(
+            &yaml,
+            yaml_str,
+            path,
+            &mut models,
+            &mut metrics,
+            &mut errors,
+        );
+        assert_eq!(
+            errors,
+            vec!["models/orders.yml:3:11: Semantic model 'orders' missing model reference"]
+        );
     }
 }
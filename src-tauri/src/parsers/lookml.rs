@@ -0,0 +1,327 @@
+//! Parser for Looker LookML projects (views and explores)
+
+use crate::paths::normalize_path;
+use crate::types::{LookmlExplore, LookmlField, LookmlView};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// A parsed LookML value: a scalar, a list (skipped, nothing reads one yet),
+/// or a (possibly named) block
+#[derive(Debug, Clone)]
+enum LkmlValue {
+    Scalar(String),
+    List,
+    Block {
+        name: Option<String>,
+        items: Vec<(String, LkmlValue)>,
+    },
+}
+
+impl LkmlValue {
+    fn items(&self) -> &[(String, LkmlValue)] {
+        match self {
+            LkmlValue::Block { items, .. } => items,
+            _ => &[],
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        match self {
+            LkmlValue::Block { name, .. } => name.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn scalar(&self, key: &str) -> Option<String> {
+        self.items().iter().find_map(|(k, v)| match v {
+            LkmlValue::Scalar(s) if k == key => Some(s.clone()),
+            _ => None,
+        })
+    }
+
+    fn child(&self, key: &str) -> Option<&LkmlValue> {
+        self.items().iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    fn children<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a LkmlValue> + 'a {
+        self.items()
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+}
+
+pub struct LookmlParser;
+
+impl LookmlParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse every `.view.lkml` and `.model.lkml` file under a LookML project directory
+    pub fn parse(&self, path: impl AsRef<Path>) -> Result<(Vec<LookmlView>, Vec<LookmlExplore>)> {
+        let mut views = Vec::new();
+        let mut explores = Vec::new();
+
+        for entry in WalkDir::new(path.as_ref())
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                let name = e.file_name().to_string_lossy();
+                name.ends_with(".view.lkml") || name.ends_with(".model.lkml")
+            })
+        {
+            let content = fs::read_to_string(entry.path())
+                .with_context(|| format!("Failed to read LookML file: {:?}", entry.path()))?;
+
+            let (file_views, file_explores) =
+                self.parse_str(&content, &normalize_path(entry.path()));
+            views.extend(file_views);
+            explores.extend(file_explores);
+        }
+
+        Ok((views, explores))
+    }
+
+    /// Parse the contents of a single LookML file
+    pub fn parse_str(
+        &self,
+        content: &str,
+        file_path: &str,
+    ) -> (Vec<LookmlView>, Vec<LookmlExplore>) {
+        let chars: Vec<char> = content.chars().collect();
+        let mut pos = 0;
+        let items = parse_items(&chars, &mut pos);
+
+        let views = items
+            .iter()
+            .filter(|(k, _)| k == "view")
+            .filter_map(|(_, v)| self.convert_view(v, file_path))
+            .collect();
+
+        let explores = items
+            .iter()
+            .filter(|(k, _)| k == "explore")
+            .filter_map(|(_, v)| {
+                let name = v.name()?.to_string();
+                Some(LookmlExplore {
+                    view_name: v.scalar("from").unwrap_or_else(|| name.clone()),
+                    joins: v
+                        .children("join")
+                        .filter_map(|j| j.name().map(|s| s.to_string()))
+                        .collect(),
+                    description: v.scalar("description"),
+                    name,
+                })
+            })
+            .collect();
+
+        (views, explores)
+    }
+
+    fn convert_view(&self, view: &LkmlValue, file_path: &str) -> Option<LookmlView> {
+        Some(LookmlView {
+            name: view.name()?.to_string(),
+            sql_table_name: view.scalar("sql_table_name"),
+            derived_table_sql: view.child("derived_table").and_then(|d| d.scalar("sql")),
+            dimensions: view
+                .children("dimension")
+                .chain(view.children("dimension_group"))
+                .filter_map(|d| self.convert_field(d))
+                .collect(),
+            measures: view
+                .children("measure")
+                .filter_map(|m| self.convert_field(m))
+                .collect(),
+            file_path: file_path.to_string(),
+        })
+    }
+
+    fn convert_field(&self, field: &LkmlValue) -> Option<LookmlField> {
+        Some(LookmlField {
+            name: field.name()?.to_string(),
+            field_type: field.scalar("type"),
+            sql: field.scalar("sql"),
+            label: field.scalar("label"),
+            description: field.scalar("description"),
+        })
+    }
+}
+
+impl Default for LookmlParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse `key: value` pairs until the end of input or a closing brace
+fn parse_items(chars: &[char], pos: &mut usize) -> Vec<(String, LkmlValue)> {
+    let mut items = Vec::new();
+
+    loop {
+        skip_whitespace(chars, pos);
+        if *pos >= chars.len() {
+            break;
+        }
+        if chars[*pos] == '}' {
+            *pos += 1;
+            break;
+        }
+
+        let key = read_token(chars, pos);
+        skip_whitespace(chars, pos);
+        if key.is_empty() || chars.get(*pos) != Some(&':') {
+            // Not a key we understand; skip a character and resynchronise
+            *pos += 1;
+            continue;
+        }
+        *pos += 1;
+
+        let value = parse_value(&key, chars, pos);
+        items.push((key, value));
+    }
+
+    items
+}
+
+fn parse_value(key: &str, chars: &[char], pos: &mut usize) -> LkmlValue {
+    skip_whitespace(chars, pos);
+
+    // SQL-like values run verbatim up to the `;;` terminator
+    if key.starts_with("sql") || key == "html" || key == "expression" {
+        let start = *pos;
+        while *pos < chars.len() && !(chars[*pos] == ';' && chars.get(*pos + 1) == Some(&';')) {
+            *pos += 1;
+        }
+        let value: String = chars[start..*pos].iter().collect();
+        *pos = (*pos + 2).min(chars.len());
+        return LkmlValue::Scalar(value.trim().to_string());
+    }
+
+    match chars.get(*pos) {
+        Some('{') => {
+            *pos += 1;
+            LkmlValue::Block {
+                name: None,
+                items: parse_items(chars, pos),
+            }
+        }
+        Some('[') => {
+            let mut depth = 0;
+            while *pos < chars.len() {
+                match chars[*pos] {
+                    '[' => depth += 1,
+                    ']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            *pos += 1;
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+                *pos += 1;
+            }
+            LkmlValue::List
+        }
+        Some('"') => {
+            *pos += 1;
+            let start = *pos;
+            while *pos < chars.len() && chars[*pos] != '"' {
+                if chars[*pos] == '\\' {
+                    *pos += 1;
+                }
+                *pos += 1;
+            }
+            let value: String = chars[start..(*pos).min(chars.len())].iter().collect();
+            *pos += 1;
+            LkmlValue::Scalar(value)
+        }
+        _ => {
+            let token = read_token(chars, pos);
+            skip_whitespace(chars, pos);
+            if chars.get(*pos) == Some(&'{') {
+                *pos += 1;
+                LkmlValue::Block {
+                    name: Some(token),
+                    items: parse_items(chars, pos),
+                }
+            } else {
+                LkmlValue::Scalar(token)
+            }
+        }
+    }
+}
+
+fn read_token(chars: &[char], pos: &mut usize) -> String {
+    let start = *pos;
+    while *pos < chars.len()
+        && (chars[*pos].is_alphanumeric() || matches!(chars[*pos], '_' | '.' | '-' | '+'))
+    {
+        *pos += 1;
+    }
+    chars[start..*pos].iter().collect()
+}
+
+fn skip_whitespace(chars: &[char], pos: &mut usize) {
+    while *pos < chars.len() {
+        if chars[*pos].is_whitespace() {
+            *pos += 1;
+        } else if chars[*pos] == '#' {
+            while *pos < chars.len() && chars[*pos] != '\n' {
+                *pos += 1;
+            }
+        } else {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_view() {
+        let lkml = r#"
+        # Orders view
+        view: orders {
+          sql_table_name: analytics.fct_orders ;;
+
+          dimension: order_id {
+            primary_key: yes
+            type: number
+            sql: ${TABLE}.order_id ;;
+          }
+
+          dimension_group: ordered {
+            type: time
+            timeframes: [date, week, month]
+            sql: ${TABLE}.ordered_at ;;
+          }
+
+          measure: total_revenue {
+            type: sum
+            label: "Total Revenue"
+            sql: ${TABLE}.amount ;;
+          }
+        }
+        "#;
+
+        let (views, explores) = LookmlParser::new().parse_str(lkml, "orders.view.lkml");
+
+        assert!(explores.is_empty());
+        assert_eq!(views.len(), 1);
+        let view = &views[0];
+        assert_eq!(view.name, "orders");
+        assert_eq!(view.sql_table_name.as_deref(), Some("analytics.fct_orders"));
+        assert_eq!(view.dimensions.len(), 2);
+        assert_eq!(view.dimensions[0].sql.as_deref(), Some("${TABLE}.order_id"));
+        assert_eq!(view.measures.len(), 1);
+        assert_eq!(view.measures[0].name, "total_revenue");
+        assert_eq!(view.measures[0].field_type.as_deref(), Some("sum"));
+        assert_eq!(view.measures[0].label.as_deref(), Some("Total Revenue"));
+    }
+}
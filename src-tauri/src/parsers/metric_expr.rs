@@ -0,0 +1,153 @@
+//! Parser for derived metric expressions
+//!
+//! A derived metric's `expr` is a SQL expression over its input metrics,
+//! e.g. `revenue - cost` or `coalesce(profit, 0) / nullif(orders, 0)`.
+//! Only bare identifiers are extracted; function names, SQL keywords, cast
+//! target types, `extract` fields, literals and quoted strings are skipped.
+
+use regex::Regex;
+
+/// SQL keywords that can appear in a derived expression without naming a metric
+const SQL_KEYWORDS: &[&str] = &[
+    "and",
+    "or",
+    "not",
+    "is",
+    "null",
+    "in",
+    "as",
+    "case",
+    "when",
+    "then",
+    "else",
+    "end",
+    "true",
+    "false",
+    "between",
+    "like",
+    "distinct",
+    "over",
+    "partition",
+    "by",
+    "order",
+    "asc",
+    "desc",
+    "nulls",
+    "first",
+    "last",
+    "from",
+    "cast",
+    "try_cast",
+    "interval",
+    "extract",
+    "filter",
+    "where",
+    "within",
+    "group",
+    "rows",
+    "range",
+    "unbounded",
+    "preceding",
+    "following",
+    "current",
+    "row",
+    "exists",
+    "all",
+    "any",
+    "some",
+    "ilike",
+    "similar",
+    "to",
+    "escape",
+    "at",
+    "time",
+    "zone",
+    "ignore",
+    "respect",
+];
+
+/// Extract the distinct identifiers an expression refers to, in order of first appearance
+pub fn parse_expr_identifiers(expr: &str) -> Vec<String> {
+    let string_regex = Regex::new(r#"'[^']*'|"[^"]*""#).unwrap();
+    let identifier_regex = Regex::new(r"\b([A-Za-z_][A-Za-z0-9_]*)\b(\s*\()?").unwrap();
+
+    let stripped = string_regex.replace_all(expr, " ");
+    let mut identifiers: Vec<String> = Vec::new();
+
+    for cap in identifier_regex.captures_iter(&stripped) {
+        // `name(` is a function call, not a metric
+        if cap.get(2).is_some() {
+            continue;
+        }
+        let name = &cap[1];
+        if SQL_KEYWORDS.contains(&name.to_lowercase().as_str()) {
+            continue;
+        }
+        let before = stripped[..cap.get(1).unwrap().start()].trim_end();
+        if names_type(before) || names_date_part(before) {
+            continue;
+        }
+        if !identifiers.iter().any(|i| i == name) {
+            identifiers.push(name.to_string());
+        }
+    }
+
+    identifiers
+}
+
+/// Whether the identifier after `before` is a type, as in `amount::numeric`
+/// or `cast(amount as decimal)`
+fn names_type(before: &str) -> bool {
+    before.ends_with("::") || last_word(before).eq_ignore_ascii_case("as")
+}
+
+/// Whether the identifier after `before` is the field of `extract(epoch from ...)`
+fn names_date_part(before: &str) -> bool {
+    before
+        .strip_suffix('(')
+        .is_some_and(|call| last_word(call.trim_end()).eq_ignore_ascii_case("extract"))
+}
+
+/// The identifier `text` ends with, or `""` when it ends with anything else
+fn last_word(text: &str) -> &str {
+    text.rsplit(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .next()
+        .unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skips_functions_keywords_and_literals() {
+        let identifiers = parse_expr_identifiers(
+            "case when orders > 0 then coalesce(revenue, 0) / orders else null end + 'x_label'",
+        );
+
+        assert_eq!(identifiers, vec!["orders", "revenue"]);
+    }
+
+    #[test]
+    fn test_skips_interval_and_window_keywords() {
+        let identifiers = parse_expr_identifiers(
+            "sum(revenue) filter (where is_paid) over (order by ordered_at rows between \
+             unbounded preceding and current row) - interval '1 day' + count(distinct orders)",
+        );
+
+        assert_eq!(
+            identifiers,
+            vec!["revenue", "is_paid", "ordered_at", "orders"]
+        );
+    }
+
+    #[test]
+    fn test_skips_cast_types_and_extract_fields() {
+        let identifiers = parse_expr_identifiers(
+            "cast(amount as decimal(10, 2)) + refunds::numeric \
+             + extract(epoch from shipped_at) - CAST(fee AS int)",
+        );
+
+        assert_eq!(identifiers, vec!["amount", "refunds", "shipped_at", "fee"]);
+    }
+}
@@ -0,0 +1,99 @@
+//! Parser for MetricFlow filter expressions
+//!
+//! Filters are Jinja templates such as
+//! `{{ Dimension('customer__region') }} = 'US'`. Only the semantic object
+//! references are extracted; the surrounding predicate is left as-is.
+
+use crate::types::{FilterReference, FilterReferenceKind};
+use regex::Regex;
+
+/// Extract every `Dimension`, `TimeDimension`, `Entity` and `Metric` reference
+/// from a filter string, in the order they appear
+pub fn parse_filter_references(filter: &str) -> Vec<FilterReference> {
+    let reference_regex = Regex::new(
+        r#"\b(TimeDimension|Dimension|Entity|Metric)\s*\(\s*['"]([^'"]+)['"](?:\s*,\s*['"]([^'"]+)['"])?"#,
+    )
+    .unwrap();
+
+    reference_regex
+        .captures_iter(filter)
+        .filter_map(|cap| {
+            let kind = match cap.get(1)?.as_str() {
+                "Dimension" => FilterReferenceKind::Dimension,
+                "TimeDimension" => FilterReferenceKind::TimeDimension,
+                "Entity" => FilterReferenceKind::Entity,
+                _ => FilterReferenceKind::Metric,
+            };
+            let reference = cap.get(2)?.as_str().trim();
+
+            // Dimensions are qualified by an entity path (`order__customer__region`);
+            // the last entity before the element is the one that owns it
+            let (entity, name) = match kind {
+                FilterReferenceKind::Dimension | FilterReferenceKind::TimeDimension => {
+                    match reference.rsplit_once("__") {
+                        Some((path, name)) => (
+                            path.rsplit("__").next().map(|s| s.to_string()),
+                            name.to_string(),
+                        ),
+                        None => (None, reference.to_string()),
+                    }
+                }
+                _ => (None, reference.to_string()),
+            };
+
+            let granularity = match kind {
+                FilterReferenceKind::TimeDimension => cap.get(3).map(|m| m.as_str().to_string()),
+                _ => None,
+            };
+
+            Some(FilterReference {
+                kind,
+                name,
+                entity,
+                granularity,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dimension_reference() {
+        let refs = parse_filter_references("{{ Dimension('customer__region') }} = 'US'");
+
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].kind, FilterReferenceKind::Dimension);
+        assert_eq!(refs[0].name, "region");
+        assert_eq!(refs[0].entity.as_deref(), Some("customer"));
+    }
+
+    #[test]
+    fn test_time_dimension_reference() {
+        let refs = parse_filter_references(
+            "{{ TimeDimension('order__ordered_at', 'month') }} >= '2024-01-01' \
+             AND {{ Entity('customer') }} IS NOT NULL",
+        );
+
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].kind, FilterReferenceKind::TimeDimension);
+        assert_eq!(refs[0].name, "ordered_at");
+        assert_eq!(refs[0].entity.as_deref(), Some("order"));
+        assert_eq!(refs[0].granularity.as_deref(), Some("month"));
+        assert_eq!(refs[1].kind, FilterReferenceKind::Entity);
+        assert_eq!(refs[1].name, "customer");
+    }
+
+    #[test]
+    fn test_metric_reference_with_group_by() {
+        let refs =
+            parse_filter_references("{{ Metric('order_count', group_by=['customer']) }} > 1");
+
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].kind, FilterReferenceKind::Metric);
+        assert_eq!(refs[0].name, "order_count");
+        assert_eq!(refs[0].entity, None);
+    }
+}
@@ -2,8 +2,18 @@
 
 pub mod dbt_project;
 pub mod dbt_semantic;
+pub mod lookml;
+pub mod metric_expr;
+pub mod metric_filter;
+pub mod semantic_manifest;
 pub mod snowflake;
+pub mod yaml;
 
 pub use dbt_project::DbtProjectParser;
 pub use dbt_semantic::DbtSemanticLayerParser;
+pub use lookml::LookmlParser;
+pub use metric_expr::parse_expr_identifiers;
+pub use metric_filter::parse_filter_references;
+pub use semantic_manifest::SemanticManifestParser;
 pub use snowflake::SnowflakeSemanticLayerParser;
+pub use yaml::{interpolate_env_vars, parse_yaml_documents};
@@ -0,0 +1,269 @@
+//! Parser for dbt's compiled `semantic_manifest.json`
+//!
+//! dbt writes the manifest to the target directory on `dbt parse`, with refs
+//! resolved and defaults applied. Its definitions have the same shape as the
+//! YAML ones except for filters and windows, which are normalized here before
+//! reusing the YAML parser's conversions.
+
+use crate::parsers::dbt_project::target_dir;
+use crate::parsers::DbtSemanticLayerParser;
+use crate::types::{DbtModel, DbtProject, Metric, SemanticModel};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SEMANTIC_MANIFEST_FILE_NAME: &str = "semantic_manifest.json";
+
+pub struct SemanticManifestParser {
+    project_path: PathBuf,
+}
+
+impl SemanticManifestParser {
+    pub fn new(project_path: impl AsRef<Path>) -> Self {
+        Self {
+            project_path: project_path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Read semantic models and metrics from the project's compiled manifest,
+    /// or `None` when dbt has not written one. Semantic models point at the
+    /// one of `models` their `node_relation` names, or at the relation's alias
+    /// when none matches.
+    pub fn parse(
+        &self,
+        project: &DbtProject,
+        models: &[DbtModel],
+    ) -> Result<Option<(Vec<SemanticModel>, Vec<Metric>)>> {
+        let manifest_file = target_dir(&self.project_path, project.target_path.as_deref())
+            .join(SEMANTIC_MANIFEST_FILE_NAME);
+        if !manifest_file.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&manifest_file)
+            .with_context(|| format!("Failed to read {:?}", manifest_file))?;
+        let mut manifest: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {:?} as JSON", manifest_file))?;
+        normalize(&mut manifest);
+        let manifest = serde_yaml::to_value(&manifest)?;
+
+        let yaml_parser = DbtSemanticLayerParser::new(&self.project_path);
+        let mut semantic_models = manifest["semantic_models"]
+            .as_sequence()
+            .into_iter()
+            .flatten()
+            .map(|sm| yaml_parser.parse_semantic_model(sm))
+            .collect::<Result<Vec<_>>>()?;
+        for semantic_model in &mut semantic_models {
+            if let Some(model) = relation_model(semantic_model, models) {
+                semantic_model.model = model.name.clone();
+            }
+        }
+        let metrics = manifest["metrics"]
+            .as_sequence()
+            .into_iter()
+            .flatten()
+            .map(|m| yaml_parser.parse_metric(m))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some((semantic_models, metrics)))
+    }
+}
+
+/// The model a semantic model's `node_relation` names: the one with that
+/// qualified name, else the only one whose alias (or name, without an alias)
+/// is the relation's table name
+fn relation_model<'a>(
+    semantic_model: &SemanticModel,
+    models: &'a [DbtModel],
+) -> Option<&'a DbtModel> {
+    let relation = normalize_relation(semantic_model.node_relation.as_deref()?);
+    if let Some(model) = models.iter().find(|m| {
+        m.qualified_name
+            .as_deref()
+            .is_some_and(|name| normalize_relation(name) == relation)
+    }) {
+        return Some(model);
+    }
+
+    let table = relation.rsplit('.').next()?;
+    let mut candidates = models
+        .iter()
+        .filter(|m| m.alias.as_deref().unwrap_or(&m.name).to_lowercase() == table);
+    match (candidates.next(), candidates.next()) {
+        (Some(model), None) => Some(model),
+        _ => None,
+    }
+}
+
+/// Lowercase a relation name and drop its identifier quotes, so
+/// `"WAREHOUSE"."analytics"."fct_orders"` matches `warehouse.analytics.fct_orders`
+fn normalize_relation(relation: &str) -> String {
+    relation.replace(['"', '`'], "").to_lowercase()
+}
+
+/// Rewrite manifest-only shapes into their YAML form, recursively:
+/// `{"where_filters": [{"where_sql_template": ...}]}` becomes the filters
+/// joined with `AND`, `{"count": 7, "granularity": "day"}` becomes `7 day`,
+/// and cumulative params nested under `cumulative_type_params` move up into
+/// `type_params`
+fn normalize(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(obj) => {
+            if let Some(filters) = obj.get("where_filters").and_then(|f| f.as_array()) {
+                let clauses: Vec<&str> = filters
+                    .iter()
+                    .filter_map(|f| f["where_sql_template"].as_str())
+                    .collect();
+                *value = serde_json::Value::String(clauses.join(" AND "));
+                return;
+            }
+            if let (Some(count), Some(granularity)) = (
+                obj.get("count").and_then(|c| c.as_u64()),
+                obj.get("granularity"),
+            ) {
+                if let Some(granularity) = granularity.as_str() {
+                    *value = serde_json::Value::String(format!("{} {}", count, granularity));
+                    return;
+                }
+            }
+            if let Some(serde_json::Value::Object(cumulative)) =
+                obj.remove("cumulative_type_params")
+            {
+                for (key, param) in cumulative {
+                    if obj.get(&key).map_or(true, |v| v.is_null()) {
+                        obj.insert(key, param);
+                    }
+                }
+            }
+            obj.values_mut().for_each(normalize);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(normalize),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"{
+        "semantic_models": [{
+            "name": "orders",
+            "description": "Order facts",
+            "node_relation": {
+                "alias": "fct_orders",
+                "schema_name": "analytics",
+                "database": "warehouse",
+                "relation_name": "\"warehouse\".\"analytics\".\"fct_orders\""
+            },
+            "defaults": {"agg_time_dimension": "ordered_at"},
+            "entities": [{"name": "order_id", "type": "primary", "expr": null}],
+            "measures": [{"name": "order_total", "agg": "sum", "expr": "amount"}],
+            "dimensions": [{
+                "name": "ordered_at",
+                "type": "time",
+                "type_params": {"time_granularity": "day", "validity_params": null}
+            }],
+            "config": {"meta": {"owner": "finance"}}
+        }],
+        "metrics": [{
+            "name": "large_order_total",
+            "type": "simple",
+            "type_params": {
+                "measure": {"name": "order_total", "filter": null, "alias": null}
+            },
+            "filter": {"where_filters": [
+                {"where_sql_template": "{{ Dimension('order_id__is_large') }}"}
+            ]}
+        }, {
+            "name": "weekly_order_total",
+            "type": "cumulative",
+            "type_params": {
+                "measure": {"name": "order_total", "fill_nulls_with": 0.5, "join_to_timespine": true},
+                "window": null,
+                "cumulative_type_params": {
+                    "window": {"count": 7, "granularity": "day"},
+                    "grain_to_date": null
+                }
+            }
+        }]
+    }"#;
+
+    #[test]
+    fn test_parse_semantic_manifest() {
+        let project_dir =
+            std::env::temp_dir().join(format!("semantic-tracer-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(project_dir.join("target")).unwrap();
+        fs::write(
+            project_dir.join("target").join(SEMANTIC_MANIFEST_FILE_NAME),
+            MANIFEST,
+        )
+        .unwrap();
+        let project = DbtProject {
+            name: "shop".to_string(),
+            version: None,
+            config_version: None,
+            profile: None,
+            model_paths: vec!["models".to_string()],
+            seed_paths: Vec::new(),
+            test_paths: Vec::new(),
+            analysis_paths: Vec::new(),
+            macro_paths: Vec::new(),
+            snapshot_paths: Vec::new(),
+            target_path: None,
+        };
+
+        // The model behind `fct_orders` sets it as its alias
+        let model = DbtModel {
+            unique_id: "model.orders".to_string(),
+            name: "orders".to_string(),
+            schema: Some("analytics".to_string()),
+            database: Some("warehouse".to_string()),
+            qualified_name: Some("warehouse.analytics.fct_orders".to_string()),
+            alias: Some("fct_orders".to_string()),
+            description: None,
+            columns: Vec::new(),
+            depends_on: Vec::new(),
+            refs: Vec::new(),
+            sources: Vec::new(),
+            metric_refs: Vec::new(),
+            file_path: "models/orders.sql".to_string(),
+            raw_sql: None,
+            materialization: None,
+            tags: Vec::new(),
+            access: None,
+            group: None,
+            unit_test_count: 0,
+            unique_key: Vec::new(),
+            duplicate_columns: Vec::new(),
+            source_location: None,
+            project: None,
+        };
+
+        let parser = SemanticManifestParser::new(&project_dir);
+        let (semantic_models, metrics) = parser.parse(&project, &[]).unwrap().unwrap();
+        let (resolved, _) = parser.parse(&project, &[model]).unwrap().unwrap();
+        fs::remove_dir_all(&project_dir).ok();
+
+        assert_eq!(resolved[0].model, "orders");
+
+        assert_eq!(semantic_models.len(), 1);
+        let orders = &semantic_models[0];
+        assert_eq!(orders.model, "fct_orders");
+        assert_eq!(orders.measures[0].expr.as_deref(), Some("amount"));
+        assert_eq!(orders.meta["owner"], "finance");
+
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(
+            metrics[0].filter.as_deref(),
+            Some("{{ Dimension('order_id__is_large') }}")
+        );
+        assert_eq!(metrics[0].filter_references.len(), 1);
+        assert_eq!(metrics[1].type_params.window.as_deref(), Some("7 day"));
+        let weekly_measure = metrics[1].type_params.measure.as_ref().unwrap();
+        assert_eq!(weekly_measure.name, "order_total");
+        assert_eq!(weekly_measure.fill_nulls_with, Some(0.5));
+        assert_eq!(weekly_measure.join_to_timespine, Some(true));
+    }
+}
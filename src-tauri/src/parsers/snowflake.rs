@@ -27,6 +27,26 @@ impl SnowflakeSemanticLayerParser {
         })
     }
 
+    /// Check that every metric and dimension `table` names a declared table,
+    /// returning a warning for each dangling reference
+    pub fn validate_table_refs(&self, layer: &SnowflakeSemanticLayer) -> Vec<String> {
+        let tables: Vec<&str> = layer.tables.iter().map(|t| t.name.as_str()).collect();
+
+        let metric_refs = layer.metrics.iter().map(|m| ("Metric", &m.name, &m.table));
+        let dimension_refs = layer
+            .dimensions
+            .iter()
+            .map(|d| ("Dimension", &d.name, &d.table));
+
+        metric_refs
+            .chain(dimension_refs)
+            .filter(|(_, _, table)| !tables.contains(&table.as_str()))
+            .map(|(kind, name, table)| {
+                format!("{} '{}' references undefined table '{}'", kind, name, table)
+            })
+            .collect()
+    }
+
     fn parse_tables(&self, yaml: &serde_yaml::Value) -> Vec<SnowflakeTable> {
         yaml["tables"]
             .as_sequence()
@@ -92,3 +112,49 @@ impl Default for SnowflakeSemanticLayerParser {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dangling_table_reference() {
+        let yaml: serde_yaml::Value = serde_yaml::from_str(
+            r#"
+tables:
+  - name: orders
+    database: analytics
+    schema: marts
+    table: fct_orders
+metrics:
+  - name: revenue
+    table: orders
+    expression: sum(amount)
+  - name: refunds
+    table: order
+    expression: sum(refund_amount)
+dimensions:
+  - name: region
+    table: customers
+    expression: region
+"#,
+        )
+        .unwrap();
+        let parser = SnowflakeSemanticLayerParser::new();
+        let layer = SnowflakeSemanticLayer {
+            tables: parser.parse_tables(&yaml),
+            metrics: parser.parse_metrics(&yaml),
+            dimensions: parser.parse_dimensions(&yaml),
+        };
+
+        let warnings = parser.validate_table_refs(&layer);
+
+        assert_eq!(
+            warnings,
+            vec![
+                "Metric 'refunds' references undefined table 'order'",
+                "Dimension 'region' references undefined table 'customers'",
+            ]
+        );
+    }
+}
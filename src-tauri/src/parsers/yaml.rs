@@ -0,0 +1,129 @@
+//! Shared YAML helpers
+
+use regex::Regex;
+use serde::Deserialize;
+
+/// Parse every `---`-separated document in a YAML file.
+///
+/// `serde_yaml::from_str` rejects multi-document input outright, which would
+/// drop every definition in such files.
+pub fn parse_yaml_documents(content: &str) -> Result<Vec<serde_yaml::Value>, serde_yaml::Error> {
+    serde_yaml::Deserializer::from_str(content)
+        .map(serde_yaml::Value::deserialize)
+        .collect()
+}
+
+/// Substitute `{{ env_var('NAME') }}` and `{{ env_var('NAME', 'default') }}`
+/// in every string of a YAML value from the process environment.
+///
+/// Variables that are unset and have no default are left as-is; their names
+/// are returned, each once, so the caller can report them.
+pub fn interpolate_env_vars(value: &mut serde_yaml::Value) -> Vec<String> {
+    let mut unset = Vec::new();
+    interpolate_into(value, &mut unset);
+    unset
+}
+
+fn interpolate_into(value: &mut serde_yaml::Value, unset: &mut Vec<String>) {
+    match value {
+        serde_yaml::Value::String(s) if s.contains("env_var") => {
+            *s = render_env_vars(s, unset);
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for item in items {
+                interpolate_into(item, unset);
+            }
+        }
+        serde_yaml::Value::Mapping(map) => {
+            for item in map.values_mut() {
+                interpolate_into(item, unset);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn render_env_vars(s: &str, unset: &mut Vec<String>) -> String {
+    let env_var_regex = Regex::new(
+        r#"\{\{\s*env_var\(\s*['"]([^'"]+)['"]\s*(?:,\s*['"]([^'"]*)['"]\s*)?\)\s*\}\}"#,
+    )
+    .unwrap();
+
+    env_var_regex
+        .replace_all(s, |cap: &regex::Captures| {
+            let name = &cap[1];
+            match std::env::var(name) {
+                Ok(value) => value,
+                Err(_) => match cap.get(2) {
+                    Some(default) => default.as_str().to_string(),
+                    None => {
+                        if !unset.iter().any(|u| u == name) {
+                            unset.push(name.to_string());
+                        }
+                        cap[0].to_string()
+                    }
+                },
+            }
+        })
+        .into_owned()
+}
+
+/// Find the 1-based line and column of the `name: <name>` item under a
+/// top-level `section:` key, e.g. a metric in `metrics:`.
+///
+/// The position is that of the item's `-`, which is where `serde_yaml` would
+/// report an error in that definition. Matches in any document of a
+/// multi-document file; the first one wins.
+pub fn locate_definition(content: &str, section: &str, name: &str) -> Option<(usize, usize)> {
+    let header = format!("{}:", section);
+    let mut section_indent = None;
+    let mut item_start = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+
+        if let Some(outer) = section_indent {
+            // A sibling key at or above the section's level ends it; sequence
+            // items may sit at the same indent as their key
+            if indent < outer || (indent == outer && !trimmed.starts_with('-')) {
+                section_indent = None;
+                item_start = None;
+            }
+        }
+
+        if section_indent.is_none() {
+            if trimmed.trim_end() == header {
+                section_indent = Some(indent);
+            }
+            continue;
+        }
+
+        let mut key_line = trimmed;
+        if let Some(rest) = trimmed.strip_prefix('-') {
+            match item_start {
+                Some((_, item_indent)) if indent > item_indent => {}
+                _ => {
+                    item_start = Some((index + 1, indent));
+                    key_line = rest.trim_start();
+                }
+            }
+        }
+
+        let key_value = key_line
+            .strip_prefix("name:")
+            .map(|value| value.trim().trim_matches(|c| c == '"' || c == '\''));
+        if let (Some(value), Some((item_line, item_indent))) = (key_value, item_start) {
+            let key_indent = line.len() - key_line.len();
+            // Only the item's own key counts, not a nested measure or column name
+            if value == name && key_indent <= item_indent + 2 {
+                return Some((item_line, item_indent + 1));
+            }
+        }
+    }
+
+    None
+}
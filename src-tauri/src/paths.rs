@@ -0,0 +1,69 @@
+//! Resolution of user-supplied paths and normalization of stored file paths
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Resolve a path entered by the user to an absolute, canonical path.
+///
+/// A leading `~` is expanded to the home directory, and relative paths are
+/// resolved against the current working directory. Fails if the path does
+/// not exist.
+pub fn resolve_path(raw: &str) -> Result<PathBuf> {
+    let expanded = match raw.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            let home = std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .context("Cannot expand '~': no home directory is set")?;
+            PathBuf::from(home).join(rest.trim_start_matches(['/', '\\']))
+        }
+        _ => PathBuf::from(raw),
+    };
+
+    expanded
+        .canonicalize()
+        .with_context(|| format!("Cannot resolve path '{}'", raw))
+}
+
+/// Render a path with forward slashes, dropping the `\\?\` prefix Windows
+/// adds to canonical paths, so file paths compare and display the same on
+/// every platform
+pub fn normalize_path(path: &Path) -> String {
+    let normalized = path.to_string_lossy().replace('\\', "/");
+    match normalized.strip_prefix("//?/") {
+        Some(stripped) => stripped.to_string(),
+        None => normalized,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_relative_path() {
+        let resolved = resolve_path("./src").unwrap();
+
+        assert!(resolved.is_absolute());
+        assert_eq!(
+            resolved,
+            std::env::current_dir()
+                .unwrap()
+                .join("src")
+                .canonicalize()
+                .unwrap()
+        );
+        assert!(resolve_path("./does-not-exist").is_err());
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            normalize_path(Path::new(r"models\staging\stg_orders.sql")),
+            "models/staging/stg_orders.sql"
+        );
+        assert_eq!(
+            normalize_path(Path::new(r"\\?\C:\projects\jaffle_shop")),
+            "C:/projects/jaffle_shop"
+        );
+    }
+}
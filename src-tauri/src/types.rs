@@ -12,12 +12,67 @@ pub struct ProjectConfig {
     pub dbt_project_path: String,
     pub semantic_layer_path: Option<String>,
     pub semantic_layer_type: SemanticLayerType,
+    #[serde(default)]
+    pub strict: bool,
+    pub profiles_path: Option<String>,
+    #[serde(default)]
+    pub require_metric_owner: bool,
+    pub severity_overrides: Option<HashMap<IssueType, IssueSeverity>>,
+    #[serde(default)]
+    pub use_cache: bool,
+    #[serde(default)]
+    pub prune_unreferenced: bool,
+    pub allowed_aggs: Option<Vec<String>>,
+    #[serde(default)]
+    pub deterministic_ids: bool,
+    #[serde(default)]
+    pub keep_raw_sql: bool, // keep model SQL in the result; dropped after the audit otherwise
+    pub ref_macros: Option<Vec<String>>, // macros to treat like ref(), besides ref itself
+    pub source_macros: Option<Vec<String>>, // macros to treat like source(), besides source itself
+    pub sql_extensions: Option<Vec<String>>, // model file extensions besides .sql and .sql.jinja
+    #[serde(default)]
+    pub include_tests: bool, // add dbt data tests to the lineage graph
+    #[serde(default)]
+    pub scope: Option<Scope>, // parse only part of a monorepo project
+    #[serde(default)]
+    pub completeness_policy: CompletenessPolicy,
+    #[serde(default)]
+    pub fail_on: Option<IssueSeverity>, // mark the parse failed on audit issues at or above this
+    #[serde(default)]
+    pub prefer_manifest: bool, // read target/semantic_manifest.json instead of YAML when present
+}
+
+/// What a metric's lineage needs for the metric to count as complete
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CompletenessPolicy {
+    pub require_source_path: bool,  // traces back to a source or seed
+    pub require_tests: bool,        // every upstream model has at least one test
+    pub require_descriptions: bool, // every upstream model has a description
+}
+
+impl Default for CompletenessPolicy {
+    fn default() -> Self {
+        Self {
+            require_source_path: true,
+            require_tests: false,
+            require_descriptions: false,
+        }
+    }
+}
+
+/// Restricts a parse to a subset of a project's models
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Scope {
+    Tags(Vec<String>),  // models carrying any of these tags
+    PathPrefix(String), // models under this path, relative to the project root
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SemanticLayerType {
     DbtSemanticLayer,
     Snowflake,
+    Looker,
     None,
 }
 
@@ -36,24 +91,94 @@ pub struct DbtProject {
     pub test_paths: Vec<String>,
     pub analysis_paths: Vec<String>,
     pub macro_paths: Vec<String>,
+    #[serde(default)]
+    pub snapshot_paths: Vec<String>,
     pub target_path: Option<String>,
 }
 
+/// The active target of a dbt profile, used to qualify relation names
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileTarget {
+    pub name: String,
+    pub database: Option<String>,
+    pub schema: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DbtModel {
     pub unique_id: String,
     pub name: String,
     pub schema: Option<String>,
     pub database: Option<String>,
+    pub qualified_name: Option<String>, // database.schema.name, when a profile is available
+    #[serde(default)]
+    pub alias: Option<String>, // configured relation name, used instead of `name` in the warehouse
     pub description: Option<String>,
     pub columns: Vec<DbtColumn>,
     pub depends_on: Vec<String>,
     pub refs: Vec<String>,
     pub sources: Vec<DbtSourceRef>,
+    #[serde(default)]
+    pub metric_refs: Vec<String>, // metrics queried via {{ metric('...') }}
     pub file_path: String,
     pub raw_sql: Option<String>,
     pub materialization: Option<String>,
     pub tags: Vec<String>,
+    pub access: Option<String>, // public, protected, private
+    pub group: Option<String>,
+    #[serde(default)]
+    pub unit_test_count: usize, // `unit_tests:` entries targeting this model
+    #[serde(default)]
+    pub unique_key: Vec<String>, // incremental models' unique_key columns
+    #[serde(default)]
+    pub duplicate_columns: Vec<String>, // columns listed more than once in schema.yml
+    #[serde(default)]
+    pub source_location: Option<SourceLocation>, // schema.yml entry, if any
+    #[serde(default)]
+    pub project: Option<String>, // set on results merged from several projects
+}
+
+/// Where a definition was found in a YAML file; line and column are 1-based
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbtSnapshot {
+    pub unique_id: String,
+    pub name: String,
+    pub refs: Vec<String>,
+    pub sources: Vec<DbtSourceRef>,
+    pub strategy: Option<String>, // timestamp, check
+    pub unique_key: Option<String>,
+    pub updated_at: Option<String>,
+    pub target_schema: Option<String>,
+    pub file_path: String,
+    pub raw_sql: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbtSeed {
+    pub unique_id: String,
+    pub name: String,
+    pub file_path: String,
+    pub description: Option<String>,
+    pub columns: Vec<DbtColumn>,
+}
+
+/// A dbt data test: a generic test declared in a yml file, or a singular
+/// test SQL file in a test path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbtTest {
+    pub unique_id: String,
+    pub name: String, // e.g. not_null_orders_order_id, or the singular test's file stem
+    pub test_type: String, // generic, singular
+    pub refs: Vec<String>, // models the test reads
+    pub column: Option<String>, // column a generic column test is declared on
+    pub file_path: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,11 +197,15 @@ pub struct DbtSource {
     pub name: String,
     pub schema: Option<String>,
     pub database: Option<String>,
+    pub qualified_name: Option<String>, // database.schema.name, when a profile is available
     pub description: Option<String>,
     pub columns: Vec<DbtColumn>,
     pub loader: Option<String>,
     pub freshness: Option<DbtFreshness>,
+    pub loaded_at_field: Option<String>,
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub project: Option<String>, // set on results merged from several projects
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -106,10 +235,20 @@ pub struct SemanticModel {
     pub name: String,
     pub description: Option<String>,
     pub model: String, // ref to dbt model
+    #[serde(default)]
+    pub node_relation: Option<String>, // database.schema.alias, for manifest-form definitions
+    pub group: Option<String>,
+    pub primary_entity: Option<String>,
     pub defaults: Option<SemanticModelDefaults>,
     pub entities: Vec<SemanticEntity>,
     pub measures: Vec<Measure>,
     pub dimensions: Vec<Dimension>,
+    #[serde(default)]
+    pub source_location: Option<SourceLocation>,
+    #[serde(default)]
+    pub meta: HashMap<String, serde_json::Value>, // from `meta` or `config.meta`
+    #[serde(default)]
+    pub project: Option<String>, // set on results merged from several projects
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -131,8 +270,15 @@ pub struct Measure {
     pub agg: String, // sum, count, avg, min, max, count_distinct
     pub expr: Option<String>,
     pub description: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>, // display name, preferred over `name` in the UI
     pub create_metric: Option<bool>,
     pub non_additive_dimension: Option<NonAdditiveDimension>,
+    pub agg_time_dimension: Option<String>, // overrides the semantic model default
+    #[serde(default)]
+    pub fill_nulls_with: Option<f64>, // reported instead of null for empty time buckets
+    #[serde(default)]
+    pub join_to_timespine: Option<bool>, // return every time bucket, not only those with rows
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -147,6 +293,8 @@ pub struct Dimension {
     pub dimension_type: String, // categorical, time
     pub expr: Option<String>,
     pub description: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>, // display name, preferred over `name` in the UI
     pub type_params: Option<DimensionTypeParams>,
 }
 
@@ -161,9 +309,42 @@ pub struct Metric {
     pub name: String,
     pub description: Option<String>,
     pub metric_type: String, // simple, derived, cumulative, conversion
+    pub group: Option<String>,
     pub type_params: MetricTypeParams,
     pub filter: Option<String>,
+    #[serde(default)]
+    pub filter_references: Vec<FilterReference>,
     pub label: Option<String>,
+    #[serde(default)]
+    pub meta: HashMap<String, serde_json::Value>,
+    pub config: Option<MetricConfig>,
+    #[serde(default)]
+    pub source_location: Option<SourceLocation>,
+    #[serde(default)]
+    pub project: Option<String>, // set on results merged from several projects
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricConfig {
+    pub enabled: Option<bool>,
+    pub group: Option<String>,
+}
+
+/// A semantic object referenced from a metric filter, e.g. `Dimension('customer__region')`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterReference {
+    pub kind: FilterReferenceKind,
+    pub name: String,
+    pub entity: Option<String>, // entity the dimension is qualified by
+    pub granularity: Option<String>, // TimeDimension only
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum FilterReferenceKind {
+    Dimension,
+    TimeDimension,
+    Entity,
+    Metric,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -173,6 +354,26 @@ pub struct MetricTypeParams {
     pub metrics: Option<Vec<MetricRef>>,
     pub window: Option<String>,
     pub grain_to_date: Option<String>,
+    #[serde(default)]
+    pub conversion_type_params: Option<ConversionTypeParams>,
+}
+
+/// `type_params.conversion_type_params` of a conversion metric
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionTypeParams {
+    pub base_measure: Option<MeasureRef>,
+    pub conversion_measure: Option<MeasureRef>,
+    pub entity: Option<String>,
+    pub window: Option<String>,
+    pub calculation: Option<String>, // conversion_rate, conversions
+    #[serde(default)]
+    pub constant_properties: Vec<ConstantProperty>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstantProperty {
+    pub base_property: String,
+    pub conversion_property: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -180,11 +381,16 @@ pub struct MeasureRef {
     pub name: String,
     pub filter: Option<String>,
     pub alias: Option<String>,
+    #[serde(default)]
+    pub fill_nulls_with: Option<f64>, // overrides the measure's setting for this metric
+    #[serde(default)]
+    pub join_to_timespine: Option<bool>, // overrides the measure's setting for this metric
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricRef {
     pub name: String,
+    pub alias: Option<String>,
     pub offset_window: Option<String>,
     pub offset_to_grain: Option<String>,
 }
@@ -227,6 +433,37 @@ pub struct SnowflakeDimension {
     pub dimension_type: Option<String>,
 }
 
+// =============================================================================
+// LookML Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LookmlView {
+    pub name: String,
+    pub sql_table_name: Option<String>,
+    pub derived_table_sql: Option<String>,
+    pub dimensions: Vec<LookmlField>,
+    pub measures: Vec<LookmlField>,
+    pub file_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LookmlField {
+    pub name: String,
+    pub field_type: Option<String>, // sum, count, string, time, ...
+    pub sql: Option<String>,
+    pub label: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LookmlExplore {
+    pub name: String,
+    pub view_name: String, // base view, from `from:` or the explore name
+    pub joins: Vec<String>,
+    pub description: Option<String>,
+}
+
 // =============================================================================
 // Lineage Graph Types
 // =============================================================================
@@ -239,6 +476,9 @@ pub enum LineageNodeType {
     Entity,
     Model,
     Source,
+    Snapshot,
+    Seed,
+    Test,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -250,7 +490,7 @@ pub struct LineageNode {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum LineageEdgeType {
     MetricToMeasure,
     MeasureToEntity,
@@ -259,6 +499,19 @@ pub enum LineageEdgeType {
     ModelToSource,
     DimensionToEntity,
     MetricToMetric, // for derived metrics
+    MetricToDimension, // for filters
+    MetricToEntity,    // for filters
+    MeasureToModel,    // for Looker views
+    MeasureToSource,   // for Looker views
+    DimensionToModel,  // for Looker views
+    DimensionToSource, // for Looker views
+    ModelToSnapshot,
+    SnapshotToModel,
+    SnapshotToSource,
+    ModelToMetric, // for models querying metrics via metric()
+    ModelToSeed,
+    MeasureToDimension, // to the measure's agg_time_dimension
+    TestToModel,        // labelled with the tested column, if any
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -276,6 +529,34 @@ pub struct LineageGraph {
     pub edges: Vec<LineageEdge>,
 }
 
+/// Lineage changes between two parse runs, keyed by logical `{type}.{name}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineageDiff {
+    pub added_nodes: Vec<String>,
+    pub removed_nodes: Vec<String>,
+    pub added_edges: Vec<EdgeChange>,
+    pub removed_edges: Vec<EdgeChange>,
+    pub changed_metrics: Vec<String>, // metrics whose upstream set changed
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeChange {
+    pub source: String,
+    pub target: String,
+    pub edge_type: String,
+}
+
+/// Text formats produced by the `export` command
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ExportFormat {
+    Dot,
+    GraphMl,
+    Mermaid,
+    Svg,
+    DbtDocs,
+    Sarif, // the audit issues rather than the graph
+}
+
 // =============================================================================
 // Audit Types
 // =============================================================================
@@ -284,9 +565,17 @@ pub struct LineageGraph {
 pub struct AuditResult {
     pub completeness_score: f64,
     pub documentation_coverage: f64,
+    #[serde(default)]
+    pub column_documentation_coverage: f64, // described columns across models and sources
     pub model_coverage: f64,
+    #[serde(default)]
+    pub ownership_coverage: f64,
     pub issues: Vec<AuditIssue>,
     pub summary: AuditSummary,
+    #[serde(default)]
+    pub issue_counts: HashMap<String, usize>, // keyed by issue type, e.g. "MissingDescription"
+    #[serde(default)]
+    pub severity_counts: HashMap<String, usize>, // keyed by severity, e.g. "Warning"
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -296,16 +585,19 @@ pub struct AuditIssue {
     pub message: String,
     pub node_id: Option<String>,
     pub suggestion: Option<String>,
+    #[serde(default)]
+    pub source_location: Option<SourceLocation>, // definition of the node the issue is about
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Ordered from most to least severe, so `Error < Warning`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum IssueSeverity {
     Error,
     Warning,
     Info,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum IssueType {
     MissingDescription,
     OrphanedModel,
@@ -315,6 +607,26 @@ pub enum IssueType {
     MissingMeasure,
     UndocumentedColumn,
     NoTests,
+    UnusedColumn,
+    MissingOwner,
+    MissingMetricRef,
+    InvalidNonAdditiveDimension,
+    MissingModelRef,
+    DuplicateName,
+    GrainMismatch,
+    AccessViolation,
+    InvalidFreshness,
+    InvalidAggregation,
+    DisallowedAggregation,
+    MissingDimension,
+    IncrementalWithoutUniqueKey,
+    UnusedMeasure,
+    ManyToManyJoin,
+    DuplicateDefinition,
+    InvalidCumulativeParams,
+    DeprecatedMetricUsage,
+    EntityExprMismatch,
+    MissingTimeSpineJoin,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -327,6 +639,106 @@ pub struct AuditSummary {
     pub documented_models: usize,
     pub tested_models: usize,
     pub orphaned_models: usize,
+    #[serde(default)]
+    pub owned_metrics: usize,
+    #[serde(default)]
+    pub metrics_by_type: HashMap<String, usize>,
+    #[serde(default)]
+    pub measures_by_agg: HashMap<String, usize>,
+}
+
+/// How much of a source's column surface feeds at least one metric
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceUtilization {
+    pub source_name: String,
+    pub total_columns: usize,
+    pub used_columns: usize,
+    pub utilization: f64, // fraction of columns used, 0.0 - 1.0
+    pub unused_columns: Vec<String>,
+    pub column_level: bool, // false when approximated from model-level lineage
+}
+
+/// Where data enters and leaves the lineage graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEndpoints {
+    pub roots: Vec<String>,  // sources and seeds with no upstream
+    pub leaves: Vec<String>, // metrics nothing depends on
+}
+
+/// Overall shape of the lineage graph, for a health dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphStats {
+    pub node_count: usize,
+    pub edge_count: usize,
+    pub component_count: usize,      // weakly connected components
+    pub longest_path: Option<usize>, // in edges; None when the graph has a cycle
+    pub has_cycle: bool,
+    pub average_fan_out: f64,                  // edges per node
+    pub nodes_by_type: HashMap<String, usize>, // keyed by node type, e.g. "Model"
+}
+
+/// Outcome of a structural validation run without building the lineage graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub passed: bool, // false when any issue has Error severity
+    pub issues: Vec<AuditIssue>,
+}
+
+/// Whether a metric traces back to a source, and where its chain breaks if not
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricCompleteness {
+    pub metric_name: String,
+    pub has_source_path: bool,
+    pub missing_link: Option<String>, // first missing link, e.g. "measure not found"
+}
+
+/// Metrics that reference a measure; single-use measures are inlining candidates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeasureUsage {
+    pub measure_name: String,
+    pub semantic_model: Option<String>,
+    pub metrics: Vec<String>,
+    pub usage_count: usize,
+}
+
+/// The physical columns a measure reads, in its semantic model's dbt model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeasureColumn {
+    pub measure_name: String,
+    pub semantic_model: String,
+    pub model_name: String,
+    pub columns: Vec<String>, // best effort for expressions; empty for constants like `1`
+}
+
+/// How much a metric depends on and how much depends on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlastRadius {
+    pub metric_name: String,
+    #[serde(default)]
+    pub project: Option<String>, // the metric's project, in results merged from several
+    pub upstream_count: usize, // models, snapshots, seeds and sources the metric reads from
+    pub downstream_consumer_count: usize, // nodes that depend on the metric, directly or not
+    pub score: usize,          // upstream_count + downstream_consumer_count
+}
+
+/// Metrics whose names differ only in word order or case, e.g.
+/// `total_revenue` and `revenue_total`; advisory only
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidationHint {
+    pub normalized_name: String, // sorted lowercase name tokens joined by `_`
+    pub metrics: Vec<String>,
+    pub shared_measure: Option<String>, // measure every metric in the cluster aggregates, if they agree
+}
+
+/// A node in a metric's upstream dependency tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricTreeNode {
+    pub id: String,
+    pub name: String,
+    pub node_type: LineageNodeType,
+    #[serde(default)]
+    pub is_ref: bool, // already expanded elsewhere in the tree, so left without children
+    pub children: Vec<MetricTreeNode>,
 }
 
 // =============================================================================
@@ -339,12 +751,31 @@ pub struct ParseResult {
     pub dbt_project: Option<DbtProject>,
     pub models: Vec<DbtModel>,
     pub sources: Vec<DbtSource>,
+    #[serde(default)]
+    pub snapshots: Vec<DbtSnapshot>,
+    #[serde(default)]
+    pub seeds: Vec<DbtSeed>,
+    #[serde(default)]
+    pub tests: Vec<DbtTest>, // only parsed when `include_tests` is set
     pub semantic_models: Vec<SemanticModel>,
     pub metrics: Vec<Metric>,
+    #[serde(default)]
+    pub lookml_views: Vec<LookmlView>,
+    #[serde(default)]
+    pub lookml_explores: Vec<LookmlExplore>,
     pub lineage: LineageGraph,
     pub audit: AuditResult,
     pub errors: Vec<String>,
     pub warnings: Vec<String>,
+    #[serde(default)]
+    pub from_cache: bool,
+}
+
+/// Payload of the `parse-progress` event emitted while a project is parsed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseProgress {
+    pub phase: String,
+    pub count: usize,
 }
 
 impl Default for ParseResult {
@@ -354,8 +785,13 @@ impl Default for ParseResult {
             dbt_project: None,
             models: Vec::new(),
             sources: Vec::new(),
+            snapshots: Vec::new(),
+            seeds: Vec::new(),
+            tests: Vec::new(),
             semantic_models: Vec::new(),
             metrics: Vec::new(),
+            lookml_views: Vec::new(),
+            lookml_explores: Vec::new(),
             lineage: LineageGraph {
                 nodes: Vec::new(),
                 edges: Vec::new(),
@@ -363,8 +799,12 @@ impl Default for ParseResult {
             audit: AuditResult {
                 completeness_score: 0.0,
                 documentation_coverage: 0.0,
+                column_documentation_coverage: 0.0,
                 model_coverage: 0.0,
+                ownership_coverage: 0.0,
                 issues: Vec::new(),
+                issue_counts: HashMap::new(),
+                severity_counts: HashMap::new(),
                 summary: AuditSummary {
                     total_metrics: 0,
                     total_measures: 0,
@@ -374,10 +814,14 @@ impl Default for ParseResult {
                     documented_models: 0,
                     tested_models: 0,
                     orphaned_models: 0,
+                    owned_metrics: 0,
+                    metrics_by_type: HashMap::new(),
+                    measures_by_agg: HashMap::new(),
                 },
             },
             errors: Vec::new(),
             warnings: Vec::new(),
+            from_cache: false,
         }
     }
 }
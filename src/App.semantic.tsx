@@ -12,7 +12,7 @@ import { SearchResults } from './components/Search/SearchResults';
 import { useLineageState } from './hooks/useLineageState';
 import { parseProject, getMetricLineage, getImpactAnalysis } from './services/tauri';
 import { exportToPng, exportAuditJson, exportLineageJson, copyAsMermaid } from './services/export';
-import type { ProjectConfig, LineageNode, AuditIssue } from './types/semantic';
+import type { ProjectConfig, LineageNode, AuditIssue, CommandError } from './types/semantic';
 
 type ViewMode = 'full' | 'metric' | 'impact';
 type SidebarMode = 'catalog' | 'audit' | 'detail' | 'none';
@@ -52,7 +52,7 @@ function App() {
         setParseResult(result);
         setSidebarMode('catalog');
       } catch (err) {
-        setError(err instanceof Error ? err.message : 'Failed to parse project');
+        setError((err as CommandError)?.message ?? 'Failed to parse project');
       } finally {
         setLoading(false);
       }
@@ -322,9 +322,14 @@ function App() {
                   Export Lineage (JSON)
                 </button>
                 <button
-                  onClick={() => {
-                    copyAsMermaid(parseResult);
-                    setShowExportMenu(false);
+                  onClick={async () => {
+                    try {
+                      await copyAsMermaid(parseResult);
+                    } catch {
+                      // Already logged by the export service
+                    } finally {
+                      setShowExportMenu(false);
+                    }
                   }}
                   className="w-full px-4 py-2 text-sm text-left hover:bg-gray-50"
                 >
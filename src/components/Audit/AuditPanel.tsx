@@ -56,7 +56,7 @@ export function AuditPanel({ audit, onIssueClick }: AuditPanelProps) {
 
           <div>
             <div className="flex justify-between text-sm mb-1">
-              <span>Documentation</span>
+              <span>Node Documentation</span>
               <span className={getScoreColor(audit.documentation_coverage)}>
                 {audit.documentation_coverage.toFixed(1)}%
               </span>
@@ -69,6 +69,21 @@ export function AuditPanel({ audit, onIssueClick }: AuditPanelProps) {
             </div>
           </div>
 
+          <div>
+            <div className="flex justify-between text-sm mb-1">
+              <span>Column Documentation</span>
+              <span className={getScoreColor(audit.column_documentation_coverage)}>
+                {audit.column_documentation_coverage.toFixed(1)}%
+              </span>
+            </div>
+            <div className="w-full bg-gray-200 rounded-full h-2">
+              <div
+                className="bg-sky-500 h-2 rounded-full"
+                style={{ width: `${audit.column_documentation_coverage}%` }}
+              />
+            </div>
+          </div>
+
           <div>
             <div className="flex justify-between text-sm mb-1">
               <span>Model Coverage</span>
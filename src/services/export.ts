@@ -4,6 +4,7 @@
 
 import { toPng, toJpeg } from 'html-to-image';
 import type { ParseResult } from '../types/semantic';
+import { exportParseResult } from './tauri';
 
 /**
  * Export the current lineage canvas to PNG
@@ -125,28 +126,16 @@ export function exportLineageJson(parseResult: ParseResult): void {
 /**
  * Copy lineage as Mermaid diagram syntax
  */
-export function copyAsMermaid(parseResult: ParseResult): string {
-  let mermaid = 'graph TD\n';
-
-  // Add nodes
-  parseResult.lineage.nodes.forEach((node) => {
-    const nodeId = node.id.replace(/[^a-zA-Z0-9]/g, '_');
-    const label = node.name.replace(/"/g, '\\"');
-    mermaid += `  ${nodeId}["${label}"]\n`;
-  });
-
-  mermaid += '\n';
-
-  // Add edges
-  parseResult.lineage.edges.forEach((edge) => {
-    const sourceId = edge.source.replace(/[^a-zA-Z0-9]/g, '_');
-    const targetId = edge.target.replace(/[^a-zA-Z0-9]/g, '_');
-    const label = edge.label ? `|${edge.label}|` : '';
-    mermaid += `  ${sourceId} --${label}--> ${targetId}\n`;
-  });
-
-  // Copy to clipboard
-  navigator.clipboard.writeText(mermaid);
-
-  return mermaid;
+export async function copyAsMermaid(parseResult: ParseResult): Promise<string> {
+  try {
+    const mermaid = await exportParseResult(parseResult, 'Mermaid');
+
+    // Copy to clipboard
+    await navigator.clipboard.writeText(mermaid);
+
+    return mermaid;
+  } catch (error) {
+    console.error('Failed to copy Mermaid diagram:', error);
+    throw error;
+  }
 }
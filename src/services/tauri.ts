@@ -8,6 +8,7 @@ import type {
   ParseResult,
   ProjectConfig,
   LineageNode,
+  ExportFormat,
 } from '../types/semantic';
 
 /**
@@ -78,14 +79,33 @@ export async function getImpactAnalysis(
 }
 
 /**
- * Search for nodes by name or description
+ * Search for nodes by name or description, optionally tolerating typos
  */
 export async function searchNodes(
   parseResult: ParseResult,
-  query: string
+  query: string,
+  fuzzy = false,
+  threshold?: number
 ): Promise<LineageNode[]> {
   return invoke<LineageNode[]>('search_nodes', {
     parseResult,
     query,
+    fuzzy,
+    threshold,
+  });
+}
+
+/**
+ * Export a parse result, or a subgraph of one, in the given text format
+ */
+export async function exportParseResult(
+  parseResult: ParseResult,
+  format: ExportFormat,
+  projectPath?: string
+): Promise<string> {
+  return invoke<string>('export', {
+    parseResult,
+    format,
+    projectPath,
   });
 }
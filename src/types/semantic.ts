@@ -11,9 +11,36 @@ export interface ProjectConfig {
   dbt_project_path: string;
   semantic_layer_path?: string;
   semantic_layer_type: SemanticLayerType;
+  strict?: boolean;
+  profiles_path?: string;
+  require_metric_owner?: boolean;
+  severity_overrides?: Partial<Record<IssueType, IssueSeverity>>;
+  use_cache?: boolean;
+  prune_unreferenced?: boolean;
+  allowed_aggs?: string[];
+  deterministic_ids?: boolean;
+  keep_raw_sql?: boolean;
+  ref_macros?: string[];
+  source_macros?: string[];
+  sql_extensions?: string[];
+  include_tests?: boolean;
+  scope?: Scope;
+  completeness_policy?: CompletenessPolicy;
+  fail_on?: IssueSeverity;
+  prefer_manifest?: boolean;
 }
 
-export type SemanticLayerType = 'DbtSemanticLayer' | 'Snowflake' | 'None';
+/** What a metric's lineage needs for the metric to count as complete */
+export interface CompletenessPolicy {
+  require_source_path: boolean;
+  require_tests: boolean;
+  require_descriptions: boolean;
+}
+
+/** Restricts a parse to a subset of a project's models */
+export type Scope = { Tags: string[] } | { PathPrefix: string };
+
+export type SemanticLayerType = 'DbtSemanticLayer' | 'Snowflake' | 'Looker' | 'None';
 
 // =============================================================================
 // dbt Project Types
@@ -29,6 +56,7 @@ export interface DbtProject {
   test_paths: string[];
   analysis_paths: string[];
   macro_paths: string[];
+  snapshot_paths: string[];
   target_path?: string;
 }
 
@@ -37,15 +65,63 @@ export interface DbtModel {
   name: string;
   schema?: string;
   database?: string;
+  qualified_name?: string;
+  alias?: string;
   description?: string;
   columns: DbtColumn[];
   depends_on: string[];
   refs: string[];
   sources: DbtSourceRef[];
+  metric_refs: string[];
   file_path: string;
   raw_sql?: string;
   materialization?: string;
   tags: string[];
+  access?: string;
+  group?: string;
+  unit_test_count: number;
+  unique_key: string[];
+  duplicate_columns: string[];
+  source_location?: SourceLocation;
+  project?: string;
+}
+
+/** Where a definition was found in a YAML file; line and column are 1-based */
+export interface SourceLocation {
+  file: string;
+  line: number;
+  column: number;
+}
+
+export interface DbtSnapshot {
+  unique_id: string;
+  name: string;
+  refs: string[];
+  sources: DbtSourceRef[];
+  strategy?: string;
+  unique_key?: string;
+  updated_at?: string;
+  target_schema?: string;
+  file_path: string;
+  raw_sql?: string;
+}
+
+export interface DbtSeed {
+  unique_id: string;
+  name: string;
+  file_path: string;
+  description?: string;
+  columns: DbtColumn[];
+}
+
+/** A generic test declared in a yml file, or a singular test SQL file */
+export interface DbtTest {
+  unique_id: string;
+  name: string;
+  test_type: string;
+  refs: string[];
+  column?: string;
+  file_path: string;
 }
 
 export interface DbtColumn {
@@ -62,11 +138,14 @@ export interface DbtSource {
   name: string;
   schema?: string;
   database?: string;
+  qualified_name?: string;
   description?: string;
   columns: DbtColumn[];
   loader?: string;
   freshness?: DbtFreshness;
+  loaded_at_field?: string;
   tags: string[];
+  project?: string;
 }
 
 export interface DbtSourceRef {
@@ -92,10 +171,16 @@ export interface SemanticModel {
   name: string;
   description?: string;
   model: string;
+  node_relation?: string;
+  group?: string;
+  primary_entity?: string;
   defaults?: SemanticModelDefaults;
   entities: SemanticEntity[];
   measures: Measure[];
   dimensions: Dimension[];
+  source_location?: SourceLocation;
+  meta: Record<string, unknown>;
+  project?: string;
 }
 
 export interface SemanticModelDefaults {
@@ -114,8 +199,12 @@ export interface Measure {
   agg: string;
   expr?: string;
   description?: string;
+  label?: string;
   create_metric?: boolean;
   non_additive_dimension?: NonAdditiveDimension;
+  agg_time_dimension?: string;
+  fill_nulls_with?: number;
+  join_to_timespine?: boolean;
 }
 
 export interface NonAdditiveDimension {
@@ -128,6 +217,7 @@ export interface Dimension {
   dimension_type: string;
   expr?: string;
   description?: string;
+  label?: string;
   type_params?: DimensionTypeParams;
 }
 
@@ -140,31 +230,98 @@ export interface Metric {
   name: string;
   description?: string;
   metric_type: string;
+  group?: string;
   type_params: MetricTypeParams;
   filter?: string;
+  filter_references: FilterReference[];
   label?: string;
+  meta: Record<string, unknown>;
+  config?: MetricConfig;
+  source_location?: SourceLocation;
+  project?: string;
+}
+
+export interface MetricConfig {
+  enabled?: boolean;
+  group?: string;
 }
 
+/** A semantic object referenced from a metric filter, e.g. `Dimension('customer__region')` */
+export interface FilterReference {
+  kind: FilterReferenceKind;
+  name: string;
+  entity?: string;
+  granularity?: string;
+}
+
+export type FilterReferenceKind = 'Dimension' | 'TimeDimension' | 'Entity' | 'Metric';
+
 export interface MetricTypeParams {
   measure?: MeasureRef;
   expr?: string;
   metrics?: MetricRef[];
   window?: string;
   grain_to_date?: string;
+  conversion_type_params?: ConversionTypeParams;
+}
+
+export interface ConversionTypeParams {
+  base_measure?: MeasureRef;
+  conversion_measure?: MeasureRef;
+  entity?: string;
+  window?: string;
+  calculation?: string;
+  constant_properties: ConstantProperty[];
+}
+
+export interface ConstantProperty {
+  base_property: string;
+  conversion_property: string;
 }
 
 export interface MeasureRef {
   name: string;
   filter?: string;
   alias?: string;
+  fill_nulls_with?: number;
+  join_to_timespine?: boolean;
 }
 
 export interface MetricRef {
   name: string;
+  alias?: string;
   offset_window?: string;
   offset_to_grain?: string;
 }
 
+// =============================================================================
+// LookML Types
+// =============================================================================
+
+export interface LookmlView {
+  name: string;
+  sql_table_name?: string;
+  derived_table_sql?: string;
+  dimensions: LookmlField[];
+  measures: LookmlField[];
+  file_path: string;
+}
+
+export interface LookmlField {
+  name: string;
+  field_type?: string;
+  sql?: string;
+  label?: string;
+  description?: string;
+}
+
+export interface LookmlExplore {
+  name: string;
+  view_name: string;
+  joins: string[];
+  description?: string;
+}
+
 // =============================================================================
 // Lineage Graph Types
 // =============================================================================
@@ -175,7 +332,10 @@ export type LineageNodeType =
   | 'Dimension'
   | 'Entity'
   | 'Model'
-  | 'Source';
+  | 'Source'
+  | 'Snapshot'
+  | 'Seed'
+  | 'Test';
 
 export interface LineageNode {
   id: string;
@@ -192,7 +352,20 @@ export type LineageEdgeType =
   | 'ModelToModel'
   | 'ModelToSource'
   | 'DimensionToEntity'
-  | 'MetricToMetric';
+  | 'MetricToMetric'
+  | 'MetricToDimension'
+  | 'MetricToEntity'
+  | 'MeasureToModel'
+  | 'MeasureToSource'
+  | 'DimensionToModel'
+  | 'DimensionToSource'
+  | 'ModelToSnapshot'
+  | 'SnapshotToModel'
+  | 'SnapshotToSource'
+  | 'ModelToMetric'
+  | 'ModelToSeed'
+  | 'MeasureToDimension'
+  | 'TestToModel';
 
 export interface LineageEdge {
   id: string;
@@ -207,6 +380,9 @@ export interface LineageGraph {
   edges: LineageEdge[];
 }
 
+/** Text formats produced by the `export` command */
+export type ExportFormat = 'Dot' | 'GraphMl' | 'Mermaid' | 'Svg' | 'DbtDocs' | 'Sarif';
+
 // =============================================================================
 // Audit Types
 // =============================================================================
@@ -214,9 +390,13 @@ export interface LineageGraph {
 export interface AuditResult {
   completeness_score: number;
   documentation_coverage: number;
+  column_documentation_coverage: number;
   model_coverage: number;
+  ownership_coverage: number;
   issues: AuditIssue[];
   summary: AuditSummary;
+  issue_counts: Partial<Record<IssueType, number>>;
+  severity_counts: Partial<Record<IssueSeverity, number>>;
 }
 
 export interface AuditIssue {
@@ -225,6 +405,7 @@ export interface AuditIssue {
   message: string;
   node_id?: string;
   suggestion?: string;
+  source_location?: SourceLocation;
 }
 
 export type IssueSeverity = 'Error' | 'Warning' | 'Info';
@@ -237,7 +418,27 @@ export type IssueType =
   | 'CircularDependency'
   | 'MissingMeasure'
   | 'UndocumentedColumn'
-  | 'NoTests';
+  | 'NoTests'
+  | 'UnusedColumn'
+  | 'MissingOwner'
+  | 'MissingMetricRef'
+  | 'InvalidNonAdditiveDimension'
+  | 'MissingModelRef'
+  | 'DuplicateName'
+  | 'GrainMismatch'
+  | 'AccessViolation'
+  | 'InvalidFreshness'
+  | 'InvalidAggregation'
+  | 'DisallowedAggregation'
+  | 'MissingDimension'
+  | 'IncrementalWithoutUniqueKey'
+  | 'UnusedMeasure'
+  | 'ManyToManyJoin'
+  | 'DuplicateDefinition'
+  | 'InvalidCumulativeParams'
+  | 'DeprecatedMetricUsage'
+  | 'EntityExprMismatch'
+  | 'MissingTimeSpineJoin';
 
 export interface AuditSummary {
   total_metrics: number;
@@ -248,6 +449,9 @@ export interface AuditSummary {
   documented_models: number;
   tested_models: number;
   orphaned_models: number;
+  owned_metrics: number;
+  metrics_by_type: Record<string, number>;
+  measures_by_agg: Record<string, number>;
 }
 
 // =============================================================================
@@ -259,12 +463,36 @@ export interface ParseResult {
   dbt_project?: DbtProject;
   models: DbtModel[];
   sources: DbtSource[];
+  snapshots: DbtSnapshot[];
+  seeds: DbtSeed[];
+  tests: DbtTest[];
   semantic_models: SemanticModel[];
   metrics: Metric[];
+  lookml_views: LookmlView[];
+  lookml_explores: LookmlExplore[];
   lineage: LineageGraph;
   audit: AuditResult;
   errors: string[];
   warnings: string[];
+  from_cache: boolean;
+}
+
+/** Payload of the `parse-progress` event emitted while a project is parsed */
+export interface ParseProgress {
+  phase: string;
+  count: number;
+}
+
+/** Error returned by a failed backend command */
+export interface CommandError {
+  kind:
+    | 'PathNotFound'
+    | 'ParseFailed'
+    | 'NodeNotFound'
+    | 'AmbiguousNode'
+    | 'CycleDetected'
+    | 'Internal';
+  message: string;
 }
 
 // =============================================================================